@@ -0,0 +1,38 @@
+#![feature(test)]
+
+
+extern crate test;
+extern crate float;
+
+
+use test::{Bencher, black_box};
+
+use float::polynomial;
+
+
+const COEFFS: [f64; 16] = [
+    1.0, -0.5, 0.25, -0.125, 0.0625, -0.03125, 0.015625, -0.0078125,
+    1.0, -0.5, 0.25, -0.125, 0.0625, -0.03125, 0.015625, -0.0078125,
+];
+
+
+#[bench]
+fn bench_horner_4(b: &mut Bencher) {
+    b.iter(|| polynomial::eval(black_box(0.75_f64), &COEFFS[..4]));
+}
+#[bench]
+fn bench_estrin_4(b: &mut Bencher) {
+    b.iter(|| polynomial::eval_estrin(black_box(0.75_f64), &COEFFS[..4]));
+}
+#[bench]
+fn bench_horner_16(b: &mut Bencher) {
+    b.iter(|| polynomial::eval(black_box(0.75_f64), &COEFFS));
+}
+#[bench]
+fn bench_estrin_16(b: &mut Bencher) {
+    b.iter(|| polynomial::eval_estrin(black_box(0.75_f64), &COEFFS));
+}
+#[bench]
+fn bench_auto_16(b: &mut Bencher) {
+    b.iter(|| polynomial::eval_auto(black_box(0.75_f64), &COEFFS));
+}
@@ -0,0 +1,61 @@
+"""Lists the imports of a wasm module and fails if there are any.
+
+A C math function the target does not define links as an import from the
+host environment and only fails when the module is instantiated.
+"""
+
+import sys
+
+
+def leb128(data, offset):
+    value = shift = 0
+    while True:
+        byte = data[offset]
+        offset += 1
+        value |= (byte & 0x7f) << shift
+        shift += 7
+        if byte < 0x80:
+            return value, offset
+
+
+def name(data, offset):
+    length, offset = leb128(data, offset)
+    return data[offset:offset + length].decode(), offset + length
+
+
+def imports(data):
+    assert data[:4] == b"\0asm", "not a wasm module"
+    offset = 8
+    while offset < len(data):
+        section = data[offset]
+        size, offset = leb128(data, offset + 1)
+        end = offset + size
+        if section == 2:
+            count, offset = leb128(data, offset)
+            for _ in range(count):
+                module, offset = name(data, offset)
+                field, offset = name(data, offset)
+                yield module, field
+                kind = data[offset]
+                offset += 1
+                if kind == 0:
+                    _, offset = leb128(data, offset)
+                elif kind == 1:
+                    flags, offset = leb128(data, offset + 1)
+                    _, offset = leb128(data, offset)
+                    if flags & 1:
+                        _, offset = leb128(data, offset)
+                elif kind == 2:
+                    flags, offset = leb128(data, offset)
+                    _, offset = leb128(data, offset)
+                    if flags & 1:
+                        _, offset = leb128(data, offset)
+                else:
+                    offset += 2
+        offset = end
+
+
+found = list(imports(open(sys.argv[1], "rb").read()))
+for module, field in found:
+    print("imports {}.{}".format(module, field))
+sys.exit(1 if found else 0)
@@ -0,0 +1,61 @@
+//! Calls every math function of `Float` from exported functions, so that
+//! built as a wasm module its imports name any C math symbol the target does
+//! not define. CI builds it for wasm32-unknown-unknown and checks that it
+//! imports nothing:
+//!
+//! ```sh
+//! cargo build --release --target wasm32-unknown-unknown --example wasm_link
+//! python3 ci/wasm_imports.py target/wasm32-unknown-unknown/release/examples/wasm_link.wasm
+//! ```
+
+extern crate float;
+
+
+use float::Float;
+
+
+#[no_mangle]
+pub extern "C" fn float_link_f32(x: f32, y: f32) -> f32 {
+    let unary: [fn(&f32) -> f32; 34] = [
+        Float::trunc, Float::floor, Float::ceil, Float::round,
+        Float::round_ties_even, Float::fract, Float::recip, Float::exp,
+        Float::exp2, Float::ln, Float::log2, Float::log10,
+        Float::sqrt, Float::cbrt, Float::to_degrees, Float::to_radians,
+        Float::sin, Float::cos, Float::tan, Float::asin,
+        Float::acos, Float::atan, Float::tanpi, Float::atanpi,
+        Float::asinpi, Float::acospi, Float::sinh, Float::cosh,
+        Float::tanh, Float::asinh, Float::acosh, Float::atanh,
+        Float::exp_m1, Float::ln_1p,
+    ];
+    let binary: [fn(&f32, &f32) -> f32; 7] = [
+        Float::copysign, Float::powf, Float::log, Float::hypot,
+        Float::atan2, Float::ln_add_exp, Float::ln_sub_exp,
+    ];
+
+    let sum = unary.iter().fold(Float::mul_add(&x, &y, &x), |sum, f| sum + f(&x));
+    let sum = sum + Float::powi(&x, 3) + Float::rootn(&x, 3) + Float::sin_cos(&x).0;
+    binary.iter().fold(sum, |sum, f| sum + f(&x, &y))
+}
+
+#[no_mangle]
+pub extern "C" fn float_link_f64(x: f64, y: f64) -> f64 {
+    let unary: [fn(&f64) -> f64; 34] = [
+        Float::trunc, Float::floor, Float::ceil, Float::round,
+        Float::round_ties_even, Float::fract, Float::recip, Float::exp,
+        Float::exp2, Float::ln, Float::log2, Float::log10,
+        Float::sqrt, Float::cbrt, Float::to_degrees, Float::to_radians,
+        Float::sin, Float::cos, Float::tan, Float::asin,
+        Float::acos, Float::atan, Float::tanpi, Float::atanpi,
+        Float::asinpi, Float::acospi, Float::sinh, Float::cosh,
+        Float::tanh, Float::asinh, Float::acosh, Float::atanh,
+        Float::exp_m1, Float::ln_1p,
+    ];
+    let binary: [fn(&f64, &f64) -> f64; 7] = [
+        Float::copysign, Float::powf, Float::log, Float::hypot,
+        Float::atan2, Float::ln_add_exp, Float::ln_sub_exp,
+    ];
+
+    let sum = unary.iter().fold(Float::mul_add(&x, &y, &x), |sum, f| sum + f(&x));
+    let sum = sum + Float::powi(&x, 3) + Float::rootn(&x, 3) + Float::sin_cos(&x).0;
+    binary.iter().fold(sum, |sum, f| sum + f(&x, &y))
+}
@@ -0,0 +1,56 @@
+//! Every finite or infinite float must survive `parse(format(x))` bit for bit
+//! in each of the formats core provides, and every NaN must come back as a
+//! NaN with the same canonical checksum.
+
+#![no_main]
+
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate float;
+
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use float::checksum_canonical;
+
+
+fuzz_target!(|data: &[u8]| {
+    for chunk in data.chunks(8) {
+        let mut bytes = [0_u8; 8];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        let bits = u64::from_le_bytes(bytes);
+
+        check(f64::from_bits(bits), |x| x.to_bits() as u64);
+        check(f32::from_bits(bits as u32), |x| x.to_bits() as u64);
+    }
+
+    if let Ok(text) = std::str::from_utf8(data) {
+        // parsing arbitrary text must never panic, and whatever parses must
+        // format back to the same value
+        if let Ok(x) = text.parse::<f64>() {
+            check(x, |x| x.to_bits());
+        }
+        if let Ok(x) = text.parse::<f32>() {
+            check(x, |x| x.to_bits() as u64);
+        }
+    }
+});
+
+fn check<T, F>(x: T, bits: F)
+    where T: float::Float + Display + std::fmt::Debug + std::fmt::LowerExp + FromStr,
+          <T as FromStr>::Err: std::fmt::Debug,
+          F: Fn(T) -> u64,
+{
+    let texts = [format!("{}", x), format!("{:?}", x), format!("{:e}", x)];
+
+    for text in texts.iter() {
+        let y: T = text.parse().expect("formatted float does not parse");
+        if x.is_nan() {
+            assert!(y.is_nan(), "{} parsed to {:?}", text, y);
+            assert_eq!(checksum_canonical(&[x]), checksum_canonical(&[y]));
+        } else {
+            assert_eq!(bits(x), bits(y), "{} parsed to {:?}", text, y);
+        }
+    }
+}
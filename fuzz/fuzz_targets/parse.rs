@@ -0,0 +1,56 @@
+//! `parse_float` must not panic on any text in any configuration, must agree
+//! with `str::parse` where it rewrites nothing, and must return the same bits
+//! for a spelling and the standard text it stands for.
+
+#![no_main]
+
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate float;
+
+
+use float::parse::{self, ParseConfig};
+
+
+fuzz_target!(|data: &[u8]| {
+    let text = match std::str::from_utf8(data) {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+
+    for &fortran_exponent in &[false, true] {
+        for &decimal_comma in &[false, true] {
+            for &underscores in &[false, true] {
+                let config = ParseConfig {
+                    fortran_exponent: fortran_exponent,
+                    decimal_comma: decimal_comma,
+                    underscores: underscores,
+                };
+                let _ = parse::parse_float::<f32>(text, &config);
+                let _ = parse::parse_float::<f64>(text, &config);
+            }
+        }
+    }
+
+    // the default configuration only trims
+    let standard = text.trim().parse::<f64>().ok().map(f64::to_bits);
+    let default = parse::parse_float::<f64>(text, &ParseConfig::default()).ok().map(f64::to_bits);
+    if !text.trim().is_empty() {
+        assert_eq!(default, standard, "{:?}", text);
+    }
+
+    // and text without the extra spellings reads the same when tolerant
+    if !text.contains(|c| c == '_' || c == 'd' || c == 'D') {
+        let tolerant = parse::parse_float::<f64>(text, &ParseConfig::tolerant()).ok().map(f64::to_bits);
+        assert_eq!(tolerant, default, "{:?}", text);
+    }
+
+    // a Fortran exponent is an ordinary one
+    if let Ok(x) = parse::parse_float::<f64>(text, &ParseConfig::default()) {
+        if text.contains('e') && !text.contains('_') {
+            let fortran = text.replace('e', "D");
+            let config = ParseConfig { fortran_exponent: true, ..ParseConfig::default() };
+            assert_eq!(parse::parse_float::<f64>(&fortran, &config).map(f64::to_bits), Ok(x.to_bits()), "{:?}", fortran);
+        }
+    }
+});
@@ -0,0 +1,51 @@
+//! Encoding to PCM and decoding again must land within half an LSB, plus the
+//! dither, of every in-range sample, and G.711 must round trip its own codes.
+
+#![no_main]
+
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate float;
+
+
+use float::pcm::{self, ClipPolicy, Dither};
+
+
+fuzz_target!(|data: &[u8]| {
+    let samples: Vec<f64> = data.chunks(8)
+        .map(|chunk| {
+            let mut bytes = [0_u8; 8];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            f64::from_bits(u64::from_le_bytes(bytes))
+        })
+        .collect();
+
+    let mut codes = vec![0_i16; samples.len()];
+    let mut decoded = vec![0.0_f64; samples.len()];
+
+    for &(dither, tolerance) in [(Dither::None, 0.5), (Dither::Tpdf(data.len() as u64), 1.5)].iter() {
+        let mut dither = dither;
+        pcm::encode_i16(&samples, &mut codes, &mut dither, ClipPolicy::Saturate);
+        pcm::decode_i16(&codes, &mut decoded);
+
+        for (&x, &y) in samples.iter().zip(decoded.iter()) {
+            if x.is_nan() {
+                assert_eq!(y, 0.0);
+            } else if x >= -1.0 && x <= 32766.0 / 32768.0 {
+                assert!((x - y).abs() * 32768.0 <= tolerance, "{} decoded as {}", x, y);
+            } else {
+                assert!(y >= -1.0 && y < 1.0);
+            }
+        }
+    }
+
+    for &byte in data {
+        let mu: f64 = pcm::from_mu_law(byte);
+        let a: f64 = pcm::from_a_law(byte);
+        // 0x7f and 0xff both decode to zero in mu-law
+        if byte & 0x7f != 0x7f {
+            assert_eq!(pcm::to_mu_law(mu), byte);
+        }
+        assert_eq!(pcm::to_a_law(a), byte);
+    }
+});
@@ -0,0 +1,102 @@
+//! The Rust fallbacks for `cbrt`, `expm1`, `hypot`, `log1p` and the inverse
+//! hyperbolic functions that wasm32-unknown-unknown builds use must stay
+//! within a few ulps of the C library for every input, agree with it on NaNs
+//! and infinities, and keep the symmetries of the functions exactly.
+
+#![no_main]
+
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate float;
+
+
+use float::soft_math as soft;
+
+
+// measured at 2 for f64, the f32 versions round the f64 ones
+const F64_ULPS: u64 = 4;
+const F32_ULPS: u64 = 2;
+
+
+// std's inverse hyperbolic functions are its own formulas, not the C ones
+extern "C" {
+    fn acosh(n: f64) -> f64;
+    fn asinh(n: f64) -> f64;
+    fn atanh(n: f64) -> f64;
+    fn acoshf(n: f32) -> f32;
+    fn asinhf(n: f32) -> f32;
+    fn atanhf(n: f32) -> f32;
+}
+
+
+fuzz_target!(|data: &[u8]| {
+    for chunk in data.chunks(16) {
+        let mut bytes = [0_u8; 16];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        let bits = u128::from_le_bytes(bytes);
+        let (x, y) = (f64::from_bits(bits as u64), f64::from_bits((bits >> 64) as u64));
+        let (xf, yf) = (f32::from_bits(bits as u32), f32::from_bits((bits >> 32) as u32));
+
+        unsafe {
+            check_f64("cbrt", x, soft::cbrt(x), x.cbrt());
+            check_f64("expm1", x, soft::expm1(x), x.exp_m1());
+            check_f64("log1p", x, soft::log1p(x), x.ln_1p());
+            check_f64("hypot", x, soft::hypot(x, y), x.hypot(y));
+            check_f64("acosh", x, soft::acosh(x), acosh(x));
+            check_f64("asinh", x, soft::asinh(x), asinh(x));
+            check_f64("atanh", x, soft::atanh(x), atanh(x));
+
+            check_f32("cbrtf", xf, soft::cbrtf(xf), xf.cbrt());
+            check_f32("expm1f", xf, soft::expm1f(xf), xf.exp_m1());
+            check_f32("log1pf", xf, soft::log1pf(xf), xf.ln_1p());
+            check_f32("hypotf", xf, soft::hypotf(xf, yf), xf.hypot(yf));
+            check_f32("acoshf", xf, soft::acoshf(xf), acoshf(xf));
+            check_f32("asinhf", xf, soft::asinhf(xf), asinhf(xf));
+            check_f32("atanhf", xf, soft::atanhf(xf), atanhf(xf));
+
+            assert_same(soft::cbrt(-x), -soft::cbrt(x), "cbrt is odd");
+            assert_same(soft::asinh(-x), -soft::asinh(x), "asinh is odd");
+            assert_same(soft::atanh(-x), -soft::atanh(x), "atanh is odd");
+            assert_same(soft::hypot(x, y), soft::hypot(y, x), "hypot is symmetric");
+            assert_same(soft::hypot(-x, y), soft::hypot(x, y), "hypot ignores signs");
+            assert_same(soft::hypotf(xf, yf), soft::hypotf(yf, xf), "hypotf is symmetric");
+        }
+    }
+});
+
+fn check_f64(name: &str, x: f64, soft: f64, c: f64) {
+    if c.is_nan() || soft.is_nan() {
+        assert!(c.is_nan() && soft.is_nan(), "{}({:e}) = {:e}, C gives {:e}", name, x, soft, c);
+    } else {
+        let ulps = distance(key_f64(soft), key_f64(c));
+        assert!(ulps <= F64_ULPS, "{}({:e}) = {:e}, C gives {:e}, {} ulps apart", name, x, soft, c, ulps);
+    }
+}
+
+fn check_f32(name: &str, x: f32, soft: f32, c: f32) {
+    if c.is_nan() || soft.is_nan() {
+        assert!(c.is_nan() && soft.is_nan(), "{}({:e}) = {:e}, C gives {:e}", name, x, soft, c);
+    } else {
+        let ulps = distance(key_f32(soft), key_f32(c));
+        assert!(ulps <= F32_ULPS, "{}({:e}) = {:e}, C gives {:e}, {} ulps apart", name, x, soft, c, ulps);
+    }
+}
+
+fn assert_same<T: float::Float + std::fmt::LowerExp>(a: T, b: T, what: &str) {
+    assert!(a == b || a.is_nan() && b.is_nan(), "{}: {:e} and {:e}", what, a, b);
+}
+
+// consecutive floats get consecutive keys, across zero as well
+fn key_f64(x: f64) -> i64 {
+    let bits = x.to_bits() as i64;
+    if bits < 0 { i64::MIN - bits } else { bits }
+}
+
+fn key_f32(x: f32) -> i64 {
+    let bits = x.to_bits() as i32;
+    (if bits < 0 { i32::MIN - bits } else { bits }) as i64
+}
+
+fn distance(a: i64, b: i64) -> u64 {
+    (a as i128 - b as i128).unsigned_abs() as u64
+}
@@ -0,0 +1,20 @@
+//! The vector readers must reject malformed text with an error rather than
+//! panic.
+
+#![no_main]
+
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate float;
+
+
+use float::test_vectors::{self, Operation};
+
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        let _ = test_vectors::run_fpgen(text);
+        let _ = test_vectors::run_testfloat::<f32>(Operation::Div, text);
+        let _ = test_vectors::run_testfloat::<f64>(Operation::Le, text);
+    }
+});
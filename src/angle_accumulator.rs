@@ -0,0 +1,162 @@
+use expansion::{two_sum, two_product, TAU};
+use float::Float;
+
+
+/// Accumulates incremental rotations without losing precision as the total
+/// grows.
+///
+/// The state is a whole number of periods, saturating at the limits of `i64`,
+/// plus a remainder in `[-period / 2, period / 2)`, up to a rounding at the
+/// ends, that carries its own rounding error. Whenever the remainder leaves
+/// that range a whole number of periods is moved into the counter,
+/// subtracting it exactly, so the wrapped angle stays as accurate after a
+/// million turns as after one.
+///
+/// ```
+/// use float::AngleAccumulator;
+///
+/// let step = 0.001_f32;
+/// let mut acc = AngleAccumulator::radians();
+/// let mut naive = 0.0_f32;
+/// for _ in 0..1000000 {
+///     acc.add(step);
+///     naive += step;
+/// }
+///
+/// let tau = 2.0 * std::f64::consts::PI;
+/// let exact = (1e6 * step as f64) % tau;
+/// assert_eq!(acc.turns(), 159);
+/// assert!((acc.angle() as f64 - exact).abs() < 1e-6);
+/// assert!((naive as f64 % tau - exact).abs() > 1e-2);
+///
+/// // half a period lands on the end of the range, and the move back keeps
+/// // the low word of the period
+/// let mut half = AngleAccumulator::radians();
+/// half.add(std::f64::consts::PI);
+/// assert_eq!((half.turns(), half.total()), (0, std::f64::consts::PI));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AngleAccumulator<T> {
+    turns: i64,
+    angle: T,
+    error: T,
+    period: T,
+    period_error: T,
+}
+
+impl<T: Float> AngleAccumulator<T> {
+    /// Accumulates radians, with a period of `2 pi` represented to twice the
+    /// precision of `T`.
+    #[inline]
+    pub fn radians() -> Self {
+        let period = T::from_f64(TAU.0);
+        let period_error = T::from_f64((TAU.0 - period.to_f64()) + TAU.1);
+        AngleAccumulator::with_exact_period(period, period_error)
+    }
+
+    /// Accumulates in units with the given period, such as `360.0` for
+    /// degrees, `1.0` for turns or the counts per revolution of an encoder.
+    ///
+    /// ```
+    /// use float::AngleAccumulator;
+    ///
+    /// let mut acc = AngleAccumulator::with_period(360.0_f64);
+    /// acc.add(350.0);
+    /// acc.add(20.0);
+    /// assert_eq!((acc.turns(), acc.angle()), (1, 10.0));
+    /// acc.add(-1090.0);
+    /// assert_eq!((acc.turns(), acc.angle()), (-2, 0.0));
+    /// assert_eq!(acc.total(), -720.0);
+    /// ```
+    #[inline]
+    pub fn with_period(period: T) -> Self {
+        AngleAccumulator::with_exact_period(period, T::from_f64(0.0))
+    }
+
+    #[inline]
+    fn with_exact_period(period: T, period_error: T) -> Self {
+        assert!(period > T::from_f64(0.0) && period.is_finite(), "angle period must be positive and finite");
+
+        AngleAccumulator {
+            turns: 0,
+            angle: T::from_f64(0.0),
+            error: T::from_f64(0.0),
+            period: period,
+            period_error: period_error,
+        }
+    }
+
+    /// Adds a rotation of `delta`.
+    pub fn add(&mut self, delta: T) {
+        let (sum, error) = two_sum(self.angle, delta);
+        let (angle, error) = two_sum(sum, self.error + error);
+        self.angle = angle;
+        self.error = error;
+
+        let half = self.period * T::from_f64(0.5);
+        if !(self.angle >= -half && self.angle < half) && self.angle.is_finite() {
+            self.renormalize(half);
+        }
+    }
+
+    fn renormalize(&mut self, half: T) {
+        let periods = ((self.angle + half) / self.period).floor();
+
+        // angle - product is exact by Sterbenz's lemma, the rest goes into
+        // the error term
+        let (product, product_error) = two_product(periods, self.period);
+        let angle = self.angle - product;
+        let error = self.error - product_error - periods * self.period_error;
+        let (angle, error) = two_sum(angle, error);
+
+        self.angle = angle;
+        self.error = error;
+        self.turns = self.turns.saturating_add(periods.to_f64() as i64);
+
+        // the error term can push the remainder just past either end
+        if self.angle >= half {
+            self.move_period(T::from_f64(1.0));
+            self.turns = self.turns.saturating_add(1);
+        } else if self.angle < -half {
+            self.move_period(T::from_f64(-1.0));
+            self.turns = self.turns.saturating_sub(1);
+        }
+    }
+
+    /// Subtracts `sign` periods, one either way, with both words of the
+    /// period.
+    #[inline]
+    fn move_period(&mut self, sign: T) {
+        let (angle, error) = two_sum(self.angle, -(sign * self.period));
+        let (angle, error) = two_sum(angle, (self.error + error) - sign * self.period_error);
+        self.angle = angle;
+        self.error = error;
+    }
+
+    /// The accumulated angle wrapped to `[-period / 2, period / 2)`.
+    #[inline(always)]
+    pub fn angle(&self) -> T {
+        self.angle
+    }
+
+    /// The number of whole periods moved out of the remainder.
+    #[inline(always)]
+    pub fn turns(&self) -> i64 {
+        self.turns
+    }
+
+    /// The unwrapped total, rounded to `T`.
+    #[inline]
+    pub fn total(&self) -> T {
+        let turns = T::from_f64(self.turns as f64);
+        turns * self.period + (self.angle + (self.error + turns * self.period_error))
+    }
+
+    /// Clears the accumulated rotation, keeping the period.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.turns = 0;
+        self.angle = T::from_f64(0.0);
+        self.error = T::from_f64(0.0);
+    }
+}
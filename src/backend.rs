@@ -0,0 +1,403 @@
+use core::cell::UnsafeCell;
+use core::hint;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use float::Float;
+use math;
+
+
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+static CUSTOM: CustomSlot = CustomSlot {
+    locked: AtomicBool::new(false),
+    backend: UnsafeCell::new(None),
+};
+
+const BACKENDS: [Backend; 3] = [Backend::Intrinsics, Backend::Libm, Backend::Custom];
+
+
+/// Implementations the math functions can be dispatched to at runtime.
+///
+/// Only the dynamic path, the `_ctx` operations of `ContextFloat`, consults
+/// the backend. The `Float` methods never do: they always call their
+/// implementations directly and pay nothing for the choice. `Custom` falls
+/// back to `Intrinsics` while no `MathBackend` is installed, see
+/// `is_available`.
+///
+/// `f32` values go to the `f32` functions of a backend and other types of
+/// up to 53 bits through the `f64` ones, while wider types always use their
+/// `Float` methods, so no backend drops their extra precision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// The implementations the `Float` methods use: the C math library, with
+    /// the `unstable` feature partly the compiler intrinsics, the methods of
+    /// the standard library with `std`, or the `libm` crate with `libm`.
+    Intrinsics,
+    /// The C math library called directly, or the `libm` crate with the
+    /// `libm` feature.
+    Libm,
+    /// The `MathBackend` installed by `set_math_backend`.
+    Custom,
+}
+
+impl Backend {
+    /// Returns whether this backend is in use, rather than falling back to
+    /// `Intrinsics`, which is only the case for `Custom` while no
+    /// `MathBackend` is installed.
+    ///
+    /// ```
+    /// use float::Backend;
+    /// assert!(Backend::Libm.is_available());
+    /// assert!(!Backend::Custom.is_available());
+    /// ```
+    #[inline]
+    pub fn is_available(&self) -> bool {
+        match *self {
+            Backend::Intrinsics | Backend::Libm => true,
+            Backend::Custom => custom().is_some(),
+        }
+    }
+
+    #[inline(always)]
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl Default for Backend {
+    #[inline(always)]
+    fn default() -> Self {
+        Backend::Intrinsics
+    }
+}
+
+
+/// Returns the backend selected by the innermost running `with_backend`, or
+/// `Intrinsics` outside of it.
+#[inline]
+pub fn current_backend() -> Backend {
+    BACKENDS[ACTIVE.load(Ordering::Relaxed)]
+}
+
+/// Runs `f` with `backend` selected for the dynamic path, restoring the
+/// previous selection afterwards, also when `f` panics.
+///
+/// Like `nan_tolerant` the selection is process wide, so a `MathContext`
+/// with an explicit backend is the better choice for code shared between
+/// threads.
+///
+/// ```
+/// use float::{with_backend, current_backend, Backend, ContextFloat, MathContext};
+///
+/// let x = with_backend(Backend::Libm, || {
+///     assert_eq!(current_backend(), Backend::Libm);
+///     1.0_f64.exp_ctx(&MathContext::default())
+/// });
+/// assert!((x - 2.718281828459045).abs() < 1e-15);
+/// assert_eq!(current_backend(), Backend::Intrinsics);
+/// ```
+#[inline]
+pub fn with_backend<F, R>(backend: Backend, f: F) -> R
+    where F: FnOnce() -> R,
+{
+    let _guard = BackendGuard {
+        previous: ACTIVE.swap(backend.index(), Ordering::Relaxed),
+    };
+    f()
+}
+
+struct BackendGuard {
+    previous: usize,
+}
+
+impl Drop for BackendGuard {
+    #[inline(always)]
+    fn drop(&mut self) {
+        ACTIVE.store(self.previous, Ordering::Relaxed);
+    }
+}
+
+
+/// Implementations of the math functions the dynamic path dispatches, for
+/// `Backend::Custom`, such as a vendor DSP library or a correctly rounded
+/// one.
+///
+/// The methods are named after their C counterparts, with `f` for `f32`,
+/// and default to the C math library, or the `libm` crate with the `libm`
+/// feature, so a backend overrides only the functions it has. The `f32`
+/// methods do not go through the `f64` ones; a backend with only `f64`
+/// functions can override them to round those.
+///
+/// A backend is installed as a `'static` reference, such as one to a unit
+/// struct or a `static`:
+///
+/// ```
+/// use float::{Backend, ContextFloat, Float, MathBackend, MathContext};
+///
+/// /// A cube root that only knows about perfect cubes.
+/// struct Vendor;
+///
+/// impl MathBackend for Vendor {
+///     fn cbrt(&self, x: f64) -> f64 {
+///         let root = x.abs().powf(1.0 / 3.0).round();
+///         if x < 0.0 { -root } else { root }
+///     }
+/// }
+///
+/// let ctx = MathContext::default().backend(Backend::Custom);
+/// float::set_math_backend(&Vendor);
+/// assert!(Backend::Custom.is_available());
+/// assert_eq!(10.0_f64.cbrt_ctx(&ctx), 2.0);
+/// assert_eq!(1.0_f64.exp_ctx(&ctx), 1.0_f64.exp_ctx(&MathContext::default().backend(Backend::Libm)));
+/// assert_eq!(0.5_f32.atan2_ctx(&1.0, &ctx), Float::atan2(&0.5_f32, &1.0));
+///
+/// float::clear_math_backend();
+/// assert_eq!(10.0_f64.cbrt_ctx(&ctx), 2.154434690031884);
+/// ```
+pub trait MathBackend: Sync {
+    fn expf(&self, x: f32) -> f32 { unsafe { math::expf(x) } }
+    fn exp2f(&self, x: f32) -> f32 { unsafe { math::exp2f(x) } }
+    fn expm1f(&self, x: f32) -> f32 { unsafe { math::expm1f(x) } }
+    fn logf(&self, x: f32) -> f32 { unsafe { math::logf(x) } }
+    fn log1pf(&self, x: f32) -> f32 { unsafe { math::log1pf(x) } }
+    fn log2f(&self, x: f32) -> f32 { unsafe { math::log2f(x) } }
+    fn log10f(&self, x: f32) -> f32 { unsafe { math::log10f(x) } }
+    fn cbrtf(&self, x: f32) -> f32 { unsafe { math::cbrtf(x) } }
+    fn powf(&self, x: f32, y: f32) -> f32 { unsafe { math::powf(x, y) } }
+    fn hypotf(&self, x: f32, y: f32) -> f32 { unsafe { math::hypotf(x, y) } }
+    fn sinf(&self, x: f32) -> f32 { unsafe { math::sinf32(x) } }
+    fn cosf(&self, x: f32) -> f32 { unsafe { math::cosf32(x) } }
+    fn tanf(&self, x: f32) -> f32 { unsafe { math::tanf(x) } }
+    fn asinf(&self, x: f32) -> f32 { unsafe { math::asinf(x) } }
+    fn acosf(&self, x: f32) -> f32 { unsafe { math::acosf(x) } }
+    fn atanf(&self, x: f32) -> f32 { unsafe { math::atanf(x) } }
+    fn atan2f(&self, y: f32, x: f32) -> f32 { unsafe { math::atan2f(y, x) } }
+    fn sinhf(&self, x: f32) -> f32 { unsafe { math::sinhf(x) } }
+    fn coshf(&self, x: f32) -> f32 { unsafe { math::coshf(x) } }
+    fn tanhf(&self, x: f32) -> f32 { unsafe { math::tanhf(x) } }
+    fn asinhf(&self, x: f32) -> f32 { unsafe { math::asinhf(x) } }
+    fn acoshf(&self, x: f32) -> f32 { unsafe { math::acoshf(x) } }
+    fn atanhf(&self, x: f32) -> f32 { unsafe { math::atanhf(x) } }
+
+    fn exp(&self, x: f64) -> f64 { unsafe { math::exp(x) } }
+    fn exp2(&self, x: f64) -> f64 { unsafe { math::exp2(x) } }
+    fn expm1(&self, x: f64) -> f64 { unsafe { math::expm1(x) } }
+    fn log(&self, x: f64) -> f64 { unsafe { math::log(x) } }
+    fn log1p(&self, x: f64) -> f64 { unsafe { math::log1p(x) } }
+    fn log2(&self, x: f64) -> f64 { unsafe { math::log2(x) } }
+    fn log10(&self, x: f64) -> f64 { unsafe { math::log10(x) } }
+    fn cbrt(&self, x: f64) -> f64 { unsafe { math::cbrt(x) } }
+    fn pow(&self, x: f64, y: f64) -> f64 { unsafe { math::pow(x, y) } }
+    fn hypot(&self, x: f64, y: f64) -> f64 { unsafe { math::hypot(x, y) } }
+    fn sin(&self, x: f64) -> f64 { unsafe { math::sinf64(x) } }
+    fn cos(&self, x: f64) -> f64 { unsafe { math::cosf64(x) } }
+    fn tan(&self, x: f64) -> f64 { unsafe { math::tan(x) } }
+    fn asin(&self, x: f64) -> f64 { unsafe { math::asin(x) } }
+    fn acos(&self, x: f64) -> f64 { unsafe { math::acos(x) } }
+    fn atan(&self, x: f64) -> f64 { unsafe { math::atan(x) } }
+    fn atan2(&self, y: f64, x: f64) -> f64 { unsafe { math::atan2(y, x) } }
+    fn sinh(&self, x: f64) -> f64 { unsafe { math::sinh(x) } }
+    fn cosh(&self, x: f64) -> f64 { unsafe { math::cosh(x) } }
+    fn tanh(&self, x: f64) -> f64 { unsafe { math::tanh(x) } }
+    fn asinh(&self, x: f64) -> f64 { unsafe { math::asinh(x) } }
+    fn acosh(&self, x: f64) -> f64 { unsafe { math::acosh(x) } }
+    fn atanh(&self, x: f64) -> f64 { unsafe { math::atanh(x) } }
+}
+
+/// Installs `backend` as `Backend::Custom`, replacing the one installed
+/// before. Like `with_backend` this is process wide.
+#[inline]
+pub fn set_math_backend(backend: &'static dyn MathBackend) {
+    CUSTOM.with(|slot| *slot = Some(backend));
+}
+
+/// Removes the backend installed by `set_math_backend`, after which
+/// `Backend::Custom` falls back to `Intrinsics`.
+#[inline]
+pub fn clear_math_backend() {
+    CUSTOM.with(|slot| *slot = None);
+}
+
+#[inline]
+fn custom() -> Option<&'static dyn MathBackend> {
+    CUSTOM.with(|slot| *slot)
+}
+
+/// The installed backend. A reference to a trait object is two words, more
+/// than an atomic holds, so it sits behind a spin lock that is only held to
+/// copy it in or out.
+struct CustomSlot {
+    locked: AtomicBool,
+    backend: UnsafeCell<Option<&'static dyn MathBackend>>,
+}
+
+// the lock serializes every access to `backend`
+unsafe impl Sync for CustomSlot {}
+
+impl CustomSlot {
+    #[inline]
+    fn with<F, R>(&self, f: F) -> R
+        where F: FnOnce(&mut Option<&'static dyn MathBackend>) -> R,
+    {
+        while self.locked.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            hint::spin_loop();
+        }
+        let result = f(unsafe { &mut *self.backend.get() });
+        self.locked.store(false, Ordering::Release);
+        result
+    }
+}
+
+/// `Backend::Libm`, the defaults of `MathBackend`.
+struct Libm;
+
+impl MathBackend for Libm {}
+
+static LIBM: Libm = Libm;
+
+/// The implementation of `backend` for `T`, `None` for the `Float` methods.
+/// Types wider than `f64` keep to their own methods, which the `f64`
+/// functions would round.
+#[inline]
+fn provider<T: Float>(backend: Backend) -> Option<&'static dyn MathBackend> {
+    if T::mantissa_digits() > 53 {
+        return None;
+    }
+    match backend {
+        Backend::Libm => Some(&LIBM),
+        Backend::Custom => custom(),
+        Backend::Intrinsics => None,
+    }
+}
+
+
+/// The functions the dynamic path dispatches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Function {
+    Exp,
+    Exp2,
+    ExpM1,
+    Ln,
+    Ln1p,
+    Log2,
+    Log10,
+    Cbrt,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Asinh,
+    Acosh,
+    Atanh,
+}
+
+pub(crate) fn unary<T: Float>(backend: Backend, function: Function, x: T) -> T {
+    match provider::<T>(backend) {
+        Some(provider) if T::mantissa_digits() == 24 => {
+            let x = x.to_f64() as f32;
+            T::from_f64(match function {
+                Function::Exp => provider.expf(x),
+                Function::Exp2 => provider.exp2f(x),
+                Function::ExpM1 => provider.expm1f(x),
+                Function::Ln => provider.logf(x),
+                Function::Ln1p => provider.log1pf(x),
+                Function::Log2 => provider.log2f(x),
+                Function::Log10 => provider.log10f(x),
+                Function::Cbrt => provider.cbrtf(x),
+                Function::Sin => provider.sinf(x),
+                Function::Cos => provider.cosf(x),
+                Function::Tan => provider.tanf(x),
+                Function::Asin => provider.asinf(x),
+                Function::Acos => provider.acosf(x),
+                Function::Atan => provider.atanf(x),
+                Function::Sinh => provider.sinhf(x),
+                Function::Cosh => provider.coshf(x),
+                Function::Tanh => provider.tanhf(x),
+                Function::Asinh => provider.asinhf(x),
+                Function::Acosh => provider.acoshf(x),
+                Function::Atanh => provider.atanhf(x),
+            } as f64)
+        },
+        Some(provider) => {
+            let x = x.to_f64();
+            T::from_f64(match function {
+                Function::Exp => provider.exp(x),
+                Function::Exp2 => provider.exp2(x),
+                Function::ExpM1 => provider.expm1(x),
+                Function::Ln => provider.log(x),
+                Function::Ln1p => provider.log1p(x),
+                Function::Log2 => provider.log2(x),
+                Function::Log10 => provider.log10(x),
+                Function::Cbrt => provider.cbrt(x),
+                Function::Sin => provider.sin(x),
+                Function::Cos => provider.cos(x),
+                Function::Tan => provider.tan(x),
+                Function::Asin => provider.asin(x),
+                Function::Acos => provider.acos(x),
+                Function::Atan => provider.atan(x),
+                Function::Sinh => provider.sinh(x),
+                Function::Cosh => provider.cosh(x),
+                Function::Tanh => provider.tanh(x),
+                Function::Asinh => provider.asinh(x),
+                Function::Acosh => provider.acosh(x),
+                Function::Atanh => provider.atanh(x),
+            })
+        },
+        None => match function {
+            Function::Exp => x.exp(),
+            Function::Exp2 => x.exp2(),
+            Function::ExpM1 => x.exp_m1(),
+            Function::Ln => x.ln(),
+            Function::Ln1p => x.ln_1p(),
+            Function::Log2 => x.log2(),
+            Function::Log10 => x.log10(),
+            Function::Cbrt => x.cbrt(),
+            Function::Sin => x.sin(),
+            Function::Cos => x.cos(),
+            Function::Tan => x.tan(),
+            Function::Asin => x.asin(),
+            Function::Acos => x.acos(),
+            Function::Atan => x.atan(),
+            Function::Sinh => x.sinh(),
+            Function::Cosh => x.cosh(),
+            Function::Tanh => x.tanh(),
+            Function::Asinh => x.asinh(),
+            Function::Acosh => x.acosh(),
+            Function::Atanh => x.atanh(),
+        },
+    }
+}
+
+pub(crate) fn powf_with<T: Float>(backend: Backend, x: T, y: T) -> T {
+    match provider::<T>(backend) {
+        Some(provider) if T::mantissa_digits() == 24 => {
+            T::from_f64(provider.powf(x.to_f64() as f32, y.to_f64() as f32) as f64)
+        },
+        Some(provider) => T::from_f64(provider.pow(x.to_f64(), y.to_f64())),
+        None => x.powf(&y),
+    }
+}
+
+pub(crate) fn hypot_with<T: Float>(backend: Backend, x: T, y: T) -> T {
+    match provider::<T>(backend) {
+        Some(provider) if T::mantissa_digits() == 24 => {
+            T::from_f64(provider.hypotf(x.to_f64() as f32, y.to_f64() as f32) as f64)
+        },
+        Some(provider) => T::from_f64(provider.hypot(x.to_f64(), y.to_f64())),
+        None => x.hypot(&y),
+    }
+}
+
+pub(crate) fn atan2_with<T: Float>(backend: Backend, y: T, x: T) -> T {
+    match provider::<T>(backend) {
+        Some(provider) if T::mantissa_digits() == 24 => {
+            T::from_f64(provider.atan2f(y.to_f64() as f32, x.to_f64() as f32) as f64)
+        },
+        Some(provider) => T::from_f64(provider.atan2(y.to_f64(), x.to_f64())),
+        None => y.atan2(&x),
+    }
+}
@@ -0,0 +1,323 @@
+use expansion::two_sum;
+use float::Float;
+
+
+/// Room for the most bins a type keeps, the six of `f32`.
+const MAX_FOLD: usize = 6;
+
+
+/// Reproducible summation in the style of ReproBLAS' binned types.
+///
+/// Every summand is split along a fixed grid of exponent levels `W` bits apart
+/// (`W = mantissa_digits - 13`), starting from the smallest subnormal, and the
+/// pieces falling into the highest levels touched so far are accumulated
+/// exactly, together with integer carries. Enough levels are kept to cover
+/// twice the precision of `T` below the top one, so a summand is only
+/// truncated once it lies that far below the largest; a single summand comes
+/// back unchanged, subnormals included. Because the grid does not depend on
+/// the data and every kept piece is added exactly, the result is
+/// bit-identical for any order of `add` and `merge` calls, which makes it
+/// suitable for deterministic parallel reductions.
+///
+/// ```
+/// use float::BinnedSum;
+///
+/// let values = [1e16_f64, 1.0, -1e16, 0.1, 3.5e-3, -2.0];
+///
+/// let mut forward = BinnedSum::new();
+/// forward.add_slice(&values);
+///
+/// let mut backward = BinnedSum::new();
+/// for &x in values.iter().rev() {
+///     backward.add(x);
+/// }
+///
+/// let mut merged = BinnedSum::new();
+/// let mut other = BinnedSum::new();
+/// merged.add_slice(&values[3..]);
+/// other.add_slice(&values[..3]);
+/// merged.merge(&other);
+///
+/// assert_eq!(forward.sum(), backward.sum());
+/// assert_eq!(forward.sum(), merged.sum());
+///
+/// let mut single = BinnedSum::new();
+/// single.add(1.0000001_f32);
+/// assert_eq!(single.sum(), 1.0000001);
+///
+/// let mut tiny = BinnedSum::new();
+/// tiny.add_slice(&[1e-305_f64, 1e-305, 5e-324]);
+/// assert_eq!(tiny.sum(), 2e-305 + 5e-324);
+///
+/// let mut spread = BinnedSum::new();
+/// spread.add_slice(&[1.0_f64, 1e-30, -1.0]);
+/// assert_eq!(spread.sum(), 1e-30);
+/// ```
+#[derive(Clone, Copy)]
+pub struct BinnedSum<T> {
+    level: Option<i32>,
+    bins: [T; MAX_FOLD],
+    carries: [i64; MAX_FOLD],
+    pending: usize,
+    nan: bool,
+    pos_inf: bool,
+    neg_inf: bool,
+}
+
+impl<T: Float> BinnedSum<T> {
+    #[inline]
+    pub fn new() -> Self {
+        let zero = T::from_f64(0.0);
+        debug_assert!(fold::<T>() <= MAX_FOLD);
+
+        BinnedSum {
+            level: None,
+            bins: [zero; MAX_FOLD],
+            carries: [0; MAX_FOLD],
+            pending: 0,
+            nan: false,
+            pos_inf: false,
+            neg_inf: false,
+        }
+    }
+
+    pub fn add(&mut self, x: T) {
+        if !x.is_finite() {
+            if x.is_nan() {
+                self.nan = true;
+            } else if x > T::from_f64(0.0) {
+                self.pos_inf = true;
+            } else {
+                self.neg_inf = true;
+            }
+            return;
+        }
+
+        let top = match level_of(x) {
+            Some(level) => level,
+            None => return,
+        };
+        self.raise(top);
+
+        let level = self.level.unwrap();
+        let mut rest = x;
+
+        for k in 0..fold::<T>() {
+            let bin_level = level - k as i32;
+
+            // level zero holds the smallest subnormal, nothing lies below it
+            if bin_level < 0 {
+                break;
+            }
+
+            let unit = bin_unit::<T>(bin_level);
+            let count = (rest / unit).trunc();
+            self.bins[k] = self.bins[k] + count;
+            rest = rest - count * unit;
+        }
+
+        self.pending += 1;
+        if self.pending >= max_pending::<T>() {
+            self.renormalize();
+        }
+    }
+
+    #[inline]
+    pub fn add_slice(&mut self, xs: &[T]) {
+        for &x in xs {
+            self.add(x);
+        }
+    }
+
+    /// Adds all summands accumulated by `other` to `self`.
+    pub fn merge(&mut self, other: &Self) {
+        let mut other = *other;
+
+        self.nan |= other.nan;
+        self.pos_inf |= other.pos_inf;
+        self.neg_inf |= other.neg_inf;
+
+        let level = match (self.level, other.level) {
+            (Some(a), Some(b)) => if a > b { a } else { b },
+            (Some(a), None) => a,
+            (None, Some(b)) => b,
+            (None, None) => return,
+        };
+
+        self.raise(level);
+        other.raise(level);
+        self.renormalize();
+        other.renormalize();
+
+        for k in 0..fold::<T>() {
+            self.bins[k] = self.bins[k] + other.bins[k];
+            self.carries[k] += other.carries[k];
+        }
+        self.renormalize();
+    }
+
+    /// Returns the sum of all summands, identical for every summation order.
+    pub fn sum(&self) -> T {
+        let zero = T::from_f64(0.0);
+
+        if self.nan || (self.pos_inf && self.neg_inf) {
+            return T::nan();
+        } else if self.pos_inf {
+            return T::infinity();
+        } else if self.neg_inf {
+            return T::neg_infinity();
+        }
+
+        let level = match self.level {
+            Some(level) => level,
+            None => return zero,
+        };
+
+        let mut canonical = *self;
+        canonical.renormalize();
+
+        let radix = bin_radix::<T>();
+        let mut sum = zero;
+        let mut err = zero;
+
+        for k in (0..fold::<T>()).rev() {
+            if level < k as i32 {
+                continue;
+            }
+            let unit = bin_unit::<T>(level - k as i32);
+            let carry = if canonical.carries[k] == 0 {
+                zero
+            } else {
+                T::from_f64(canonical.carries[k] as f64) * unit * radix
+            };
+
+            for &term in &[canonical.bins[k] * unit, carry] {
+                let (s, e) = two_sum(sum, term);
+                err = err + e;
+                sum = s;
+            }
+        }
+
+        sum + err
+    }
+
+    /// Moves the top bin up to `level`, dropping bins that fall off the bottom.
+    fn raise(&mut self, level: i32) {
+        let current = match self.level {
+            Some(current) => current,
+            None => {
+                self.level = Some(level);
+                return;
+            },
+        };
+
+        if level <= current {
+            return;
+        }
+
+        let shift = (level - current) as usize;
+        let zero = T::from_f64(0.0);
+
+        for k in (0..fold::<T>()).rev() {
+            if k >= shift {
+                self.bins[k] = self.bins[k - shift];
+                self.carries[k] = self.carries[k - shift];
+            } else {
+                self.bins[k] = zero;
+                self.carries[k] = 0;
+            }
+        }
+        self.level = Some(level);
+    }
+
+    /// Moves whole multiples of the bin radix out of every bin into its carry,
+    /// leaving each bin with the same sign as its carry so the state is canonical.
+    fn renormalize(&mut self) {
+        let zero = T::from_f64(0.0);
+        let radix = bin_radix::<T>();
+
+        for k in 0..fold::<T>() {
+            let high = (self.bins[k] / radix).trunc();
+            self.bins[k] = self.bins[k] - high * radix;
+            self.carries[k] += high.to_f64() as i64;
+
+            if self.carries[k] > 0 && self.bins[k] < zero {
+                self.carries[k] -= 1;
+                self.bins[k] = self.bins[k] + radix;
+            } else if self.carries[k] < 0 && self.bins[k] > zero {
+                self.carries[k] += 1;
+                self.bins[k] = self.bins[k] - radix;
+            }
+        }
+        self.pending = 0;
+    }
+}
+
+impl<T: Float> Default for BinnedSum<T> {
+    #[inline(always)]
+    fn default() -> Self {
+        BinnedSum::new()
+    }
+}
+
+
+#[inline(always)]
+fn bin_width<T: Float>() -> i32 {
+    T::mantissa_digits() as i32 - 13
+}
+
+/// Number of bins kept, enough for `2 * mantissa_digits` bits below the
+/// lowest bit of the top level.
+#[inline(always)]
+// `usize::div_ceil` needs Rust 1.73
+#[allow(clippy::manual_div_ceil)]
+fn fold<T: Float>() -> usize {
+    let w = bin_width::<T>() as usize;
+    1 + (2 * T::mantissa_digits() as usize + w - 1) / w
+}
+
+#[inline(always)]
+fn max_pending<T: Float>() -> usize {
+    1 << (T::mantissa_digits() as i32 - bin_width::<T>() - 2)
+}
+
+#[inline(always)]
+fn bin_radix<T: Float>() -> T {
+    T::from_f64(2.0).powi(bin_width::<T>())
+}
+
+/// Exponent of the smallest subnormal, the unit of level zero.
+#[inline(always)]
+fn base_exponent<T: Float>() -> i32 {
+    T::min_exp() - T::mantissa_digits() as i32
+}
+
+/// Unit in the last place of the bin at `level`, `2^(base + level * W)`.
+#[inline]
+fn bin_unit<T: Float>(level: i32) -> T {
+    let exp = base_exponent::<T>() + level * bin_width::<T>();
+    let two = T::from_f64(2.0);
+
+    if exp >= 0 {
+        two.powi(exp)
+    } else {
+        // in two halves, since the reciprocal of a subnormal overflows
+        let half = -exp / 2;
+        two.powi(half).recip() * two.powi(-exp - half).recip()
+    }
+}
+
+/// Level of the bin holding the leading bit of `x`, `None` for zero.
+#[inline]
+fn level_of<T: Float>(x: T) -> Option<i32> {
+    let (mantissa, exponent, _) = x.integer_decode();
+
+    if mantissa == 0 {
+        None
+    } else {
+        let bits = 64 - mantissa.leading_zeros() as i32;
+        let exp = exponent as i32 + bits - 1;
+
+        Some((exp - base_exponent::<T>()) / bin_width::<T>())
+    }
+}
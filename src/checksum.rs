@@ -0,0 +1,51 @@
+use float::Float;
+
+
+/// Reflected ECMA-182 polynomial, as used by CRC-64/XZ.
+const CRC64_POLY: u64 = 0xc96c5795d7870f42;
+const CANONICAL_NAN: u64 = 0x7ff8000000000000;
+
+
+/// Returns a CRC-64/XZ over the canonical bit patterns of `values`.
+///
+/// Every value is widened to `f64`, which is exact, and NaNs are replaced by
+/// a single quiet NaN, so the fingerprint ignores NaN payloads and signs that
+/// differ between platforms while still telling `0.0` from `-0.0`. Equal
+/// `f32` and `f64` buffers produce the same checksum.
+///
+/// ```
+/// use float::checksum_canonical;
+///
+/// let a = [1.0_f64, -0.0, f64::NAN, 2.5];
+/// let b = [1.0_f32, -0.0, -f32::NAN, 2.5];
+/// assert_eq!(checksum_canonical(&a), checksum_canonical(&b));
+/// assert!(checksum_canonical(&a) != checksum_canonical(&[1.0, 0.0, f64::NAN, 2.5]));
+/// assert!(checksum_canonical(&a) != checksum_canonical(&[2.5, 1.0, -0.0, f64::NAN]));
+/// ```
+pub fn checksum_canonical<T: Float>(values: &[T]) -> u64 {
+    let mut crc = !0_u64;
+
+    for value in values {
+        let bits: u64 = if value.is_nan() {
+            CANONICAL_NAN
+        } else {
+            Float::to_bits(&value.to_f64())
+        };
+
+        for shift in 0..8 {
+            crc = crc64_update(crc, (bits >> (shift * 8)) as u8);
+        }
+    }
+
+    !crc
+}
+
+
+#[inline]
+fn crc64_update(mut crc: u64, byte: u8) -> u64 {
+    crc ^= byte as u64;
+    for _ in 0..8 {
+        crc = if crc & 1 != 0 { (crc >> 1) ^ CRC64_POLY } else { crc >> 1 };
+    }
+    crc
+}
@@ -0,0 +1,199 @@
+//! A quick IEEE 754 trust check of the arithmetic and the math backend on the
+//! running target.
+//!
+//! Every input goes through a volatile read first, so the compiler can not
+//! fold the checks at build time and the results describe the hardware,
+//! the float environment and the linked math library actually in use.
+
+use core::fmt;
+use core::ptr;
+
+use collections::vec::Vec;
+
+use float::Float;
+use nan_debug::nan_tolerant;
+
+
+/// A check that did not hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ConformanceFailure {
+    /// `"f32"` or `"f64"`.
+    pub float: &'static str,
+    /// Short description of the property.
+    pub check: &'static str,
+}
+
+impl fmt::Display for ConformanceFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.float, self.check)
+    }
+}
+
+
+/// Outcome of `run` and of `self_test`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConformanceReport {
+    /// Number of checks that held.
+    pub passed: usize,
+    /// The checks that did not hold, in the order they ran.
+    pub failures: Vec<ConformanceFailure>,
+}
+
+impl ConformanceReport {
+    #[inline]
+    pub fn is_conformant(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    #[inline]
+    pub fn total(&self) -> usize {
+        self.passed + self.failures.len()
+    }
+
+    #[inline]
+    pub(crate) fn record(&mut self, float: &'static str, check: &'static str, ok: bool) {
+        if ok {
+            self.passed += 1;
+        } else {
+            self.failures.push(ConformanceFailure {
+                float: float,
+                check: check,
+            });
+        }
+    }
+}
+
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} of {} checks passed", self.passed, self.total())?;
+        for (index, failure) in self.failures.iter().enumerate() {
+            write!(f, "{}{}", if index == 0 { "; failed: " } else { ", " }, failure)?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Runs the battery for `f32` and `f64`: signed zeros, NaN propagation,
+/// infinity arithmetic, round to nearest even on halfway cases, gradual
+/// underflow and the exact special values of the math functions.
+///
+/// The checks produce NaNs on purpose and run under `nan_tolerant`.
+///
+/// ```
+/// let report = float::conformance::run();
+/// assert!(report.is_conformant(), "{}", report);
+/// assert!(report.total() > 40);
+/// ```
+pub fn run() -> ConformanceReport {
+    let mut report = ConformanceReport {
+        passed: 0,
+        failures: Vec::new(),
+    };
+
+    nan_tolerant(|| {
+        check::<f32>(&mut report, "f32");
+        check::<f64>(&mut report, "f64");
+    });
+
+    report
+}
+
+
+/// Hides `x` from constant folding.
+#[inline(never)]
+pub(crate) fn opaque<T: Copy>(x: T) -> T {
+    unsafe { ptr::read_volatile(&x) }
+}
+
+// comparing a value with itself is the point of the NaN and x - x checks
+#[allow(clippy::eq_op, clippy::neg_cmp_op_on_partial_ord)]
+fn check<T: Float>(report: &mut ConformanceReport, float: &'static str) {
+    let zero = opaque(T::from_f64(0.0));
+    let neg_zero = opaque(T::neg_zero());
+    let one = opaque(T::from_f64(1.0));
+    let two = opaque(T::from_f64(2.0));
+    let half = opaque(T::from_f64(0.5));
+    let eps = opaque(T::epsilon());
+    let inf = opaque(T::infinity());
+    let nan = opaque(T::nan());
+    let min_normal = opaque(two.powi(T::min_exp() - 1));
+    let max = opaque((two - eps) * two.powi(T::max_exp() - 1));
+
+    let is_pos_zero = |x: T| x == zero && x.is_sign_positive();
+    let is_neg_zero = |x: T| x == zero && x.is_sign_negative();
+
+    // signed zero
+    report.record(float, "-0 == +0", neg_zero == zero);
+    report.record(float, "+0 * -1 is -0", is_neg_zero(zero * -one));
+    report.record(float, "-0 + -0 is -0", is_neg_zero(neg_zero + neg_zero));
+    report.record(float, "+0 + -0 is +0", is_pos_zero(zero + neg_zero));
+    report.record(float, "x - x is +0", is_pos_zero(one - one));
+    report.record(float, "1 / -0 is -inf", one / neg_zero == -inf);
+    report.record(float, "-0 / 1 is -0", is_neg_zero(neg_zero / one));
+    report.record(float, "recip(+0) is +inf", zero.recip() == inf);
+
+    // NaN propagation and comparisons
+    report.record(float, "NaN != NaN", !(nan == nan) && nan != nan);
+    report.record(float, "NaN is unordered", !(nan < one) && !(nan > one) && !(nan <= nan));
+    report.record(float, "NaN + 1 is NaN", (nan + one).is_nan());
+    report.record(float, "NaN * 0 is NaN", (nan * zero).is_nan());
+    report.record(float, "0 / 0 is NaN", (zero / zero).is_nan());
+    report.record(float, "NaN classification", nan.is_nan() && !nan.is_finite() && !nan.is_infinite());
+
+    // infinities
+    report.record(float, "inf - inf is NaN", (inf - inf).is_nan());
+    report.record(float, "inf * 0 is NaN", (inf * zero).is_nan());
+    report.record(float, "inf / inf is NaN", (inf / inf).is_nan());
+    report.record(float, "inf + 1 is inf", inf + one == inf);
+    report.record(float, "1 / inf is +0", is_pos_zero(one / inf));
+    report.record(float, "-1 / inf is -0", is_neg_zero(-one / inf));
+    report.record(float, "MAX * 2 overflows to inf", max * two == inf);
+    report.record(float, "MAX + ulp/4 rounds to MAX", max + max * eps * half * half == max);
+    report.record(float, "MAX + ulp/2 ties to inf",
+                  max + two.powi(T::max_exp() - 1 - T::mantissa_digits() as i32) == inf);
+
+    // round to nearest, ties to even
+    report.record(float, "1 + eps/2 ties down to 1", one + eps * half == one);
+    report.record(float, "1 + 3eps/2 ties up to 1 + 2eps", one + eps + eps * half == one + two * eps);
+    report.record(float, "1 - eps/4 ties up to 1", one - eps * half * half == one);
+    report.record(float, "-(1 + eps/2) ties to -1", -one - eps * half == -one);
+    report.record(float, "1/3 * 3 is 1", one / opaque(T::from_f64(3.0)) * opaque(T::from_f64(3.0)) == one);
+
+    // gradual underflow
+    report.record(float, "MIN_POSITIVE / 2 is subnormal", {
+        let x = min_normal * half;
+        x > zero && !x.is_normal() && x * two == min_normal
+    });
+    report.record(float, "smallest subnormal / 2 ties to +0", {
+        let tiny = min_normal * eps;
+        tiny > zero && is_pos_zero(tiny * half)
+    });
+    report.record(float, "x - y is 0 only for x == y", {
+        let a = min_normal * opaque(T::from_f64(1.5));
+        (a - min_normal) != zero
+    });
+
+    // exact special values of the backend
+    report.record(float, "trunc(-0.5) is -0", is_neg_zero((-half).trunc()));
+    report.record(float, "floor(-0.5) is -1", (-half).floor() == -one);
+    report.record(float, "fract(-1.5) is -0.5", (-one - half).fract() == -half);
+    report.record(float, "powi(x, 0) is 1", opaque(T::from_f64(7.5)).powi(0) == one);
+    report.record(float, "powi(2, -1) is 0.5", two.powi(-1) == half);
+    report.record(float, "exp(0) is 1", zero.exp() == one);
+    report.record(float, "exp(-inf) is +0", is_pos_zero((-inf).exp()));
+    report.record(float, "exp(inf) is inf", inf.exp() == inf);
+    report.record(float, "exp2(10) is 1024", opaque(T::from_f64(10.0)).exp2() == T::from_f64(1024.0));
+    report.record(float, "ln(1) is +0", is_pos_zero(one.ln()));
+    report.record(float, "ln(0) is -inf", zero.ln() == -inf);
+    report.record(float, "ln(-1) is NaN", (-one).ln().is_nan());
+    report.record(float, "log2(8) is 3", opaque(T::from_f64(8.0)).log2() == T::from_f64(3.0));
+    report.record(float, "log10(1000) is 3", opaque(T::from_f64(1000.0)).log10() == T::from_f64(3.0));
+    report.record(float, "cbrt(-8) is -2", opaque(T::from_f64(-8.0)).cbrt() == -two);
+    report.record(float, "hypot(3, 4) is 5", opaque(T::from_f64(3.0)).hypot(&T::from_f64(4.0)) == T::from_f64(5.0));
+    report.record(float, "hypot(inf, NaN) is inf", inf.hypot(&nan) == inf);
+    report.record(float, "powf(NaN, 0) is 1", nan.powf(&zero) == one);
+    report.record(float, "powf(1, NaN) is 1", one.powf(&nan) == one);
+    report.record(float, "exp_m1(-0) is -0", is_neg_zero(neg_zero.exp_m1()));
+    report.record(float, "ln_1p(-1) is -inf", (-one).ln_1p() == -inf);
+}
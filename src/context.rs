@@ -0,0 +1,277 @@
+use backend::{self, Backend, Function};
+use expansion::{two_sum, two_product};
+use float::Float;
+use rounding::RoundingMode;
+
+
+/// What a context-aware operation does when its result is NaN.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NanPolicy {
+    /// Return the NaN, as IEEE 754 does.
+    Propagate,
+    /// Panic, naming the operation.
+    Panic,
+    /// Return the given value instead.
+    Replace(f64),
+}
+
+impl Default for NanPolicy {
+    #[inline(always)]
+    fn default() -> Self {
+        NanPolicy::Propagate
+    }
+}
+
+
+/// Configuration passed explicitly to the `_ctx` operations of
+/// `ContextFloat`, so libraries can be configured without global or thread
+/// local state. Contexts are built from `MathContext::new` or `default` and
+/// refined with the builder methods.
+///
+/// ```
+/// use float::{ContextFloat, MathContext, NanPolicy, RoundingMode};
+///
+/// let up = MathContext::new(RoundingMode::TowardPositive, NanPolicy::Propagate);
+/// let down = MathContext::new(RoundingMode::TowardNegative, NanPolicy::Propagate);
+/// assert_eq!(1.0_f64.add_ctx(&1e-30, &up), 1.0000000000000002);
+/// assert_eq!(1.0_f64.add_ctx(&1e-30, &down), 1.0);
+/// assert_eq!(1.0_f64.div_ctx(&3.0, &down), 0.3333333333333333);
+/// assert_eq!(1.0_f64.div_ctx(&3.0, &up), 0.33333333333333337);
+///
+/// let lenient = MathContext::default().nan_policy(NanPolicy::Replace(0.0));
+/// assert_eq!(float::nan_tolerant(|| (-1.0_f32).ln_ctx(&lenient)), 0.0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MathContext {
+    pub rounding: RoundingMode,
+    pub nan_policy: NanPolicy,
+    /// Backend of the math functions, `None` for `current_backend()`.
+    pub backend: Option<Backend>,
+}
+
+impl MathContext {
+    #[inline(always)]
+    pub fn new(rounding: RoundingMode, nan_policy: NanPolicy) -> Self {
+        MathContext {
+            rounding: rounding,
+            nan_policy: nan_policy,
+            backend: None,
+        }
+    }
+
+    #[inline(always)]
+    pub fn rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    #[inline(always)]
+    pub fn nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+
+    /// Pins the math functions to `backend`, ignoring `with_backend`.
+    ///
+    /// ```
+    /// use float::{Backend, ContextFloat, MathContext, RoundingMode};
+    ///
+    /// let ctx = MathContext::default().backend(Backend::Libm).rounding(RoundingMode::TowardZero);
+    /// assert_eq!(8.0_f64.cbrt_ctx(&ctx), 1.9999999999999998);
+    /// ```
+    #[inline(always)]
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    #[inline]
+    fn active_backend(&self) -> Backend {
+        match self.backend {
+            Some(backend) => backend,
+            None => backend::current_backend(),
+        }
+    }
+
+    #[inline]
+    fn finish<T: Float>(&self, name: &'static str, value: T) -> T {
+        if value.is_nan() {
+            match self.nan_policy {
+                NanPolicy::Propagate => value,
+                NanPolicy::Panic => panic!("{} produced a NaN", name),
+                NanPolicy::Replace(replacement) => T::from_f64(replacement),
+            }
+        } else {
+            value
+        }
+    }
+}
+
+impl Default for MathContext {
+    #[inline(always)]
+    fn default() -> Self {
+        MathContext::new(RoundingMode::NearestEven, NanPolicy::Propagate)
+    }
+}
+
+
+macro_rules! impl_unary_ctx {
+    ($($name:ident => $method:ident, $function:ident),*) => ($(
+        #[inline]
+        fn $name(&self, ctx: &MathContext) -> Self {
+            let value = backend::unary(ctx.active_backend(), Function::$function, *self);
+            ctx.finish(stringify!($method), widen_directed(value, ctx.rounding))
+        }
+    )*)
+}
+
+
+/// Operations taking an explicit `MathContext`, implemented for every `Float`.
+///
+/// The four basic operations round exactly in the requested direction, using
+/// the exact error of `two_sum` and `two_product`. Products and quotients in
+/// the subnormal range are the exception, as their error may underflow. The
+/// math functions, the elementary, trigonometric and hyperbolic ones, go
+/// through the selected backend and are only faithful, so for the directed
+/// modes their nearest result is moved one ulp in the requested direction,
+/// which yields a bound of the true value whenever the backend is accurate
+/// to within an ulp.
+pub trait ContextFloat: Float {
+    #[inline]
+    fn add_ctx(&self, other: &Self, ctx: &MathContext) -> Self {
+        let (sum, error) = two_sum(*self, *other);
+        ctx.finish("add", round_directed(sum, error, self.is_finite() && other.is_finite(), ctx.rounding))
+    }
+    #[inline]
+    fn sub_ctx(&self, other: &Self, ctx: &MathContext) -> Self {
+        self.add_ctx(&-*other, ctx)
+    }
+    #[inline]
+    fn mul_ctx(&self, other: &Self, ctx: &MathContext) -> Self {
+        let product = *self * *other;
+        let finite = self.is_finite() && other.is_finite();
+
+        // the rounding error has the same sign after scaling both operands
+        // into [1, 2), where splitting them can not overflow
+        let error = if finite && product.is_normal() {
+            two_product(self.significand(), other.significand()).1
+        } else {
+            two_product(*self, *other).1
+        };
+        ctx.finish("mul", round_directed(product, error, finite, ctx.rounding))
+    }
+    #[inline]
+    fn div_ctx(&self, other: &Self, ctx: &MathContext) -> Self {
+        let quotient = *self / *other;
+        let zero = Self::from_f64(0.0);
+        let finite = self.is_finite() && other.is_finite() && *other != zero;
+
+        // the remainder self - quotient * other is exact and has the sign of
+        // the error times the sign of other; as for mul it is taken on the
+        // operands scaled into [1, 2)
+        let error = if finite && quotient.is_normal() {
+            let (a, b) = (self.significand(), other.significand());
+            let (product, product_error) = two_product(a / b, b);
+            let remainder = (a - product) - product_error;
+            if (remainder > zero) == (b > zero) { remainder.abs() } else { -remainder.abs() }
+        } else if finite && quotient.is_finite() {
+            let (product, product_error) = two_product(quotient, *other);
+            let remainder = (*self - product) - product_error;
+            if (remainder > zero) == (*other > zero) { remainder.abs() } else { -remainder.abs() }
+        } else {
+            zero
+        };
+        ctx.finish("div", round_directed(quotient, error, finite, ctx.rounding))
+    }
+    #[inline]
+    fn powi_ctx(&self, n: i32, ctx: &MathContext) -> Self {
+        ctx.finish("powi", widen_directed(self.powi(n), ctx.rounding))
+    }
+    #[inline]
+    fn powf_ctx(&self, n: &Self, ctx: &MathContext) -> Self {
+        let value = backend::powf_with(ctx.active_backend(), *self, *n);
+        ctx.finish("powf", widen_directed(value, ctx.rounding))
+    }
+    #[inline]
+    fn hypot_ctx(&self, other: &Self, ctx: &MathContext) -> Self {
+        let value = backend::hypot_with(ctx.active_backend(), *self, *other);
+        ctx.finish("hypot", widen_directed(value, ctx.rounding))
+    }
+    #[inline]
+    fn atan2_ctx(&self, other: &Self, ctx: &MathContext) -> Self {
+        let value = backend::atan2_with(ctx.active_backend(), *self, *other);
+        ctx.finish("atan2", widen_directed(value, ctx.rounding))
+    }
+
+    impl_unary_ctx!(exp_ctx => exp, Exp, exp2_ctx => exp2, Exp2, exp_m1_ctx => exp_m1, ExpM1,
+                    ln_ctx => ln, Ln, ln_1p_ctx => ln_1p, Ln1p, log2_ctx => log2, Log2,
+                    log10_ctx => log10, Log10, cbrt_ctx => cbrt, Cbrt,
+                    sin_ctx => sin, Sin, cos_ctx => cos, Cos, tan_ctx => tan, Tan,
+                    asin_ctx => asin, Asin, acos_ctx => acos, Acos, atan_ctx => atan, Atan,
+                    sinh_ctx => sinh, Sinh, cosh_ctx => cosh, Cosh, tanh_ctx => tanh, Tanh,
+                    asinh_ctx => asinh, Asinh, acosh_ctx => acosh, Acosh, atanh_ctx => atanh, Atanh);
+}
+
+impl<T: Float> ContextFloat for T {}
+
+
+/// Rounds `value + error`, where `value` is the nearest rounding and `error`
+/// the exact remainder, in the direction of `mode`.
+#[inline]
+fn round_directed<T: Float>(value: T, error: T, finite_inputs: bool, mode: RoundingMode) -> T {
+    let zero = T::from_f64(0.0);
+
+    if value.is_nan() {
+        return value;
+    } else if value.is_infinite() {
+        // an overflow of finite inputs may have to stop at the largest finite value
+        let negative = value < zero;
+        return if finite_inputs && !mode.overflows_to_infinity(negative) {
+            step(value, negative)
+        } else {
+            value
+        };
+    }
+
+    let up = match mode {
+        RoundingMode::NearestEven => return value,
+        RoundingMode::TowardPositive => error > zero,
+        RoundingMode::TowardNegative => false,
+        RoundingMode::TowardZero => value < zero && error > zero,
+    };
+    let down = match mode {
+        RoundingMode::TowardNegative => error < zero,
+        RoundingMode::TowardZero => value > zero && error < zero,
+        _ => false,
+    };
+
+    if up {
+        step(value, true)
+    } else if down {
+        step(value, false)
+    } else {
+        value
+    }
+}
+
+/// Moves a faithful `value` one ulp in the direction of `mode`.
+#[inline]
+fn widen_directed<T: Float>(value: T, mode: RoundingMode) -> T {
+    let zero = T::from_f64(0.0);
+
+    if value.is_nan() || value.is_infinite() {
+        return value;
+    }
+    match mode {
+        RoundingMode::NearestEven => value,
+        RoundingMode::TowardPositive => step(value, true),
+        RoundingMode::TowardNegative => step(value, false),
+        RoundingMode::TowardZero => if value == zero { value } else { step(value, value < zero) },
+    }
+}
+
+/// Returns the neighbour of `x` toward positive (`up`) or negative infinity.
+#[inline(always)]
+fn step<T: Float>(x: T, up: bool) -> T {
+    if up { x.next_up() } else { x.next_down() }
+}
@@ -0,0 +1,318 @@
+//! Exact decimal text of floats, for debugging and teaching.
+//!
+//! Every finite binary float is a decimal fraction with a finite number of
+//! digits. The shortest round-trip text that `Display` writes hides them,
+//! `Exact` writes them all.
+
+use core::cmp::Ordering;
+use core::{fmt, str};
+
+use collections::vec::Vec;
+
+use float::Float;
+
+
+/// Writes the exact decimal value of the float in positional notation.
+///
+/// NaN and the infinities are written like `Display` does.
+///
+/// ```
+/// use float::decimal::Exact;
+///
+/// assert_eq!(format!("{}", Exact(0.1_f64)), "0.1000000000000000055511151231257827021181583404541015625");
+/// assert_eq!(format!("{}", Exact(0.1_f32)), "0.100000001490116119384765625");
+/// assert_eq!(format!("{}", Exact(1e23_f64)), "99999999999999991611392");
+/// assert_eq!(format!("{}", Exact(-0.0_f64)), "-0");
+/// assert_eq!(format!("{}", Exact(-f32::INFINITY)), "-inf");
+///
+/// let tiny = format!("{}", Exact(5e-324_f64));
+/// assert_eq!(tiny.len(), 1076);
+/// assert!(tiny.starts_with("0.000") && tiny.ends_with("65625"));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Exact<T>(pub T);
+
+impl<T: Float> fmt::Display for Exact<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let x = self.0;
+        if x.is_nan() {
+            return f.write_str("NaN");
+        }
+        if x.is_sign_negative() {
+            f.write_str("-")?;
+        }
+        if x.is_infinite() {
+            return f.write_str("inf");
+        }
+
+        let (digits, point) = exact_digits(x);
+        if digits.is_empty() {
+            return f.write_str("0");
+        }
+
+        let length = digits.len() as isize;
+        if point <= 0 {
+            f.write_str("0.")?;
+            write_zeros(f, -point)?;
+            write_digits(f, &digits)
+        } else if point >= length {
+            write_digits(f, &digits)?;
+            write_zeros(f, point - length)
+        } else {
+            write_digits(f, &digits[..point as usize])?;
+            f.write_str(".")?;
+            write_digits(f, &digits[point as usize..])
+        }
+    }
+}
+
+
+/// The number of significant decimal digits in the shortest text that reads
+/// back to `x`, as written by `Display` or `LowerExp`. Zero for NaN and the
+/// infinities.
+///
+/// ```
+/// use float::decimal;
+///
+/// assert_eq!(decimal::digits_required(0.1_f64), 1);
+/// assert_eq!(decimal::digits_required(0.1_f64 + 0.2), 17);
+/// assert_eq!(decimal::digits_required(1.0_f32 / 3.0), 8);
+/// assert_eq!(decimal::digits_required(1e23_f64), 1);
+/// assert_eq!(decimal::digits_required(0.0_f64), 1);
+/// assert_eq!(decimal::digits_required(f64::NAN), 0);
+/// ```
+pub fn digits_required<T: Float + fmt::LowerExp>(x: T) -> u32 {
+    if !x.is_finite() {
+        return 0;
+    }
+
+    let mut counter = DigitCounter {
+        digits: 0,
+        in_exponent: false,
+    };
+    // always succeeds, the counter never fails
+    let _ = fmt::write(&mut counter, format_args!("{:e}", x));
+    counter.digits
+}
+
+
+/// Compares `x` exactly with the decimal number in `text`, for
+/// `Float::cmp_decimal_str`.
+pub(crate) fn cmp_decimal_str<T: Float>(x: T, text: &str) -> Option<Ordering> {
+    let (text_negative, text_digits, text_point) = parse_decimal(text.as_bytes())?;
+    if x.is_nan() {
+        return None;
+    } else if x.is_infinite() {
+        return Some(if x > T::from_f64(0.0) { Ordering::Greater } else { Ordering::Less });
+    }
+
+    let negative = x < T::from_f64(0.0);
+    let text_negative = text_negative && !text_digits.is_empty();
+    if negative != text_negative {
+        return Some(if negative { Ordering::Less } else { Ordering::Greater });
+    }
+
+    let (digits, point) = exact_digits(x);
+    let magnitude = match (digits.is_empty(), text_digits.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        // both are stripped of leading and trailing zeros, so the position
+        // of the point decides first and then the digits in order
+        (false, false) => point.cmp(&text_point).then(digits[..].cmp(&text_digits[..])),
+    };
+    Some(if negative { magnitude.reverse() } else { magnitude })
+}
+
+/// Parses `[+-] digits [. digits] [(e|E) [+-] digits]` into its sign, its
+/// significant digits and the position of the point before them, like
+/// `exact_digits`.
+fn parse_decimal(text: &[u8]) -> Option<(bool, Vec<u8>, isize)> {
+    // beyond this any float compares the same way
+    const EXPONENT_LIMIT: isize = 1_000_000;
+
+    let is_digit = |i: usize| i < text.len() && text[i] >= b'0' && text[i] <= b'9';
+    let mut i = 0;
+    let negative = match text.first() {
+        Some(&b'-') => { i += 1; true },
+        Some(&b'+') => { i += 1; false },
+        _ => false,
+    };
+
+    let mut digits = Vec::new();
+    let mut point = 0_isize;
+    while is_digit(i) {
+        digits.push(text[i]);
+        point += 1;
+        i += 1;
+    }
+    if i < text.len() && text[i] == b'.' {
+        i += 1;
+        while is_digit(i) {
+            digits.push(text[i]);
+            i += 1;
+        }
+    }
+    if digits.is_empty() {
+        return None;
+    }
+
+    if i < text.len() && (text[i] == b'e' || text[i] == b'E') {
+        i += 1;
+        let exponent_negative = match text.get(i) {
+            Some(&b'-') => { i += 1; true },
+            Some(&b'+') => { i += 1; false },
+            _ => false,
+        };
+        if !is_digit(i) {
+            return None;
+        }
+        let mut exponent = 0_isize;
+        while is_digit(i) {
+            exponent = (10 * exponent + (text[i] - b'0') as isize).min(EXPONENT_LIMIT);
+            i += 1;
+        }
+        point += if exponent_negative { -exponent } else { exponent };
+    }
+    if i != text.len() {
+        return None;
+    }
+
+    let leading = digits.iter().take_while(|&&c| c == b'0').count();
+    digits.drain(..leading);
+    point -= leading as isize;
+    while digits.last() == Some(&b'0') {
+        digits.pop();
+    }
+    Some((negative, digits, point))
+}
+
+/// The exact decimal digits of `|x|` for a finite `x`, without leading or
+/// trailing zeros, and the position of the point: the value is
+/// `0.d1 d2 ... * 10^point`. No digits for zero.
+fn exact_digits<T: Float>(x: T) -> (Vec<u8>, isize) {
+    let (mantissa, exponent, _) = x.integer_decode();
+    if mantissa == 0 {
+        return (Vec::new(), 0);
+    }
+
+    let mut limbs = Limbs::new(mantissa);
+    // m 2^-k = m 5^k / 10^k, so the digits of m 5^k with k of them after
+    // the point
+    let fraction_digits = if exponent >= 0 {
+        limbs.mul_pow(2, exponent as u32);
+        0
+    } else {
+        limbs.mul_pow(5, -exponent as u32);
+        -exponent as isize
+    };
+
+    let mut digits = limbs.digits();
+    let point = digits.len() as isize - fraction_digits;
+    while digits.last() == Some(&b'0') {
+        digits.pop();
+    }
+    (digits, point)
+}
+
+#[inline]
+fn write_digits(f: &mut fmt::Formatter, digits: &[u8]) -> fmt::Result {
+    f.write_str(str::from_utf8(digits).map_err(|_| fmt::Error)?)
+}
+
+#[inline]
+fn write_zeros(f: &mut fmt::Formatter, count: isize) -> fmt::Result {
+    for _ in 0..count {
+        f.write_str("0")?;
+    }
+    Ok(())
+}
+
+
+/// Counts the digits written before the exponent.
+struct DigitCounter {
+    digits: u32,
+    in_exponent: bool,
+}
+
+impl fmt::Write for DigitCounter {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        for c in text.bytes() {
+            if c == b'e' {
+                self.in_exponent = true;
+            } else if !self.in_exponent && c.is_ascii_digit() {
+                self.digits += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+
+const LIMB: u64 = 1_000_000_000;
+
+/// A natural number in base `10^9` limbs, least significant first.
+struct Limbs(Vec<u32>);
+
+impl Limbs {
+    fn new(n: u64) -> Self {
+        let mut limbs = Limbs(Vec::new());
+        let mut n = n;
+        while n > 0 {
+            limbs.0.push((n % LIMB) as u32);
+            n /= LIMB;
+        }
+        limbs
+    }
+
+    /// Multiplies by `base^power`, in the largest steps that fit a `u32`.
+    fn mul_pow(&mut self, base: u32, power: u32) {
+        let (step, step_power) = if base == 2 { (1 << 31, 31) } else { (1220703125, 13) };
+        let mut power = power;
+        while power >= step_power {
+            self.mul_small(step);
+            power -= step_power;
+        }
+        if power > 0 {
+            self.mul_small(base.pow(power));
+        }
+    }
+
+    fn mul_small(&mut self, factor: u32) {
+        let mut carry = 0;
+        for limb in self.0.iter_mut() {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = (product % LIMB) as u32;
+            carry = product / LIMB;
+        }
+        while carry > 0 {
+            self.0.push((carry % LIMB) as u32);
+            carry /= LIMB;
+        }
+    }
+
+    /// The decimal digits as ASCII, most significant first, `0` for zero.
+    fn digits(&self) -> Vec<u8> {
+        let mut digits = Vec::with_capacity(9 * self.0.len() + 1);
+        match self.0.last() {
+            Some(&top) => push_digits(&mut digits, top, false),
+            None => digits.push(b'0'),
+        }
+        for &limb in self.0.iter().rev().skip(1) {
+            push_digits(&mut digits, limb, true);
+        }
+        digits
+    }
+}
+
+#[inline]
+fn push_digits(digits: &mut Vec<u8>, limb: u32, padded: bool) {
+    let start = digits.len();
+    let mut limb = limb;
+    let mut count = 0;
+    while limb > 0 || (padded && count < 9) || count == 0 {
+        digits.insert(start, b'0' + (limb % 10) as u8);
+        limb /= 10;
+        count += 1;
+    }
+}
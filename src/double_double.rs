@@ -0,0 +1,223 @@
+use core::cmp::Ordering;
+use core::ops::{Add, Sub, Mul, Div, Neg};
+
+use expansion::{two_sum, fast_two_sum, two_product};
+use float::Float;
+use pow::MulIdentity;
+use rounding::RoundingMode;
+
+
+/// An unevaluated sum `hi + lo` of two `f64` with `|lo| <= ulp(hi) / 2`,
+/// giving about 106 bits of precision with the exponent range of `f64`.
+///
+/// The arithmetic follows the accurate variants of the QD library. Once the
+/// high part overflows or becomes NaN the low part is dropped, so non-finite
+/// values behave like their `f64` counterparts.
+///
+/// The layout is that of a C struct of `hi` and `lo`, see the `ffi` module.
+///
+/// ```
+/// use float::DoubleDouble;
+///
+/// let tiny = 2.0_f64.powi(-60);
+/// let x = DoubleDouble::from_f64(1.0) + DoubleDouble::from_f64(tiny);
+/// assert_eq!((x.hi(), x.lo()), (1.0, tiny));
+/// assert_eq!((x - DoubleDouble::from_f64(1.0)).to_f64(), tiny);
+///
+/// let third = DoubleDouble::from_f64(1.0) / DoubleDouble::from_f64(3.0);
+/// assert_eq!(third.hi(), 1.0 / 3.0);
+/// assert!(((third * DoubleDouble::from_f64(3.0)) - DoubleDouble::from_f64(1.0)).to_f64().abs() < 1e-31);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+pub struct DoubleDouble {
+    hi: f64,
+    lo: f64,
+}
+
+impl DoubleDouble {
+    /// Normalizes `hi + lo`, which must not overlap by more than a few bits.
+    #[inline]
+    pub fn new(hi: f64, lo: f64) -> Self {
+        let (hi, lo) = two_sum(hi, lo);
+        DoubleDouble::renormalized(hi, lo)
+    }
+
+    #[inline(always)]
+    pub fn from_f64(x: f64) -> Self {
+        DoubleDouble {
+            hi: x,
+            lo: 0.0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    #[inline(always)]
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    /// Rounds to the nearest `f64`.
+    #[inline(always)]
+    pub fn to_f64(&self) -> f64 {
+        self.hi + self.lo
+    }
+
+    /// Rounds to `f64` with `mode`, and returns whether the result is exact.
+    ///
+    /// ```
+    /// use float::{DoubleDouble, RoundingMode};
+    ///
+    /// let x = DoubleDouble::new(1.0, -1e-20);
+    /// assert_eq!(x.to_f64_rounded(RoundingMode::NearestEven), (1.0, false));
+    /// assert_eq!(x.to_f64_rounded(RoundingMode::TowardZero), (0.9999999999999999, false));
+    /// assert_eq!(x.to_f64_rounded(RoundingMode::TowardPositive), (1.0, false));
+    /// assert_eq!((-x).to_f64_rounded(RoundingMode::TowardNegative), (-1.0, false));
+    ///
+    /// // just below a power of two the low part can be the ulp below exactly
+    /// let y = DoubleDouble::new(1.0, -f64::EPSILON / 2.0);
+    /// assert_eq!(y.to_f64_rounded(RoundingMode::TowardNegative), (1.0 - f64::EPSILON / 2.0, true));
+    /// assert_eq!(DoubleDouble::from_f64(0.5).to_f64_rounded(RoundingMode::TowardZero), (0.5, true));
+    /// assert_eq!(DoubleDouble::new(f64::MAX, 1e290).to_f64_rounded(RoundingMode::TowardPositive), (f64::INFINITY, false));
+    /// ```
+    pub fn to_f64_rounded(&self, mode: RoundingMode) -> (f64, bool) {
+        let (hi, lo) = (self.hi, self.lo);
+        if hi.is_nan() {
+            return (hi, false);
+        }
+        if lo == 0.0 {
+            return (hi, true);
+        }
+
+        let rounded = match mode {
+            RoundingMode::NearestEven => hi + lo,
+            RoundingMode::TowardZero if (lo < 0.0) == (hi > 0.0) => {
+                if hi > 0.0 { Float::next_down(&hi) } else { Float::next_up(&hi) }
+            },
+            RoundingMode::TowardPositive if lo > 0.0 => Float::next_up(&hi),
+            RoundingMode::TowardNegative if lo < 0.0 => Float::next_down(&hi),
+            _ => hi,
+        };
+        // rounded and hi are neighbours, so their difference is exact
+        (rounded, rounded.is_finite() && rounded - hi == lo)
+    }
+
+    #[inline(always)]
+    fn renormalized(hi: f64, lo: f64) -> Self {
+        DoubleDouble {
+            hi: hi,
+            lo: if hi.is_finite() { lo } else { 0.0 },
+        }
+    }
+}
+
+impl From<f64> for DoubleDouble {
+    #[inline(always)]
+    fn from(x: f64) -> Self {
+        DoubleDouble::from_f64(x)
+    }
+}
+
+/// One, so `pow_usize` raises double-doubles by repeated squaring.
+///
+/// ```
+/// use float::{pow_usize, DoubleDouble};
+///
+/// let x = DoubleDouble::from_f64(1.0 + 2.0_f64.powi(-30));
+/// let square = pow_usize(x, 2);
+/// assert_eq!((square.hi(), square.lo()), (1.0 + 2.0_f64.powi(-29), 2.0_f64.powi(-60)));
+/// assert_eq!(pow_usize(x, 0), DoubleDouble::from_f64(1.0));
+/// ```
+impl MulIdentity for DoubleDouble {
+    #[inline(always)]
+    fn mul_identity() -> Self {
+        DoubleDouble::from_f64(1.0)
+    }
+}
+
+impl Default for DoubleDouble {
+    #[inline(always)]
+    fn default() -> Self {
+        DoubleDouble::from_f64(0.0)
+    }
+}
+
+impl PartialOrd for DoubleDouble {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.hi.partial_cmp(&other.hi) {
+            Some(Ordering::Equal) => self.lo.partial_cmp(&other.lo),
+            ordering => ordering,
+        }
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+
+    #[inline(always)]
+    fn neg(self) -> Self {
+        DoubleDouble {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        let (s, e) = two_sum(self.hi, other.hi);
+        let (t, f) = two_sum(self.lo, other.lo);
+        let (s, e) = fast_two_sum(s, e + t);
+        let (hi, lo) = fast_two_sum(s, e + f);
+        DoubleDouble::renormalized(hi, lo)
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, other: Self) -> Self {
+        let (p, e) = two_product(self.hi, other.hi);
+        let e = e + (self.hi * other.lo + self.lo * other.hi);
+        let (hi, lo) = fast_two_sum(p, e);
+        DoubleDouble::renormalized(hi, lo)
+    }
+}
+
+impl Div for DoubleDouble {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, other: Self) -> Self {
+        let q1 = self.hi / other.hi;
+        if !q1.is_finite() || q1 == 0.0 {
+            return DoubleDouble::from_f64(q1);
+        }
+
+        let r = self - other * DoubleDouble::from_f64(q1);
+        let q2 = r.hi / other.hi;
+        let r = r - other * DoubleDouble::from_f64(q2);
+        let q3 = r.hi / other.hi;
+
+        let (hi, lo) = fast_two_sum(q1, q2);
+        DoubleDouble::renormalized(hi, lo) + DoubleDouble::from_f64(q3)
+    }
+}
@@ -0,0 +1,1117 @@
+//! Streaming signal processing building blocks.
+
+use core::f64::consts::LN_10;
+
+use collections::vec::Vec;
+
+use expansion::TAU;
+use float::Float;
+
+
+/// Converts an amplitude ratio to decibels, `20 log10(|amplitude|)`. Silence
+/// maps to negative infinity.
+///
+/// ```
+/// use float::dsp;
+/// assert_eq!(dsp::amplitude_to_db(1.0_f64), 0.0);
+/// assert_eq!(dsp::amplitude_to_db(0.1_f32), -20.0);
+/// assert_eq!(dsp::amplitude_to_db(0.0_f64), -std::f64::INFINITY);
+/// ```
+#[inline]
+pub fn amplitude_to_db<T: Float>(amplitude: T) -> T {
+    T::from_f64(20.0) * amplitude.abs().log10()
+}
+
+/// Converts decibels to an amplitude ratio, `10^(db / 20)`.
+///
+/// ```
+/// use float::dsp;
+/// assert_eq!(dsp::db_to_amplitude(0.0_f64), 1.0);
+/// assert!((dsp::db_to_amplitude(-6.0_f64) - 0.5011872336272722).abs() < 1e-15);
+/// assert_eq!(dsp::db_to_amplitude(-std::f32::INFINITY), 0.0);
+/// ```
+#[inline]
+pub fn db_to_amplitude<T: Float>(db: T) -> T {
+    (db * T::from_f64(LN_10 / 20.0)).exp()
+}
+
+/// Converts a power ratio to decibels, `10 log10(power)`.
+#[inline]
+pub fn power_to_db<T: Float>(power: T) -> T {
+    T::from_f64(10.0) * power.log10()
+}
+
+/// Converts decibels to a power ratio, `10^(db / 10)`.
+#[inline]
+pub fn db_to_power<T: Float>(db: T) -> T {
+    (db * T::from_f64(LN_10 / 10.0)).exp()
+}
+
+
+/// Root mean square level over a sliding window of the last `window_len`
+/// samples.
+///
+/// The sum of squares is updated incrementally and recomputed from the
+/// window every `window_len` samples, so the error left behind when a loud
+/// passage leaves the window is gone a window later instead of accumulating.
+///
+/// ```
+/// use float::dsp::RmsMeter;
+///
+/// let mut meter = RmsMeter::new(4);
+/// for &x in [0.5_f64, -0.5, 0.5, -0.5, 0.5, -0.5].iter() {
+///     meter.push(x);
+/// }
+/// assert_eq!(meter.rms(), 0.5);
+/// assert!((meter.rms_db() + 6.020599913279624).abs() < 1e-12);
+///
+/// // a burst followed by silence decays to exactly zero
+/// meter.push(1e8);
+/// for _ in 0..4 {
+///     meter.push(0.0);
+/// }
+/// assert_eq!(meter.mean_square(), 0.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RmsMeter<T> {
+    squares: Vec<T>,
+    index: usize,
+    filled: usize,
+    sum: T,
+}
+
+impl<T: Float> RmsMeter<T> {
+    pub fn new(window_len: usize) -> Self {
+        assert!(window_len > 0, "meter window length must be non-zero");
+
+        let zero = T::from_f64(0.0);
+        RmsMeter {
+            squares: (0..window_len).map(|_| zero).collect(),
+            index: 0,
+            filled: 0,
+            sum: zero,
+        }
+    }
+
+    #[inline(always)]
+    pub fn window_len(&self) -> usize {
+        self.squares.len()
+    }
+
+    /// Feeds one sample and returns the updated RMS level.
+    #[inline]
+    pub fn push(&mut self, sample: T) -> T {
+        let square = sample * sample;
+        let old = self.squares[self.index];
+        self.squares[self.index] = square;
+        self.sum = self.sum + square - old;
+
+        self.index += 1;
+        if self.index == self.squares.len() {
+            self.index = 0;
+            self.sum = self.squares.iter().fold(T::from_f64(0.0), |sum, &x| sum + x);
+        }
+        if self.filled < self.squares.len() {
+            self.filled += 1;
+        }
+
+        self.rms()
+    }
+
+    /// The mean of the squared samples in the window, or over the samples
+    /// seen so far while the window is filling.
+    #[inline]
+    pub fn mean_square(&self) -> T {
+        let zero = T::from_f64(0.0);
+        if self.filled == 0 || self.sum < zero {
+            zero
+        } else {
+            self.sum / T::from_f64(self.filled as f64)
+        }
+    }
+
+    #[inline]
+    pub fn rms(&self) -> T {
+        self.mean_square().sqrt()
+    }
+
+    /// The RMS level in decibels relative to an amplitude of one.
+    #[inline]
+    pub fn rms_db(&self) -> T {
+        power_to_db(self.mean_square())
+    }
+
+    pub fn reset(&mut self) {
+        let zero = T::from_f64(0.0);
+        for x in self.squares.iter_mut() {
+            *x = zero;
+        }
+        self.index = 0;
+        self.filled = 0;
+        self.sum = zero;
+    }
+}
+
+
+/// Largest absolute sample over a sliding window of the last `window_len`
+/// samples, in amortized constant time per sample.
+///
+/// ```
+/// use float::dsp::PeakMeter;
+///
+/// let mut meter = PeakMeter::new(3);
+/// let peaks: Vec<f32> = [0.1, -0.9, 0.2, 0.3, 0.1, 0.0]
+///     .iter().map(|&x| meter.push(x)).collect();
+/// assert_eq!(peaks, [0.1, 0.9, 0.9, 0.9, 0.3, 0.3]);
+/// assert!((meter.peak_db() + 10.457575).abs() < 1e-5);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeakMeter<T> {
+    // candidates for the maximum as (sample index, level), decreasing in level
+    queue: Vec<(u64, T)>,
+    head: usize,
+    len: usize,
+    count: u64,
+}
+
+impl<T: Float> PeakMeter<T> {
+    pub fn new(window_len: usize) -> Self {
+        assert!(window_len > 0, "meter window length must be non-zero");
+
+        let zero = T::from_f64(0.0);
+        PeakMeter {
+            queue: (0..window_len).map(|_| (0, zero)).collect(),
+            head: 0,
+            len: 0,
+            count: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn window_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Feeds one sample and returns the updated peak level.
+    #[inline]
+    pub fn push(&mut self, sample: T) -> T {
+        let capacity = self.queue.len();
+        let level = sample.abs();
+
+        if self.len > 0 && self.queue[self.head].0 + capacity as u64 <= self.count {
+            self.head = (self.head + 1) % capacity;
+            self.len -= 1;
+        }
+        while self.len > 0 && self.queue[(self.head + self.len - 1) % capacity].1 <= level {
+            self.len -= 1;
+        }
+
+        self.queue[(self.head + self.len) % capacity] = (self.count, level);
+        self.len += 1;
+        self.count += 1;
+
+        self.peak()
+    }
+
+    /// The peak level of the window, zero before the first sample.
+    #[inline]
+    pub fn peak(&self) -> T {
+        if self.len == 0 { T::from_f64(0.0) } else { self.queue[self.head].1 }
+    }
+
+    /// The peak level in decibels relative to an amplitude of one.
+    #[inline]
+    pub fn peak_db(&self) -> T {
+        amplitude_to_db(self.peak())
+    }
+
+    pub fn reset(&mut self) {
+        self.head = 0;
+        self.len = 0;
+        self.count = 0;
+    }
+}
+
+
+/// Goertzel's algorithm: the energy of a single DFT bin, updated one sample
+/// at a time with one multiplication and two additions.
+///
+/// Every `block_len` samples `push` reports the power of the bin and starts a
+/// new block, which is how tone detectors such as DTMF decoders run. A pure
+/// tone of amplitude `a` at the bin frequency has a power of about
+/// `(a * block_len / 2)^2`.
+///
+/// ```
+/// use float::dsp::Goertzel;
+///
+/// // a DTMF "5" is 770 Hz plus 1336 Hz
+/// let tone = |n: usize| {
+///     let t = n as f64 / 8000.0;
+///     ((2.0 * std::f64::consts::PI * 770.0 * t).sin() +
+///      (2.0 * std::f64::consts::PI * 1336.0 * t).sin()) as f32
+/// };
+///
+/// let mut row = Goertzel::new(770.0_f32, 8000.0, 205);
+/// let mut other = Goertzel::new(852.0_f32, 8000.0, 205);
+///
+/// let mut detected = None;
+/// for n in 0..205 {
+///     let sample = tone(n);
+///     other.push(sample);
+///     detected = row.push(sample);
+/// }
+/// let power = detected.unwrap();
+/// assert!((power / (205.0 * 205.0 / 4.0) - 1.0).abs() < 0.05);
+/// assert!(other.power() < power / 100.0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Goertzel<T> {
+    coefficient: T,
+    cos: T,
+    sin: T,
+    block_len: usize,
+    count: usize,
+    s1: T,
+    s2: T,
+    power: T,
+}
+
+impl<T: Float> Goertzel<T> {
+    /// Detects `frequency` in a signal sampled at `sample_rate`, reporting a
+    /// power every `block_len` samples. The bin is not rounded to a multiple
+    /// of `sample_rate / block_len`.
+    pub fn new(frequency: T, sample_rate: T, block_len: usize) -> Self {
+        assert!(block_len > 0, "Goertzel block length must be non-zero");
+
+        let (sin, cos) = sin_cos_turns(frequency.to_f64() / sample_rate.to_f64());
+        let zero = T::from_f64(0.0);
+
+        Goertzel {
+            coefficient: T::from_f64(2.0 * cos),
+            cos: T::from_f64(cos),
+            sin: T::from_f64(sin),
+            block_len: block_len,
+            count: 0,
+            s1: zero,
+            s2: zero,
+            power: zero,
+        }
+    }
+
+    #[inline(always)]
+    pub fn block_len(&self) -> usize {
+        self.block_len
+    }
+
+    /// Feeds one sample, returning the power of the bin when it completes a
+    /// block.
+    #[inline]
+    pub fn push(&mut self, sample: T) -> Option<T> {
+        let s0 = sample + self.coefficient * self.s1 - self.s2;
+        self.s2 = self.s1;
+        self.s1 = s0;
+        self.count += 1;
+
+        if self.count == self.block_len {
+            self.power = self.current_power();
+            self.clear();
+            Some(self.power)
+        } else {
+            None
+        }
+    }
+
+    /// Feeds every sample of `samples`, returning the power of the last block
+    /// completed if any.
+    pub fn process(&mut self, samples: &[T]) -> Option<T> {
+        let mut power = None;
+        for &sample in samples {
+            if let Some(p) = self.push(sample) {
+                power = Some(p);
+            }
+        }
+        power
+    }
+
+    /// The power of the last completed block, zero before the first one.
+    #[inline(always)]
+    pub fn power(&self) -> T {
+        self.power
+    }
+
+    /// The power of the samples pushed since the last completed block.
+    #[inline]
+    pub fn current_power(&self) -> T {
+        self.s1 * self.s1 + self.s2 * self.s2 - self.coefficient * self.s1 * self.s2
+    }
+
+    /// The complex value of the bin for the samples pushed since the last
+    /// completed block, as `(real, imaginary)`, with the phase referred to
+    /// the last sample.
+    #[inline]
+    pub fn current_bin(&self) -> (T, T) {
+        (self.s1 - self.s2 * self.cos, self.s2 * self.sin)
+    }
+
+    /// Drops the partial block and the last reported power.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.clear();
+        self.power = T::from_f64(0.0);
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.s1 = T::from_f64(0.0);
+        self.s2 = T::from_f64(0.0);
+        self.count = 0;
+    }
+}
+
+
+/// `(sin, cos)` of `turns` full turns, reduced exactly to an octant before
+/// the argument is scaled by `2 pi`.
+fn sin_cos_turns(turns: f64) -> (f64, f64) {
+    let turns = turns - turns.floor();
+    let quadrant = (turns * 4.0 + 0.5).floor();
+    let offset = turns - quadrant * 0.25;
+    let x = offset * TAU.0 + offset * TAU.1;
+    let x2 = x * x;
+
+    // Taylor series, the error is below 1e-17 for |x| <= pi / 4
+    let mut sin = 0.0;
+    let mut cos = 0.0;
+    let mut k = 21.0_f64;
+    while k > 1.0 {
+        sin = 1.0 - sin * x2 / (k * (k - 1.0));
+        cos = 1.0 - cos * x2 / ((k - 1.0) * (k - 2.0));
+        k -= 2.0;
+    }
+    let sin = sin * x;
+
+    match quadrant as i32 & 3 {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        2 => (-sin, -cos),
+        _ => (-cos, sin),
+    }
+}
+
+
+/// Attack/release envelope follower: a one pole smoother of the absolute
+/// signal level whose time constant depends on whether the level rises or
+/// falls, the detector stage of compressors and limiters.
+///
+/// A time constant `t` seconds gives the coefficient `1 - e^(-1 / (t rate))`,
+/// computed with `exp_m1` so long time constants at high sample rates keep
+/// their precision. The envelope reaches `1 - 1/e` of a step after `t`.
+///
+/// ```
+/// use float::dsp::EnvelopeFollower;
+///
+/// let mut envelope = EnvelopeFollower::new(0.001_f64, 0.1, 48000.0);
+/// for _ in 0..48 {
+///     envelope.push(-1.0);
+/// }
+/// assert!((envelope.level() - (1.0 - (-1.0_f64).exp())).abs() < 1e-12);
+///
+/// for _ in 0..4800 {
+///     envelope.push(0.0);
+/// }
+/// assert!((envelope.level() / (1.0 - (-1.0_f64).exp()) - (-1.0_f64).exp()).abs() < 1e-12);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EnvelopeFollower<T> {
+    attack: T,
+    release: T,
+    level: T,
+}
+
+impl<T: Float> EnvelopeFollower<T> {
+    /// Takes the attack and release time constants in seconds. A time of
+    /// zero follows the signal instantly.
+    pub fn new(attack_time: T, release_time: T, sample_rate: T) -> Self {
+        EnvelopeFollower {
+            attack: smoothing_coefficient(attack_time, sample_rate),
+            release: smoothing_coefficient(release_time, sample_rate),
+            level: T::from_f64(0.0),
+        }
+    }
+
+    pub fn set_times(&mut self, attack_time: T, release_time: T, sample_rate: T) {
+        self.attack = smoothing_coefficient(attack_time, sample_rate);
+        self.release = smoothing_coefficient(release_time, sample_rate);
+    }
+
+    /// Feeds one sample and returns the updated envelope.
+    #[inline]
+    pub fn push(&mut self, sample: T) -> T {
+        let target = sample.abs();
+        let coefficient = if target > self.level { self.attack } else { self.release };
+        self.level = self.level + coefficient * (target - self.level);
+        self.level
+    }
+
+    /// Smooths `samples` in place into their envelope.
+    pub fn process(&mut self, samples: &mut [T]) {
+        for x in samples.iter_mut() {
+            *x = self.push(*x);
+        }
+    }
+
+    #[inline(always)]
+    pub fn level(&self) -> T {
+        self.level
+    }
+
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.level = T::from_f64(0.0);
+    }
+}
+
+
+#[inline]
+fn smoothing_coefficient<T: Float>(time: T, sample_rate: T) -> T {
+    let zero = T::from_f64(0.0);
+    if time > zero {
+        -(-(time * sample_rate).recip()).exp_m1()
+    } else {
+        T::from_f64(1.0)
+    }
+}
+
+
+/// Integrated loudness after ITU-R BS.1770 for a single channel, in LUFS.
+///
+/// The signal is K-weighted by the standard's high shelf and high-pass
+/// biquads, designed for the given sample rate with the analog parameters
+/// libebur128 uses, and its mean square taken over 400 ms blocks every
+/// 100 ms. `integrated` gates the blocks twice, dropping those below
+/// -70 LUFS and then those more than 10 dB below the mean of the rest.
+///
+/// This is the lite version: one channel, so no channel weights, and the
+/// gate keeps one value per block, ten a second, rather than a histogram.
+///
+/// ```
+/// use float::dsp::LoudnessMeter;
+///
+/// // a full scale 1 kHz sine reads -3.01 LUFS
+/// let tone = |n: usize| (2.0 * std::f64::consts::PI * 1000.0 * n as f64 / 48000.0).sin();
+/// let mut meter = LoudnessMeter::new(48000.0_f64);
+/// for n in 0..48000 * 10 {
+///     meter.push(tone(n));
+/// }
+/// assert!((meter.momentary() + 3.01).abs() < 0.01);
+/// assert!((meter.integrated() + 3.01).abs() < 0.01);
+///
+/// // silence falls below the absolute gate, only the blocks fading out count
+/// for _ in 0..48000 * 10 {
+///     meter.push(0.0);
+/// }
+/// assert_eq!(meter.momentary(), -std::f64::INFINITY);
+/// assert!((meter.integrated() + 3.01).abs() < 0.1);
+///
+/// // a passage 40 dB down falls below the relative gate
+/// let before = meter.integrated();
+/// for n in 0..48000 * 10 {
+///     meter.push(tone(n) * 0.01);
+/// }
+/// assert!((meter.momentary() + 43.01).abs() < 0.01);
+/// assert_eq!(meter.integrated(), before);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoudnessMeter<T> {
+    shelf: Biquad<T>,
+    high_pass: Biquad<T>,
+    hop_len: usize,
+    count: usize,
+    sum: T,
+    // the sums of squares of the last four 100 ms hops
+    hops: [T; 4],
+    filled: usize,
+    blocks: Vec<T>,
+}
+
+impl<T: Float> LoudnessMeter<T> {
+    pub fn new(sample_rate: T) -> Self {
+        let rate = sample_rate.to_f64();
+        let hop_len = Float::round(&(rate * 0.1)) as usize;
+        assert!(hop_len > 0, "loudness sample rate must be at least 5 Hz");
+
+        // the pre-filter, a high shelf of about +4 dB above 1.7 kHz
+        let (f0, gain, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+        let k = tan_half_turns(f0 / rate);
+        let vh = Float::exp(&(gain * LN_10 / 20.0));
+        let vb = Float::powf(&vh, &0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+        let shelf = Biquad::new([
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+            2.0 * (k * k - 1.0) / a0,
+            (1.0 - k / q + k * k) / a0,
+        ]);
+
+        // the RLB weighting, a second order high-pass at 38 Hz
+        let (f0, q) = (38.13547087602444, 0.5003270373238773);
+        let k = tan_half_turns(f0 / rate);
+        let a0 = 1.0 + k / q + k * k;
+        let high_pass = Biquad::new([
+            1.0,
+            -2.0,
+            1.0,
+            2.0 * (k * k - 1.0) / a0,
+            (1.0 - k / q + k * k) / a0,
+        ]);
+
+        let zero = T::from_f64(0.0);
+        LoudnessMeter {
+            shelf: shelf,
+            high_pass: high_pass,
+            hop_len: hop_len,
+            count: 0,
+            sum: zero,
+            hops: [zero; 4],
+            filled: 0,
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Feeds one sample, returning the momentary loudness when it completes
+    /// a block.
+    #[inline]
+    pub fn push(&mut self, sample: T) -> Option<T> {
+        let weighted = self.high_pass.process(self.shelf.process(sample));
+        self.sum = self.sum + weighted * weighted;
+        self.count += 1;
+
+        if self.count < self.hop_len {
+            return None;
+        }
+        self.hops = [self.hops[1], self.hops[2], self.hops[3], self.sum];
+        self.sum = T::from_f64(0.0);
+        self.count = 0;
+        if self.filled < 4 {
+            self.filled += 1;
+        }
+        if self.filled < 4 {
+            return None;
+        }
+
+        let power = self.block_power();
+        if power > absolute_gate() {
+            self.blocks.push(power);
+        }
+        Some(loudness(power))
+    }
+
+    /// Feeds every sample of `samples`, returning the momentary loudness of
+    /// the last block completed if any.
+    pub fn process(&mut self, samples: &[T]) -> Option<T> {
+        let mut momentary = None;
+        for &sample in samples {
+            if let Some(m) = self.push(sample) {
+                momentary = Some(m);
+            }
+        }
+        momentary
+    }
+
+    /// The loudness of the last 400 ms block, negative infinity before the
+    /// first one.
+    #[inline]
+    pub fn momentary(&self) -> T {
+        if self.filled < 4 { T::neg_infinity() } else { loudness(self.block_power()) }
+    }
+
+    /// The gated loudness of everything pushed since the start or the last
+    /// `reset`, negative infinity while no block passes the gates.
+    pub fn integrated(&self) -> T {
+        let mean = |threshold: T| {
+            let (sum, count) = self.blocks.iter()
+                .filter(|&&power| power > threshold)
+                .fold((T::from_f64(0.0), 0), |(sum, count), &power| (sum + power, count + 1));
+            if count == 0 { None } else { Some(sum / T::from_f64(count as f64)) }
+        };
+
+        match mean(absolute_gate()) {
+            // -10 dB relative to the absolutely gated loudness
+            Some(power) => mean(power * T::from_f64(0.1)).map_or(T::neg_infinity(), loudness),
+            None => T::neg_infinity(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        let zero = T::from_f64(0.0);
+        self.shelf.clear();
+        self.high_pass.clear();
+        self.count = 0;
+        self.sum = zero;
+        self.hops = [zero; 4];
+        self.filled = 0;
+        self.blocks.clear();
+    }
+
+    #[inline]
+    fn block_power(&self) -> T {
+        let sum = self.hops[0] + self.hops[1] + self.hops[2] + self.hops[3];
+        sum / T::from_f64((4 * self.hop_len) as f64)
+    }
+}
+
+
+/// A K-weighted mean square in LUFS.
+#[inline]
+fn loudness<T: Float>(power: T) -> T {
+    T::from_f64(-0.691) + power_to_db(power)
+}
+
+/// The mean square of a block at -70 LUFS.
+#[inline]
+fn absolute_gate<T: Float>() -> T {
+    db_to_power(T::from_f64(-70.0 + 0.691))
+}
+
+/// `tan(pi x)`, the prewarped frequency of the bilinear transform for
+/// `x = f0 / sample_rate`.
+#[inline]
+fn tan_half_turns(x: f64) -> f64 {
+    let (sin, cos) = sin_cos_turns(x * 0.5);
+    sin / cos
+}
+
+
+/// A biquad section in transposed direct form II, with the coefficients
+/// `[b0, b1, b2, a1, a2]` normalized to `a0 = 1`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Biquad<T> {
+    b0: T,
+    b1: T,
+    b2: T,
+    a1: T,
+    a2: T,
+    z1: T,
+    z2: T,
+}
+
+impl<T: Float> Biquad<T> {
+    #[inline]
+    fn new(coefficients: [f64; 5]) -> Self {
+        Biquad {
+            b0: T::from_f64(coefficients[0]),
+            b1: T::from_f64(coefficients[1]),
+            b2: T::from_f64(coefficients[2]),
+            a1: T::from_f64(coefficients[3]),
+            a2: T::from_f64(coefficients[4]),
+            z1: T::from_f64(0.0),
+            z2: T::from_f64(0.0),
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, x: T) -> T {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.z1 = T::from_f64(0.0);
+        self.z2 = T::from_f64(0.0);
+    }
+}
+
+
+/// Savitzky-Golay filter: the value or a derivative, at each sample, of the
+/// least squares polynomial of degree `ORDER` through a window of `WINDOW`
+/// samples centred on it.
+///
+/// The weights come from Gorry's Gram polynomial recurrences in `f64`,
+/// which stay accurate for long windows where solving the normal equations
+/// loses digits. They are computed once for every position in the window
+/// and kept in fixed arrays, so the filter needs no allocation and `apply`
+/// also fits the edges instead of padding the signal.
+///
+/// ```
+/// use float::dsp::SavitzkyGolay;
+///
+/// let smooth = SavitzkyGolay::<f64, 5, 2>::new(0);
+/// let expected = [-3.0 / 35.0, 12.0 / 35.0, 17.0 / 35.0, 12.0 / 35.0, -3.0 / 35.0];
+/// for (&c, &e) in smooth.coefficients().iter().zip(expected.iter()) {
+///     assert!((c - e).abs() < 1e-15);
+/// }
+///
+/// // the derivative of a quadratic sampled every 0.5 is exact, edges included
+/// let signal: Vec<f64> = (0..8).map(|i| { let x = i as f64 * 0.5; x * x - x }).collect();
+/// let mut slope = [0.0; 8];
+/// SavitzkyGolay::<f64, 5, 2>::new(1).apply(&signal, &mut slope, 0.5);
+/// for (i, &s) in slope.iter().enumerate() {
+///     assert!((s - (i as f64 - 1.0)).abs() < 1e-12);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SavitzkyGolay<T, const WINDOW: usize, const ORDER: usize> {
+    // one row of weights for every position in the window
+    weights: [[T; WINDOW]; WINDOW],
+    derivative: u32,
+}
+
+impl<T: Float, const WINDOW: usize, const ORDER: usize> SavitzkyGolay<T, WINDOW, ORDER> {
+    /// Builds the filter for an odd `WINDOW` and `derivative <= ORDER <
+    /// WINDOW`; `derivative` zero smooths.
+    pub fn new(derivative: u32) -> Self {
+        assert!(WINDOW % 2 == 1, "Savitzky-Golay window length must be odd");
+        assert!(ORDER < WINDOW, "Savitzky-Golay order must be below the window length");
+        assert!(derivative as usize <= ORDER, "Savitzky-Golay derivative must not exceed the order");
+
+        // (2k + 1) (2m)^(k) / (2m + k + 1)^(k + 1) with falling factorials,
+        // for the degrees 0..=ORDER, which fit in WINDOW entries
+        let m = (WINDOW / 2) as f64;
+        let mut factors = [0.0; WINDOW];
+        for (k, factor) in factors.iter_mut().enumerate().take(ORDER + 1) {
+            let k_f = k as f64;
+            *factor = (2.0 * k_f + 1.0) * falling_factorial(2.0 * m, k_f) /
+                falling_factorial(2.0 * m + k_f + 1.0, k_f + 1.0);
+        }
+        let mut values = [[0.0; WINDOW]; WINDOW];
+        let mut derivatives = [[0.0; WINDOW]; WINDOW];
+        for i in 0..WINDOW {
+            gram(i as f64 - m, m, ORDER, 0, &mut values[i]);
+            gram(i as f64 - m, m, ORDER, derivative, &mut derivatives[i]);
+        }
+
+        let mut weights = [[T::from_f64(0.0); WINDOW]; WINDOW];
+        for t in 0..WINDOW {
+            for i in 0..WINDOW {
+                let weight = (0..ORDER + 1).fold(0.0, |sum, k| {
+                    sum + factors[k] * values[i][k] * derivatives[t][k]
+                });
+                weights[t][i] = T::from_f64(weight);
+            }
+        }
+
+        SavitzkyGolay {
+            weights: weights,
+            derivative: derivative,
+        }
+    }
+
+    #[inline(always)]
+    pub fn window_len(&self) -> usize {
+        WINDOW
+    }
+
+    /// The weights for the centre of the window, for unit sample spacing.
+    #[inline]
+    pub fn coefficients(&self) -> &[T; WINDOW] {
+        &self.weights[WINDOW / 2]
+    }
+
+    /// Evaluates the filter at the centre of `window`, `WINDOW` samples
+    /// taken `spacing` apart.
+    #[inline]
+    pub fn at(&self, window: &[T; WINDOW], spacing: T) -> T {
+        dot(self.coefficients(), window) / spacing.powi(self.derivative as i32)
+    }
+
+    /// Filters `input` into `output`, fitting the first and last windows for
+    /// the samples within half a window of either end.
+    pub fn apply(&self, input: &[T], output: &mut [T], spacing: T) {
+        let n = input.len();
+        let half = WINDOW / 2;
+        assert!(output.len() == n, "output length differs from the input length");
+        assert!(n >= WINDOW, "signal shorter than the Savitzky-Golay window");
+
+        let scale = spacing.powi(self.derivative as i32);
+        let first = &input[..WINDOW];
+        let last = &input[n - WINDOW..];
+
+        for i in 0..half {
+            output[i] = dot(&self.weights[i], first) / scale;
+            output[n - half + i] = dot(&self.weights[half + 1 + i], last) / scale;
+        }
+        for i in half..n - half {
+            output[i] = dot(self.coefficients(), &input[i - half..i + half + 1]) / scale;
+        }
+    }
+}
+
+
+#[inline]
+fn dot<T: Float>(a: &[T], b: &[T]) -> T {
+    a.iter().zip(b.iter()).fold(T::from_f64(0.0), |sum, (&x, &y)| sum + x * y)
+}
+
+/// The `derivative` of the Gram polynomials of degrees `0..=order` over the
+/// `2m + 1` points `-m..=m`, at `x`, after Gorry (1990), written to the
+/// front of `current`, which must hold more than `order` entries.
+fn gram<const WINDOW: usize>(x: f64, m: f64, order: usize, derivative: u32, current: &mut [f64; WINDOW]) {
+    let mut previous = [0.0; WINDOW];
+
+    for s in 0..derivative + 1 {
+        current[0] = if s == 0 { 1.0 } else { 0.0 };
+        for k in 1..order + 1 {
+            let kf = k as f64;
+            let a = (4.0 * kf - 2.0) / (kf * (2.0 * m - kf + 1.0));
+            let b = ((kf - 1.0) * (2.0 * m + kf)) / (kf * (2.0 * m - kf + 1.0));
+            let below = if k >= 2 { current[k - 2] } else { 0.0 };
+            current[k] = a * (x * current[k - 1] + s as f64 * previous[k - 1]) - b * below;
+        }
+        previous = *current;
+    }
+}
+
+/// `a (a - 1) ... (a - b + 1)`.
+#[inline]
+fn falling_factorial(a: f64, b: f64) -> f64 {
+    let mut product = 1.0;
+    let mut j = 0.0;
+    while j < b {
+        product *= a - j;
+        j += 1.0;
+    }
+    product
+}
+
+
+/// Finds the local maxima of `values` whose prominence is at least
+/// `min_prominence`, thinned so that no two are closer than `min_distance`
+/// samples, writes their indices in increasing order into `peaks` and
+/// returns how many were found.
+///
+/// A flat top counts once, at its middle sample, and the ends of the signal
+/// are never peaks. When peaks are too close the higher one is kept. NaN
+/// samples are treated as missing: they are never peaks and are skipped over
+/// when comparing neighbours and measuring prominence. `peaks` must have
+/// room for `values.len() / 2` indices, the most local maxima a signal can
+/// have.
+///
+/// ```
+/// use float::dsp;
+///
+/// let signal = [0.0_f64, 2.0, 1.0, 3.0, 1.0, 1.0, 5.0, 5.0, 5.0, 0.0, f64::NAN, 4.0, 0.0];
+/// let mut peaks = [0; 6];
+///
+/// let count = dsp::find_peaks(&signal, 0.0, 0, &mut peaks);
+/// assert_eq!(&peaks[..count], &[1, 3, 7, 11]);
+///
+/// let count = dsp::find_peaks(&signal, 1.5, 0, &mut peaks);
+/// assert_eq!(&peaks[..count], &[3, 7, 11]);
+///
+/// let count = dsp::find_peaks(&signal, 0.0, 5, &mut peaks);
+/// assert_eq!(&peaks[..count], &[1, 7]);
+/// ```
+pub fn find_peaks<T: Float>(values: &[T], min_prominence: T, min_distance: usize, peaks: &mut [usize]) -> usize {
+    let n = values.len();
+    assert!(peaks.len() >= n / 2, "peak buffer smaller than half the signal length");
+
+    let mut count = 0;
+    let mut previous = None;
+    let mut i = 0;
+
+    while i < n {
+        let x = values[i];
+        if x.is_nan() {
+            i += 1;
+            continue;
+        }
+
+        // the plateau of samples equal to x, and the next different sample
+        let mut end = i;
+        let mut next = i + 1;
+        while next < n && (values[next].is_nan() || values[next] == x) {
+            if values[next] == x {
+                end = next;
+            }
+            next += 1;
+        }
+
+        let rises = match previous {
+            Some(p) => p < x,
+            None => false,
+        };
+        if rises && next < n && values[next] < x {
+            let mut peak = i + (end - i) / 2;
+            while values[peak].is_nan() {
+                peak -= 1;
+            }
+            if peak_prominence(values, peak) >= min_prominence {
+                peaks[count] = peak;
+                count += 1;
+            }
+        }
+
+        previous = Some(x);
+        i = next;
+    }
+
+    if min_distance > 1 && count > 1 {
+        count = thin_peaks(values, &mut peaks[..count], min_distance);
+    }
+    count
+}
+
+/// The prominence of the sample at `peak`: its height above the higher of
+/// the lowest points between it and the nearest higher sample on either
+/// side, or the end of the signal. NaN samples are skipped.
+///
+/// ```
+/// use float::dsp;
+/// let signal = [1.0_f32, 4.0, 2.0, 3.0, 0.0];
+/// assert_eq!(dsp::peak_prominence(&signal, 1), 3.0);
+/// assert_eq!(dsp::peak_prominence(&signal, 3), 1.0);
+/// ```
+pub fn peak_prominence<T: Float>(values: &[T], peak: usize) -> T {
+    let height = values[peak];
+
+    let mut left = height;
+    for &x in values[..peak].iter().rev() {
+        if x > height {
+            break;
+        }
+        if x < left {
+            left = x;
+        }
+    }
+
+    let mut right = height;
+    for &x in values[peak + 1..].iter() {
+        if x > height {
+            break;
+        }
+        if x < right {
+            right = x;
+        }
+    }
+
+    height - if left > right { left } else { right }
+}
+
+/// Drops the lower of any peaks closer than `min_distance`, highest first,
+/// and compacts the survivors to the front of `peaks`.
+fn thin_peaks<T: Float>(values: &[T], peaks: &mut [usize], min_distance: usize) -> usize {
+    let count = peaks.len();
+    let mut order: Vec<usize> = (0..count).collect();
+    order.sort_by(|&a, &b| {
+        values[peaks[b]].partial_cmp(&values[peaks[a]]).unwrap()
+    });
+
+    let mut keep: Vec<bool> = (0..count).map(|_| true).collect();
+    for &i in order.iter() {
+        if !keep[i] {
+            continue;
+        }
+        let mut j = i;
+        while j > 0 && peaks[i] - peaks[j - 1] < min_distance {
+            j -= 1;
+            keep[j] = false;
+        }
+        let mut j = i + 1;
+        while j < count && peaks[j] - peaks[i] < min_distance {
+            keep[j] = false;
+            j += 1;
+        }
+    }
+
+    let mut kept = 0;
+    for i in 0..count {
+        if keep[i] {
+            peaks[kept] = peaks[i];
+            kept += 1;
+        }
+    }
+    kept
+}
+
+
+/// Dynamic time warping distance between `a` and `b` with a Sakoe-Chiba
+/// band: the smallest sum of `|a[i] - b[j]|` along a monotone alignment of
+/// the two sequences that stays within `band` samples of the diagonal.
+///
+/// For sequences of different lengths the band follows the stretched
+/// diagonal and is widened just enough to keep it connected. Only two rows
+/// of the band are kept, so memory and time are `O(band)` and
+/// `O(n band)`. The distance is NaN if either sequence contains a NaN and
+/// infinite if exactly one of them is empty.
+///
+/// ```
+/// use float::dsp;
+///
+/// let gesture = [0.0_f32, 1.0, 2.0, 3.0, 2.0, 1.0, 0.0];
+/// let slower = [0.0_f32, 0.0, 1.0, 2.0, 3.0, 3.0, 2.0, 1.0, 0.0];
+/// assert_eq!(dsp::dtw_distance(&gesture, &slower, 2), 0.0);
+///
+/// // without warping it is the L1 distance
+/// assert_eq!(dsp::dtw_distance(&[1.0_f64, 2.0, 3.0], &[2.0, 2.0, 2.0], 0), 2.0);
+/// ```
+pub fn dtw_distance<T: Float>(a: &[T], b: &[T], band: usize) -> T {
+    let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 {
+        return if n == m { T::from_f64(0.0) } else { T::infinity() };
+    }
+    if a.iter().chain(b.iter()).any(|x| x.is_nan()) {
+        return T::nan();
+    }
+
+    let infinity = T::infinity();
+    let mut previous: Vec<T> = Vec::new();
+    let mut current: Vec<T> = Vec::new();
+    let mut previous_low = 0;
+
+    for (i, &x) in a.iter().enumerate() {
+        let (low, high) = dtw_window(i, n, m, band);
+        current.clear();
+
+        for j in low..high + 1 {
+            let above = if i > 0 && j >= previous_low && j < previous_low + previous.len() {
+                previous[j - previous_low]
+            } else {
+                infinity
+            };
+            let diagonal = if i > 0 && j > previous_low && j <= previous_low + previous.len() {
+                previous[j - 1 - previous_low]
+            } else {
+                infinity
+            };
+            let left = if j > low { current[j - 1 - low] } else { infinity };
+
+            let mut best = if above < left { above } else { left };
+            if diagonal < best {
+                best = diagonal;
+            }
+            if i == 0 && j == 0 {
+                best = T::from_f64(0.0);
+            }
+            current.push((x - b[j]).abs() + best);
+        }
+
+        previous_low = low;
+        ::core::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[previous.len() - 1]
+}
+
+/// The columns `low..=high` of row `i` inside the band.
+#[inline]
+fn dtw_window(i: usize, n: usize, m: usize, band: usize) -> (usize, usize) {
+    if n == 1 {
+        return (0, m - 1);
+    }
+    let diagonal = |i: usize| ((i as u64 * (m - 1) as u64) / (n - 1) as u64) as usize;
+
+    let centre = diagonal(i);
+    let low = centre.saturating_sub(band);
+    let mut high = centre + band;
+    if i + 1 < n {
+        // reach the start of the next row's window
+        let next = diagonal(i + 1).saturating_sub(band + 1);
+        if next > high {
+            high = next;
+        }
+    }
+    (low, if high < m { high } else { m - 1 })
+}
@@ -0,0 +1,188 @@
+//! Exact arithmetic on floating-point expansions.
+//!
+//! An expansion is a slice of non-overlapping components sorted by increasing
+//! magnitude whose exact sum is the represented value, following Shewchuk's
+//! "Adaptive Precision Floating-Point Arithmetic and Fast Robust Geometric
+//! Predicates". All routines write into caller provided buffers and return the
+//! number of components produced; zero components are eliminated.
+
+use float::Float;
+
+
+/// `2 pi` as the unevaluated sum `hi + lo` of two `f64`, the high part
+/// correctly rounded, for reducing turns and radians past `f64` precision.
+pub(crate) const TAU: (f64, f64) = (2.0 * ::core::f64::consts::PI, 2.4492935982947064e-16);
+
+
+/// Returns `(s, e)` such that `s = fl(a + b)` and `a + b = s + e` exactly.
+#[inline]
+pub fn two_sum<T: Float>(a: T, b: T) -> (T, T) {
+    let s = a + b;
+    let bv = s - a;
+    let av = s - bv;
+    (s, (a - av) + (b - bv))
+}
+
+/// Same as `two_sum` but requires `|a| >= |b|`.
+#[inline]
+pub fn fast_two_sum<T: Float>(a: T, b: T) -> (T, T) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+/// Splits `a` into two halves `(hi, lo)` of at most `mantissa_digits / 2` bits each.
+#[inline]
+// `u32::div_ceil` needs Rust 1.73
+#[allow(clippy::manual_div_ceil)]
+pub fn split<T: Float>(a: T) -> (T, T) {
+    let splitter = T::from_f64(2.0).powi(((T::mantissa_digits() + 1) / 2) as i32) + T::from_f64(1.0);
+    let c = splitter * a;
+    let hi = c - (c - a);
+    (hi, a - hi)
+}
+
+/// Returns `(p, e)` such that `p = fl(a * b)` and `a * b = p + e` exactly.
+///
+/// ```
+/// use float::expansion;
+/// let (p, e) = expansion::two_product(0.1_f64, 0.1_f64);
+/// assert_eq!(p, 0.1_f64 * 0.1_f64);
+/// assert!(e != 0.0);
+/// ```
+#[inline]
+pub fn two_product<T: Float>(a: T, b: T) -> (T, T) {
+    let p = a * b;
+    let (ahi, alo) = split(a);
+    let (bhi, blo) = split(b);
+    let err = p - ahi * bhi - alo * bhi - ahi * blo;
+    (p, alo * blo - err)
+}
+
+/// Adds `b` to the expansion `e`, writing the result to `h`.
+///
+/// `h` must hold at least `e.len() + 1` components.
+///
+/// ```
+/// use float::expansion;
+/// let mut h = [0.0_f64; 2];
+/// let n = expansion::grow(&[1.0], 1e-30, &mut h);
+/// assert_eq!(&h[..n], &[1e-30, 1.0]);
+/// ```
+pub fn grow<T: Float>(e: &[T], b: T, h: &mut [T]) -> usize {
+    assert!(h.len() > e.len(), "output buffer too small for expansion");
+
+    let zero = T::from_f64(0.0);
+    let mut q = b;
+    let mut n = 0;
+
+    for &enow in e {
+        let (sum, err) = two_sum(q, enow);
+        q = sum;
+        if err != zero {
+            h[n] = err;
+            n += 1;
+        }
+    }
+    if q != zero || n == 0 {
+        h[n] = q;
+        n += 1;
+    }
+    n
+}
+
+/// Multiplies the expansion `e` by `b`, writing the result to `h`.
+///
+/// `h` must hold at least `2 * e.len()` components.
+///
+/// ```
+/// use float::expansion;
+/// let mut h = [0.0_f64; 2];
+/// let n = expansion::scale(&[0.1], 3.0, &mut h);
+/// assert_eq!(n, 2);
+/// assert_eq!(expansion::estimate(&h[..n]), 0.1 * 3.0);
+/// ```
+pub fn scale<T: Float>(e: &[T], b: T, h: &mut [T]) -> usize {
+    assert!(h.len() >= 2 * e.len(), "output buffer too small for expansion");
+
+    let zero = T::from_f64(0.0);
+    let mut n = 0;
+
+    if e.is_empty() {
+        return n;
+    }
+
+    let (mut q, err) = two_product(e[0], b);
+    if err != zero {
+        h[n] = err;
+        n += 1;
+    }
+    for &enow in &e[1..] {
+        let (product1, product0) = two_product(enow, b);
+        let (sum, err) = two_sum(q, product0);
+        if err != zero {
+            h[n] = err;
+            n += 1;
+        }
+        let (sum, err) = fast_two_sum(product1, sum);
+        q = sum;
+        if err != zero {
+            h[n] = err;
+            n += 1;
+        }
+    }
+    if q != zero || n == 0 {
+        h[n] = q;
+        n += 1;
+    }
+    n
+}
+
+/// Compresses the expansion `e` in place so that its largest component is a
+/// good approximation of the whole value, returning the new length.
+///
+/// ```
+/// use float::expansion;
+/// let mut e = [1e-30_f64, 1e-20, 1.0];
+/// let n = expansion::compress(&mut e);
+/// assert_eq!(e[n - 1], 1.0);
+/// ```
+pub fn compress<T: Float>(e: &mut [T]) -> usize {
+    let zero = T::from_f64(0.0);
+
+    if e.is_empty() {
+        return 0;
+    }
+
+    let mut bottom = e.len() - 1;
+    let mut q = e[bottom];
+
+    for index in (0..bottom).rev() {
+        let (sum, err) = fast_two_sum(q, e[index]);
+        if err != zero {
+            e[bottom] = sum;
+            bottom -= 1;
+            q = err;
+        } else {
+            q = sum;
+        }
+    }
+    e[bottom] = q;
+
+    let mut top = 0;
+    for index in (bottom + 1)..e.len() {
+        let (sum, err) = fast_two_sum(e[index], q);
+        if err != zero {
+            e[top] = err;
+            top += 1;
+        }
+        q = sum;
+    }
+    e[top] = q;
+    top + 1
+}
+
+/// Returns the floating-point approximation of the expansion `e`.
+#[inline]
+pub fn estimate<T: Float>(e: &[T]) -> T {
+    e.iter().fold(T::from_f64(0.0), |acc, &c| acc + c)
+}
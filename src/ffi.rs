@@ -0,0 +1,98 @@
+//! A C API for the wrapper types, enabled by the `ffi` feature.
+//!
+//! `DoubleDouble` is the only wrapper type the crate has; there is no
+//! half-precision or interval type to export, so the API covers
+//! double-double arithmetic alone.
+//!
+//! `DoubleDouble` has the layout of `struct { double hi; double lo; }` and is
+//! passed by value. C code should build values with `float_dd_new` or
+//! `float_dd_from_double` rather than filling in the fields, so the parts
+//! stay normalized. The names follow cbindgen, and `cbindgen.toml` at the
+//! crate root writes the header:
+//!
+//! ```sh
+//! cbindgen --config cbindgen.toml --output float.h
+//! ```
+//!
+//! ```
+//! use float::ffi;
+//!
+//! let third = ffi::float_dd_div(ffi::float_dd_from_double(1.0), ffi::float_dd_from_double(3.0));
+//! let one = ffi::float_dd_mul(third, ffi::float_dd_from_double(3.0));
+//! assert!(ffi::float_dd_to_double(ffi::float_dd_sub(one, ffi::float_dd_from_double(1.0))).abs() < 1e-31);
+//!
+//! let x = ffi::float_dd_new(1.0, 2.0_f64.powi(-60));
+//! assert_eq!(ffi::float_dd_cmp(x, ffi::float_dd_from_double(1.0)), 1);
+//! assert_eq!(ffi::float_dd_cmp(ffi::float_dd_neg(x), x), -1);
+//! assert_eq!(ffi::float_dd_cmp(ffi::float_dd_from_double(::std::f64::NAN), x), 2);
+//! assert_eq!((ffi::float_dd_hi(x), ffi::float_dd_lo(x)), (1.0, 2.0_f64.powi(-60)));
+//! ```
+
+use core::cmp::Ordering;
+
+use double_double::DoubleDouble;
+
+
+/// `hi + lo` normalized, the parts must not overlap by more than a few bits.
+#[no_mangle]
+pub extern "C" fn float_dd_new(hi: f64, lo: f64) -> DoubleDouble {
+    DoubleDouble::new(hi, lo)
+}
+
+#[no_mangle]
+pub extern "C" fn float_dd_from_double(x: f64) -> DoubleDouble {
+    DoubleDouble::from_f64(x)
+}
+
+/// Rounds to the nearest `double`.
+#[no_mangle]
+pub extern "C" fn float_dd_to_double(x: DoubleDouble) -> f64 {
+    x.to_f64()
+}
+
+#[no_mangle]
+pub extern "C" fn float_dd_hi(x: DoubleDouble) -> f64 {
+    x.hi()
+}
+
+#[no_mangle]
+pub extern "C" fn float_dd_lo(x: DoubleDouble) -> f64 {
+    x.lo()
+}
+
+#[no_mangle]
+pub extern "C" fn float_dd_add(a: DoubleDouble, b: DoubleDouble) -> DoubleDouble {
+    a + b
+}
+
+#[no_mangle]
+pub extern "C" fn float_dd_sub(a: DoubleDouble, b: DoubleDouble) -> DoubleDouble {
+    a - b
+}
+
+#[no_mangle]
+pub extern "C" fn float_dd_mul(a: DoubleDouble, b: DoubleDouble) -> DoubleDouble {
+    a * b
+}
+
+#[no_mangle]
+pub extern "C" fn float_dd_div(a: DoubleDouble, b: DoubleDouble) -> DoubleDouble {
+    a / b
+}
+
+#[no_mangle]
+pub extern "C" fn float_dd_neg(x: DoubleDouble) -> DoubleDouble {
+    -x
+}
+
+/// `-1`, `0` or `1` as `a` is less than, equal to or greater than `b`, and
+/// `2` when either is NaN.
+#[no_mangle]
+pub extern "C" fn float_dd_cmp(a: DoubleDouble, b: DoubleDouble) -> i32 {
+    match a.partial_cmp(&b) {
+        Some(Ordering::Less) => -1,
+        Some(Ordering::Equal) => 0,
+        Some(Ordering::Greater) => 1,
+        None => 2,
+    }
+}
@@ -0,0 +1,67 @@
+//! The `Float` math functions exported under their C names, so the crate can
+//! stand in for the C math library of other languages or of targets that
+//! have none.
+//!
+//! The `ffi-export` feature turns on `libm` as well: the exported `cbrt`
+//! must not be implemented by calling the C `cbrt` it replaces.
+//!
+//! ```
+//! extern "C" {
+//!     fn cbrt(x: f64) -> f64;
+//!     fn hypotf(x: f32, y: f32) -> f32;
+//!     fn fma(x: f64, y: f64, z: f64) -> f64;
+//! }
+//!
+//! unsafe {
+//!     assert_eq!(cbrt(-27.0), -3.0);
+//!     assert_eq!(hypotf(3.0, 4.0), 5.0);
+//!     assert_eq!(fma(0.1, 10.0, -1.0), 5.551115123125783e-17);
+//! }
+//! ```
+
+use float::Float;
+
+
+macro_rules! export_unary {
+    ($T:ty, $($name:ident => $method:ident),*) => ($(
+        #[no_mangle]
+        pub extern "C" fn $name(x: $T) -> $T {
+            Float::$method(&x)
+        }
+    )*)
+}
+
+macro_rules! export_binary {
+    ($T:ty, $($name:ident => $method:ident),*) => ($(
+        #[no_mangle]
+        pub extern "C" fn $name(x: $T, y: $T) -> $T {
+            Float::$method(&x, &y)
+        }
+    )*)
+}
+
+export_unary!(f32, acosf => acos, acoshf => acosh, asinf => asin, asinhf => asinh, atanf => atan,
+              atanhf => atanh, cbrtf => cbrt, ceilf => ceil, cosf => cos, coshf => cosh, expf => exp,
+              exp2f => exp2, expm1f => exp_m1, floorf => floor, logf => ln, log10f => log10,
+              log1pf => ln_1p, log2f => log2, roundf => round, sinf => sin, sinhf => sinh,
+              sqrtf => sqrt, tanf => tan, tanhf => tanh, truncf => trunc);
+export_binary!(f32, atan2f => atan2, copysignf => copysign, fmaxf => max, fminf => min,
+               hypotf => hypot, nextafterf => next_after, powf => powf);
+
+export_unary!(f64, acos => acos, acosh => acosh, asin => asin, asinh => asinh, atan => atan,
+              atanh => atanh, cbrt => cbrt, ceil => ceil, cos => cos, cosh => cosh, exp => exp,
+              exp2 => exp2, expm1 => exp_m1, floor => floor, log => ln, log10 => log10,
+              log1p => ln_1p, log2 => log2, round => round, sin => sin, sinh => sinh, sqrt => sqrt,
+              tan => tan, tanh => tanh, trunc => trunc);
+export_binary!(f64, atan2 => atan2, copysign => copysign, fmax => max, fmin => min, hypot => hypot,
+               nextafter => next_after, pow => powf);
+
+#[no_mangle]
+pub extern "C" fn fmaf(x: f32, y: f32, z: f32) -> f32 {
+    Float::mul_add(&x, &y, &z)
+}
+
+#[no_mangle]
+pub extern "C" fn fma(x: f64, y: f64, z: f64) -> f64 {
+    Float::mul_add(&x, &y, &z)
+}
@@ -1,42 +1,93 @@
+use core::cmp::Ordering;
 use core::num::FpCategory;
-use core::{mem, intrinsics, f32, f64};
+use core::fmt;
+use core::hash::Hash;
+use core::ops::{Add, Sub, Mul, Div, Rem, Neg, BitAnd, BitOr, BitXor, Not, Shl, Shr};
+use core::{mem, f32, f64};
 
 use approx_eq::ApproxEq;
 use signed::Signed;
 
-use libc::{c_float, c_double};
+#[cfg(feature = "nan-debug")]
+use nan_debug;
+use decimal;
+use double_double::DoubleDouble;
+use expansion::{two_sum, two_product};
+use float_key::total_order_key;
+use float_parts::{self, FloatParts, FloatFields};
+use math;
+use pow::{MulIdentity, pow_usize};
 
-#[link_name = "m"]
-extern {
-    pub fn cbrtf(n: c_float) -> c_float;
-    pub fn expm1f(n: c_float) -> c_float;
-    pub fn hypotf(x: c_float, y: c_float) -> c_float;
-    pub fn log1pf(n: c_float) -> c_float;
 
-    pub fn cbrt(n: c_double) -> c_double;
-    pub fn expm1(n: c_double) -> c_double;
-    pub fn hypot(x: c_double, y: c_double) -> c_double;
-    pub fn log1p(n: c_double) -> c_double;
-}
-
-
-pub trait Float: ApproxEq + Signed {
+pub trait Float: Copy + PartialOrd
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+    + Div<Output = Self> + Rem<Output = Self> + Neg<Output = Self>
+    + ApproxEq + Signed
+{
+    fn from_f64(n: f64) -> Self;
+    fn to_f64(&self) -> f64;
+    fn mantissa_digits() -> u32;
+    fn min_exp() -> i32;
+    fn max_exp() -> i32;
     fn nan() -> Self;
     fn infinity() -> Self;
     fn neg_infinity() -> Self;
     fn neg_zero() -> Self;
     fn epsilon() -> Self;
+    /// Mathematical constants, correctly rounded to the type.
+    fn pi() -> Self;
+    fn tau() -> Self;
+    fn e() -> Self;
+    fn sqrt_2() -> Self;
+    fn frac_1_sqrt_2() -> Self;
+    fn ln_2() -> Self;
+    fn ln_10() -> Self;
+    fn log2_e() -> Self;
+    fn log10_e() -> Self;
+    fn frac_1_pi() -> Self;
+    fn frac_2_pi() -> Self;
+    fn frac_pi_2() -> Self;
+    fn frac_pi_4() -> Self;
     fn is_nan(&self) -> bool;
     fn is_infinite(&self) -> bool;
     fn is_finite(&self) -> bool;
     fn is_normal(&self) -> bool;
+    /// Non-zero with the exponent field clear, below the smallest normal in
+    /// magnitude.
+    fn is_subnormal(&self) -> bool;
     fn classify(&self) -> FpCategory;
     fn trunc(&self) -> Self;
+    fn floor(&self) -> Self;
+    fn ceil(&self) -> Self;
+    /// Rounds to the nearest integer, halfway cases away from zero.
+    fn round(&self) -> Self;
+    /// Rounds to the nearest integer, halfway cases to the even one.
+    fn round_ties_even(&self) -> Self;
     fn fract(&self) -> Self;
     fn is_sign_positive(&self) -> bool;
     fn is_sign_negative(&self) -> bool;
+    /// The magnitude of `self` with the sign bit of `sign`, NaNs included.
+    fn copysign(&self, sign: &Self) -> Self;
+    /// `self` with its sign bit flipped when `sign` has the sign bit set,
+    /// the sign of `self * sign` without the multiplication.
+    fn flip_sign(&self, sign: &Self) -> Self;
     fn recip(&self) -> Self;
+    /// The smaller of the two, ignoring a NaN like `fmin` does.
+    fn min(&self, other: &Self) -> Self;
+    /// The larger of the two, ignoring a NaN like `fmax` does.
+    fn max(&self, other: &Self) -> Self;
+    /// Restricts to `[min, max]`, keeping NaN. Debug builds assert that
+    /// `min <= max`.
+    fn clamp(&self, min: &Self, max: &Self) -> Self;
     fn powi(&self, n: i32) -> Self;
+    fn powi_checked(&self, n: i32) -> Option<Self>;
+    /// `(1 + self)^n`, accurate also when `1 + self` would round away
+    /// digits of a small `self`. NaN below `-1`, one for `n == 0` even for
+    /// NaN.
+    fn compound(&self, n: i32) -> Self;
+    /// The real `n`th root. NaN for `n == 0` and for negative `self` with an
+    /// even `n`; odd roots keep the sign.
+    fn rootn(&self, n: i32) -> Self;
     fn powf(&self, n: &Self) -> Self;
     fn exp(&self) -> Self;
     fn exp2(&self) -> Self;
@@ -44,16 +95,151 @@ pub trait Float: ApproxEq + Signed {
     fn log(&self, base: &Self) -> Self;
     fn log2(&self) -> Self;
     fn log10(&self) -> Self;
+    /// The square root, correctly rounded. NaN below zero, except that the
+    /// root of `-0.0` is `-0.0`.
+    fn sqrt(&self) -> Self;
     fn cbrt(&self) -> Self;
     fn hypot(&self, other: &Self) -> Self;
+    /// `self * a + b` with a single rounding.
+    fn mul_add(&self, a: &Self, b: &Self) -> Self;
+    fn to_degrees(&self) -> Self;
+    fn to_radians(&self) -> Self;
+    fn sin(&self) -> Self;
+    fn cos(&self) -> Self;
+    fn tan(&self) -> Self;
+    fn asin(&self) -> Self;
+    fn acos(&self) -> Self;
+    fn atan(&self) -> Self;
+    /// The angle of the point `(other, self)`, in `[-pi, pi]`.
+    fn atan2(&self, other: &Self) -> Self;
+    fn sin_cos(&self) -> (Self, Self);
+    /// `tan(pi * self)`, exact at the multiples of a quarter: signed zeros
+    /// at the integers, `±1` and the infinities at the half integers.
+    fn tanpi(&self) -> Self;
+    /// `atan(self) / pi`, in `[-1/2, 1/2]`.
+    fn atanpi(&self) -> Self;
+    /// `asin(self) / pi`, in `[-1/2, 1/2]`.
+    fn asinpi(&self) -> Self;
+    /// `acos(self) / pi`, in `[0, 1]`.
+    fn acospi(&self) -> Self;
+    fn sinh(&self) -> Self;
+    fn cosh(&self) -> Self;
+    fn tanh(&self) -> Self;
+    fn asinh(&self) -> Self;
+    fn acosh(&self) -> Self;
+    fn atanh(&self) -> Self;
     fn exp_m1(&self) -> Self;
     fn ln_1p(&self) -> Self;
+    fn ln_add_exp(&self, other: &Self) -> Self;
+    fn ln_sub_exp(&self, other: &Self) -> Self;
+    /// The exponent of the leading bit, `floor(log2(|self|))`, subnormals
+    /// included, like C's `ilogb`. `i32::MIN` for zero and NaN, `i32::MAX`
+    /// for the infinities.
+    fn exponent(&self) -> i32;
+    /// `self` scaled by a power of two into `[1, 2)` in magnitude, keeping
+    /// the sign. Zero, the infinities and NaN are returned as they are.
+    fn significand(&self) -> Self;
+    /// `exponent` as a float, like C's `logb`: negative infinity for zero,
+    /// positive infinity for the infinities.
+    fn logb(&self) -> Self;
+    /// Whether the sign bit is set, for NaNs too.
+    fn sign_bit(&self) -> bool;
     fn integer_decode(&self) -> (u64, i16, i8);
+    /// The inverse of `integer_decode`, `sign * mantissa * 2^exponent`
+    /// rounded to nearest even like `from_parts`, so any decoded triple
+    /// comes back exactly.
+    fn integer_encode(mantissa: u64, exponent: i16, sign: i8) -> Self;
+    fn into_parts(&self) -> FloatParts;
+    fn into_raw_parts(&self) -> FloatParts;
+    /// The stored fields with their widths, for printing the encoding.
+    fn parts(&self) -> FloatFields;
+    fn from_parts(parts: FloatParts) -> Self;
+    fn from_raw_parts(parts: FloatParts) -> Self;
+    /// The unit in the last place of `self`, the spacing of the floats with
+    /// its exponent: `2^(e + 1 - mantissa_digits)` for `2^e <= |self|`, the
+    /// smallest subnormal for zero and the subnormals. Infinity for the
+    /// infinities, NaN for NaN.
+    fn ulp(&self) -> Self;
+    /// The number of floats from `self` to `other`, in either direction,
+    /// with `-0.0` and `0.0` counted as the same float. `u64::MAX` when
+    /// either is NaN.
+    fn ulps_between(&self, other: &Self) -> u64;
+    /// The least value greater than `self`: the smallest subnormal above
+    /// either zero, and unchanged for NaN and positive infinity.
+    fn next_up(&self) -> Self;
+    /// The greatest value less than `self`, mirroring `next_up`.
+    fn next_down(&self) -> Self;
+    /// The neighbour of `self` in the direction of `toward`, or `toward`
+    /// itself when they are equal, like C's `nextafter`. NaN if either is.
+    fn next_after(&self, toward: &Self) -> Self;
+    /// The IEEE 754 total order: negative NaNs, negative infinity, the
+    /// negative numbers, `-0.0`, `0.0`, the positive numbers, positive
+    /// infinity and positive NaNs, signaling NaNs before quiet ones of the
+    /// same sign.
+    fn total_cmp(&self, other: &Self) -> Ordering;
+    /// Compares with an integer exactly, without rounding it to `Self`.
+    /// `None` for NaN.
+    fn cmp_int(&self, i: i64) -> Option<Ordering>;
+    fn eq_int(&self, i: i64) -> bool;
+    /// Compares exactly with a decimal number such as `"0.1"` or `"-2.5e-3"`.
+    /// `None` for NaN or text that is not a decimal number.
+    fn cmp_decimal_str(&self, decimal: &str) -> Option<Ordering>;
+
+    /// The unsigned integer of the same width, `u32` for `f32` and `u64` for
+    /// `f64`.
+    type Bits: Copy + Eq + Ord + Hash + fmt::Debug + Into<u64>
+        + BitAnd<Output = Self::Bits> + BitOr<Output = Self::Bits> + BitXor<Output = Self::Bits>
+        + Not<Output = Self::Bits> + Shl<u32, Output = Self::Bits> + Shr<u32, Output = Self::Bits>;
+
+    /// The IEEE 754 encoding.
+    fn to_bits(&self) -> Self::Bits;
+    /// The float with the given encoding, NaN payloads included.
+    fn from_bits(bits: Self::Bits) -> Self;
+    /// A signaling NaN, the one C++'s `numeric_limits` gives. Arithmetic on
+    /// it raises the invalid flag and quiets it.
+    fn signaling_nan() -> Self;
+    /// A NaN with the quiet bit clear.
+    fn is_signaling_nan(&self) -> bool;
+    /// A positive quiet NaN carrying `payload` in the mantissa bits below the
+    /// quiet bit. Panics if the payload does not fit in them.
+    fn nan_with_payload(payload: Self::Bits) -> Self;
+    /// The mantissa bits below the quiet bit, `None` if `self` is not NaN.
+    fn nan_payload(&self) -> Option<Self::Bits>;
+
+    /// A type with at least twice the precision of `Self`.
+    type Wider: Copy + PartialOrd
+        + Add<Output = Self::Wider> + Sub<Output = Self::Wider> + Mul<Output = Self::Wider>
+        + Div<Output = Self::Wider> + Neg<Output = Self::Wider>;
+
+    fn widen(&self) -> Self::Wider;
+    fn narrow(wide: Self::Wider) -> Self;
+    fn widening_add(&self, other: &Self) -> Self::Wider;
+    fn widening_mul(&self, other: &Self) -> Self::Wider;
 }
 
 
 macro_rules! impl_core_float {
     ($T:ident) => (
+        #[inline(always)]
+        fn from_f64(n: f64) -> Self {
+            n as $T
+        }
+        #[inline(always)]
+        fn to_f64(&self) -> f64 {
+            *self as f64
+        }
+        #[inline(always)]
+        fn mantissa_digits() -> u32 {
+            ::core::$T::MANTISSA_DIGITS
+        }
+        #[inline(always)]
+        fn min_exp() -> i32 {
+            ::core::$T::MIN_EXP
+        }
+        #[inline(always)]
+        fn max_exp() -> i32 {
+            ::core::$T::MAX_EXP
+        }
         #[inline(always)]
         fn nan() -> Self {
             ::core::$T::NAN
@@ -74,6 +260,64 @@ macro_rules! impl_core_float {
         fn epsilon() -> Self {
             ::core::$T::EPSILON
         }
+        /// ```
+        /// use float::Float;
+        /// assert_eq!(f64::pi(), std::f64::consts::PI);
+        /// assert_eq!(f32::tau(), 2.0 * std::f32::consts::PI);
+        /// assert_eq!(f32::ln_10(), std::f32::consts::LN_10);
+        /// ```
+        #[inline(always)]
+        fn pi() -> Self {
+            ::core::$T::consts::PI
+        }
+        #[inline(always)]
+        fn tau() -> Self {
+            ::core::$T::consts::PI * 2.0
+        }
+        #[inline(always)]
+        fn e() -> Self {
+            ::core::$T::consts::E
+        }
+        #[inline(always)]
+        fn sqrt_2() -> Self {
+            ::core::$T::consts::SQRT_2
+        }
+        #[inline(always)]
+        fn frac_1_sqrt_2() -> Self {
+            ::core::$T::consts::FRAC_1_SQRT_2
+        }
+        #[inline(always)]
+        fn ln_2() -> Self {
+            ::core::$T::consts::LN_2
+        }
+        #[inline(always)]
+        fn ln_10() -> Self {
+            ::core::$T::consts::LN_10
+        }
+        #[inline(always)]
+        fn log2_e() -> Self {
+            ::core::$T::consts::LOG2_E
+        }
+        #[inline(always)]
+        fn log10_e() -> Self {
+            ::core::$T::consts::LOG10_E
+        }
+        #[inline(always)]
+        fn frac_1_pi() -> Self {
+            ::core::$T::consts::FRAC_1_PI
+        }
+        #[inline(always)]
+        fn frac_2_pi() -> Self {
+            ::core::$T::consts::FRAC_2_PI
+        }
+        #[inline(always)]
+        fn frac_pi_2() -> Self {
+            ::core::$T::consts::FRAC_PI_2
+        }
+        #[inline(always)]
+        fn frac_pi_4() -> Self {
+            ::core::$T::consts::FRAC_PI_4
+        }
         #[inline(always)]
         fn is_nan(&self) -> bool {
              *self != *self
@@ -92,7 +336,7 @@ macro_rules! impl_core_float {
         }
         #[inline(always)]
         fn fract(&self) -> Self {
-            *self - self.trunc()
+            check_nan("fract", &[*self], *self - self.trunc())
         }
         #[inline(always)]
         fn is_sign_positive(&self) -> bool {
@@ -104,16 +348,590 @@ macro_rules! impl_core_float {
         }
         #[inline(always)]
         fn recip(&self) -> Self {
-            1.0 / *self
+            check_nan("recip", &[*self], 1.0 / *self)
+        }
+        /// ```
+        /// use float::Float;
+        /// assert_eq!(Float::min(&1.0_f32, &-2.0), -2.0);
+        /// assert_eq!(Float::max(&f64::NAN, &3.0), 3.0);
+        /// assert_eq!(Float::min(&3.0_f64, &f64::NAN), 3.0);
+        /// assert_eq!(Float::clamp(&1.5_f64, &0.0, &1.0), 1.0);
+        /// assert_eq!(Float::clamp(&-0.5_f32, &0.0, &1.0), 0.0);
+        /// assert!(Float::clamp(&f64::NAN, &0.0, &1.0).is_nan());
+        /// ```
+        #[inline(always)]
+        fn min(&self, other: &Self) -> Self {
+            if self.is_nan() || *other < *self { *other } else { *self }
+        }
+        #[inline(always)]
+        fn max(&self, other: &Self) -> Self {
+            if self.is_nan() || *other > *self { *other } else { *self }
+        }
+        #[inline(always)]
+        fn clamp(&self, min: &Self, max: &Self) -> Self {
+            debug_assert!(*min <= *max, "clamp needs min <= max");
+            if *self < *min { *min } else if *self > *max { *max } else { *self }
+        }
+        /// Both multiply by `180 / pi` or `pi / 180` correctly rounded to the
+        /// type.
+        ///
+        /// ```
+        /// use float::Float;
+        /// assert_eq!(Float::to_degrees(&std::f64::consts::PI), 180.0);
+        /// assert_eq!(Float::to_radians(&90.0_f64), std::f64::consts::FRAC_PI_2);
+        /// assert_eq!(Float::to_radians(&180.0_f32), std::f32::consts::PI);
+        /// assert_eq!(Float::to_degrees(&1.0_f32), 57.29578);
+        /// assert_eq!(Float::to_degrees(&1.0_f64), 57.29577951308232);
+        /// assert_eq!(Float::to_radians(&1.0_f64), 0.017453292519943295);
+        /// ```
+        #[inline(always)]
+        fn to_degrees(&self) -> Self {
+            // 180 / pi rounded to f64, which also rounds to the nearest f32
+            check_nan("to_degrees", &[*self], *self * (57.29577951308232_f64 as $T))
+        }
+        #[inline(always)]
+        fn to_radians(&self) -> Self {
+            check_nan("to_radians", &[*self], *self * (::core::$T::consts::PI / 180.0))
+        }
+        /// The argument is reduced exactly to `[-1/2, 1/2]`, so there is no
+        /// rounding error of `pi` times a large argument.
+        ///
+        /// ```
+        /// use float::Float;
+        /// assert_eq!(0.25_f64.tanpi(), 1.0);
+        /// assert_eq!((-2.75_f32).tanpi(), 1.0);
+        /// assert_eq!(0.5_f64.tanpi(), f64::INFINITY);
+        /// assert_eq!(1.5_f64.tanpi(), f64::NEG_INFINITY);
+        /// assert!(1.0_f64.tanpi() == 0.0 && 1.0_f64.tanpi().is_sign_negative());
+        /// assert!(2.0_f64.tanpi().is_sign_positive() && (-2.0_f64).tanpi().is_sign_negative());
+        /// assert!(((1e15_f64 + 1.0 / 8.0).tanpi() - (2.0_f64.sqrt() - 1.0)).abs() < 1e-15);
+        /// assert!(((1.0_f64 / 3.0).tanpi() - 3.0_f64.sqrt()).abs() < 1e-14);
+        /// assert!(float::nan_tolerant(|| f64::INFINITY.tanpi()).is_nan());
+        ///
+        /// assert_eq!(1.0_f64.atanpi(), 0.25);
+        /// assert_eq!(f32::NEG_INFINITY.atanpi(), -0.5);
+        /// assert_eq!((-1.0_f64).asinpi(), -0.5);
+        /// assert_eq!(0.5_f64.asinpi(), 1.0 / 6.0);
+        /// assert_eq!((-1.0_f32).acospi(), 1.0);
+        /// assert_eq!(0.0_f64.acospi(), 0.5);
+        /// assert_eq!(1.0_f64.acospi(), 0.0);
+        /// ```
+        #[inline]
+        fn tanpi(&self) -> Self {
+            let x = *self;
+            if !x.is_finite() {
+                return check_nan("tanpi", &[x], ::core::$T::NAN);
+            }
+
+            let n = x.trunc();
+            let f = x - n;
+            let half = 0.5 as $T;
+            if f == 0.0 || f.abs() == half {
+                // signed zero or infinity, flipped for odd n
+                let base = if f == 0.0 { 0.0 } else { ::core::$T::INFINITY };
+                let odd = (n * half).fract() != 0.0;
+                let result = Float::copysign(&base, &x);
+                return if odd { -result } else { result };
+            }
+
+            // tan has period one in this scale, so move to (-1/2, 1/2)
+            let g = if f > half { f - 1.0 } else if f < -half { f + 1.0 } else { f };
+            let quarter = 0.25 as $T;
+            if g.abs() == quarter {
+                Float::copysign(&(1.0 as $T), &g)
+            } else if g.abs() < quarter {
+                (g * ::core::$T::consts::PI).tan()
+            } else {
+                // tan(pi g) = cot(pi (1/2 - |g|)) with the sign of g, and the
+                // difference is exact
+                let cot = 1.0 / ((half - g.abs()) * ::core::$T::consts::PI).tan();
+                Float::copysign(&cot, &g)
+            }
+        }
+        #[inline]
+        fn atanpi(&self) -> Self {
+            let x = *self;
+            if x.abs() == 1.0 {
+                Float::copysign(&(0.25 as $T), &x)
+            } else if x.is_infinite() {
+                Float::copysign(&(0.5 as $T), &x)
+            } else {
+                check_nan("atanpi", &[x], x.atan() * ::core::$T::consts::FRAC_1_PI)
+            }
+        }
+        #[inline]
+        fn asinpi(&self) -> Self {
+            let x = *self;
+            if x.abs() == 1.0 {
+                Float::copysign(&(0.5 as $T), &x)
+            } else if x.abs() == 0.5 {
+                Float::copysign(&(1.0 as $T / 6.0), &x)
+            } else {
+                check_nan("asinpi", &[x], x.asin() * ::core::$T::consts::FRAC_1_PI)
+            }
+        }
+        #[inline]
+        fn acospi(&self) -> Self {
+            let x = *self;
+            if x == 1.0 {
+                0.0
+            } else if x == -1.0 {
+                1.0
+            } else if x == 0.0 {
+                0.5
+            } else {
+                check_nan("acospi", &[x], x.acos() * ::core::$T::consts::FRAC_1_PI)
+            }
+        }
+        /// Both are evaluated in `f64`, `compound` as `exp(n ln(1 + x))` with
+        /// the product kept exactly as a pair and `rootn` with a Newton step
+        /// after `powf`.
+        ///
+        /// ```
+        /// use float::Float;
+        /// // a rate of 1e-9 per step for a billion steps
+        /// let grown = 1e-9_f64.compound(1_000_000_000);
+        /// assert!((grown - 2.7182818270999043).abs() < 1e-15);
+        /// assert!(((1.0 + 1e-9_f64).powi(1_000_000_000) - 2.7182818270999043).abs() > 1e-8);
+        ///
+        /// assert_eq!(0.5_f32.compound(-2), 0.44444445);
+        /// assert_eq!((-1.0_f64).compound(-1), f64::INFINITY);
+        /// assert_eq!(f64::NAN.compound(0), 1.0);
+        /// assert!(float::nan_tolerant(|| (-2.0_f64).compound(3)).is_nan());
+        ///
+        /// assert_eq!((-27.0_f64).rootn(3), -3.0);
+        /// assert_eq!(1024.0_f32.rootn(-10), 0.5);
+        /// assert_eq!(2.0_f64.powi(-1000).rootn(1000), 0.5);
+        /// assert_eq!(1e300_f64.rootn(300), 10.0);
+        /// assert!(float::nan_tolerant(|| (-16.0_f64).rootn(4)).is_nan());
+        /// assert_eq!((-0.0_f64).rootn(-3), f64::NEG_INFINITY);
+        /// assert_eq!((-0.0_f64).rootn(-2), f64::INFINITY);
+        /// assert_eq!(f64::NEG_INFINITY.rootn(5), f64::NEG_INFINITY);
+        /// assert!(float::nan_tolerant(|| 1.0_f64.rootn(0)).is_nan());
+        /// ```
+        #[inline]
+        fn compound(&self, n: i32) -> Self {
+            let x = *self as f64;
+            if n == 0 {
+                return 1.0;
+            }
+            let result = if x.is_nan() || x < -1.0 {
+                ::core::f64::NAN
+            } else if x == -1.0 {
+                if n < 0 { ::core::f64::INFINITY } else { 0.0 }
+            } else {
+                let (hi, lo) = two_product(n as f64, Float::ln_1p(&x));
+                let r = Float::exp(&hi);
+                if r.is_finite() { r + r * lo } else { r }
+            };
+            check_nan("compound", &[*self], result as $T)
+        }
+        #[inline]
+        fn rootn(&self, n: i32) -> Self {
+            let x = *self as f64;
+            let odd = n % 2 != 0;
+            if n == 0 || x.is_nan() || (x < 0.0 && !odd) {
+                return check_nan("rootn", &[*self], ::core::$T::NAN);
+            }
+
+            let a = x.abs();
+            let k = (n as i64).abs();
+            let root = if a == 0.0 || a == ::core::f64::INFINITY || k == 1 {
+                a
+            } else if k == 2 {
+                Float::sqrt(&a)
+            } else if k == 3 {
+                Float::cbrt(&a)
+            } else {
+                root_f64(a, k)
+            };
+
+            let root = if n < 0 { 1.0 / root } else { root };
+            let root = if odd { Float::copysign(&root, &x) } else { root };
+            root as $T
         }
         #[inline(always)]
         fn log(&self, base: &Self) -> Self {
-            self.ln() / base.ln()
+            check_nan("log", &[*self, *base], self.ln() / base.ln())
+        }
+        /// Returns `ln(e^self + e^other)` without overflowing for large
+        /// arguments or losing the smaller term for small ones.
+        ///
+        /// ```
+        /// use float::Float;
+        /// assert_eq!(1000.0_f64.ln_add_exp(&1000.0), 1000.0 + 2.0_f64.ln());
+        /// assert_eq!((-1000.0_f32).ln_add_exp(&f32::NEG_INFINITY), -1000.0);
+        /// assert!((0.0_f64.ln_add_exp(&-40.0) - 4.248354255291589e-18).abs() < 1e-33);
+        /// assert_eq!(f64::INFINITY.ln_add_exp(&f64::INFINITY), f64::INFINITY);
+        /// assert!(float::nan_tolerant(|| f64::NAN.ln_add_exp(&f64::INFINITY)).is_nan());
+        /// assert!(float::nan_tolerant(|| f32::NEG_INFINITY.ln_add_exp(&f32::NAN)).is_nan());
+        /// ```
+        #[inline]
+        fn ln_add_exp(&self, other: &Self) -> Self {
+            let (max, min) = if *self >= *other { (*self, *other) } else { (*other, *self) };
+            let result = if self.is_nan() || other.is_nan() {
+                *self + *other
+            } else if max == ::core::$T::INFINITY || min == ::core::$T::NEG_INFINITY {
+                max
+            } else {
+                max + (min - max).exp().ln_1p()
+            };
+            check_nan("ln_add_exp", &[*self, *other], result)
+        }
+        /// Returns `ln(e^self - e^other)`, which is NaN for `other > self` and
+        /// negative infinity for equal finite arguments. Infinity minus
+        /// anything finite is infinity, and minus infinity NaN.
+        ///
+        /// ```
+        /// use float::Float;
+        /// assert_eq!(1000.0_f64.ln_sub_exp(&f64::NEG_INFINITY), 1000.0);
+        /// assert!((1000.0_f64.ln_sub_exp(&(1000.0 - 2.0_f64.ln())) - (1000.0 - 2.0_f64.ln())).abs() < 1e-12);
+        /// assert!((1.0_f64.ln_sub_exp(&(1.0 - 1e-10)) - (1.0 + (1e-10_f64).ln())).abs() < 1e-6);
+        /// assert_eq!(2.0_f32.ln_sub_exp(&2.0), f32::NEG_INFINITY);
+        /// assert_eq!(f64::INFINITY.ln_sub_exp(&5.0), f64::INFINITY);
+        /// assert_eq!(f64::NEG_INFINITY.ln_sub_exp(&f64::NEG_INFINITY), f64::NEG_INFINITY);
+        /// float::nan_tolerant(|| {
+        ///     assert!(f64::INFINITY.ln_sub_exp(&f64::INFINITY).is_nan());
+        ///     assert!(1.0_f32.ln_sub_exp(&2.0).is_nan());
+        ///     assert!(f64::NAN.ln_sub_exp(&f64::NEG_INFINITY).is_nan());
+        /// });
+        /// ```
+        #[inline]
+        fn ln_sub_exp(&self, other: &Self) -> Self {
+            let result = if self.is_nan() || other.is_nan() {
+                *self + *other
+            } else if *self == ::core::$T::INFINITY {
+                if *other == ::core::$T::INFINITY { ::core::$T::NAN } else { *self }
+            } else if *other == ::core::$T::NEG_INFINITY {
+                *self
+            } else if *self == *other {
+                ::core::$T::NEG_INFINITY
+            } else if !(*self > *other) {
+                ::core::$T::NAN
+            } else {
+                // ln(1 - e^d), switching formulas at d = -ln 2 to stay accurate
+                let d = *other - *self;
+                if d > -::core::$T::consts::LN_2 {
+                    *self + (-d.exp_m1()).ln()
+                } else {
+                    *self + (-d.exp()).ln_1p()
+                }
+            };
+            check_nan("ln_sub_exp", &[*self, *other], result)
+        }
+        /// ```
+        /// use float::Float;
+        /// use std::cmp::Ordering;
+        ///
+        /// assert_eq!(9007199254740992.0_f64.cmp_int(9007199254740993), Some(Ordering::Less));
+        /// assert_eq!((i64::MAX as f64).cmp_int(i64::MAX), Some(Ordering::Greater));
+        /// assert!((i64::MIN as f32).eq_int(i64::MIN));
+        /// assert_eq!((-0.5_f32).cmp_int(0), Some(Ordering::Less));
+        /// assert_eq!(2.5_f64.cmp_int(2), Some(Ordering::Greater));
+        /// assert_eq!(f64::NEG_INFINITY.cmp_int(i64::MIN), Some(Ordering::Less));
+        /// assert!((-0.0_f64).eq_int(0));
+        /// assert_eq!(f32::NAN.cmp_int(0), None);
+        /// ```
+        #[inline]
+        fn cmp_int(&self, i: i64) -> Option<Ordering> {
+            cmp_int_exact(*self, i)
+        }
+        #[inline(always)]
+        fn eq_int(&self, i: i64) -> bool {
+            self.cmp_int(i) == Some(Ordering::Equal)
+        }
+        /// ```
+        /// use float::Float;
+        /// use std::cmp::Ordering;
+        ///
+        /// assert_eq!(0.1_f64.cmp_decimal_str("0.1"), Some(Ordering::Greater));
+        /// assert_eq!(0.3_f64.cmp_decimal_str("0.3"), Some(Ordering::Less));
+        /// assert_eq!(0.1_f32.cmp_decimal_str("0.100000001490116119384765625"), Some(Ordering::Equal));
+        /// assert_eq!(1e23_f64.cmp_decimal_str("1e23"), Some(Ordering::Less));
+        /// assert_eq!(0.5_f64.cmp_decimal_str("+00050.000e-2"), Some(Ordering::Equal));
+        /// assert_eq!((-0.0_f32).cmp_decimal_str("-0"), Some(Ordering::Equal));
+        /// assert_eq!((-2.0_f64).cmp_decimal_str("-1.99"), Some(Ordering::Less));
+        /// assert_eq!(f64::MAX.cmp_decimal_str("1e999999999999"), Some(Ordering::Less));
+        /// assert_eq!(1.0_f64.cmp_decimal_str("1.0.0"), None);
+        /// ```
+        #[inline]
+        fn cmp_decimal_str(&self, decimal: &str) -> Option<Ordering> {
+            decimal::cmp_decimal_str(*self, decimal)
+        }
+        /// ```
+        /// use float::Float;
+        ///
+        /// for &x in &[1.5_f64, -0.0, 5e-324, f64::MAX, -2.2250738585072014e-308] {
+        ///     let (mantissa, exponent, sign) = x.integer_decode();
+        ///     let back = f64::integer_encode(mantissa, exponent, sign);
+        ///     assert!(back == x && back.is_sign_negative() == x.is_sign_negative());
+        /// }
+        /// let (mantissa, exponent, sign) = 1e-45_f32.integer_decode();
+        /// assert_eq!(f32::integer_encode(mantissa, exponent, sign), 1e-45);
+        ///
+        /// assert_eq!(f32::integer_encode(3, -1, -1), -1.5);
+        /// assert_eq!(f32::integer_encode((1 << 24) + 1, 0, 1), 16777216.0);
+        /// assert_eq!(f64::integer_encode(1, 1024, 1), f64::INFINITY);
+        /// ```
+        /// ```
+        /// use float::Float;
+        /// assert_eq!(Float::exponent(&1.0_f64), 0);
+        /// assert_eq!(Float::exponent(&-0.75_f32), -1);
+        /// assert_eq!(Float::exponent(&f64::MAX), 1023);
+        /// assert_eq!(Float::exponent(&5e-324_f64), -1074);
+        /// assert_eq!(Float::exponent(&1e-40_f32), -133);
+        /// assert_eq!(Float::exponent(&0.0_f64), i32::MIN);
+        /// assert_eq!(Float::exponent(&f32::INFINITY), i32::MAX);
+        ///
+        /// assert_eq!(Float::significand(&-12.0_f64), -1.5);
+        /// assert_eq!(Float::significand(&1e-45_f32), 1.0);
+        /// assert_eq!(Float::significand(&-0.0_f64), 0.0);
+        ///
+        /// assert_eq!(Float::logb(&1000.0_f64), 9.0);
+        /// assert_eq!(Float::logb(&0.0_f32), f32::NEG_INFINITY);
+        /// assert_eq!(Float::logb(&f64::NEG_INFINITY), f64::INFINITY);
+        ///
+        /// assert!(Float::sign_bit(&-0.0_f64) && Float::sign_bit(&-f32::NAN));
+        /// assert!(!Float::sign_bit(&f64::NAN));
+        /// ```
+        #[inline]
+        fn exponent(&self) -> i32 {
+            if self.is_nan() || *self == 0.0 {
+                i32::min_value()
+            } else if self.is_infinite() {
+                i32::max_value()
+            } else {
+                let parts = self.into_parts();
+                parts.exponent + 63 - parts.mantissa.leading_zeros() as i32
+            }
+        }
+        #[inline]
+        fn significand(&self) -> Self {
+            if !self.is_finite() || *self == 0.0 {
+                return *self;
+            }
+            let parts = self.into_parts();
+            let length = 64 - parts.mantissa.leading_zeros() as i32;
+            Float::from_parts(FloatParts::new(parts.sign, 1 - length, parts.mantissa))
+        }
+        #[inline]
+        fn logb(&self) -> Self {
+            if self.is_nan() {
+                *self
+            } else if *self == 0.0 {
+                ::core::$T::NEG_INFINITY
+            } else if self.is_infinite() {
+                ::core::$T::INFINITY
+            } else {
+                Float::exponent(self) as $T
+            }
+        }
+        #[inline(always)]
+        fn sign_bit(&self) -> bool {
+            Float::to_bits(self) >> (8 * mem::size_of::<$T>() as u32 - 1) == 1
+        }
+        #[inline]
+        fn integer_encode(mantissa: u64, exponent: i16, sign: i8) -> Self {
+            Float::from_parts(FloatParts::new(sign < 0, exponent as i32, mantissa))
+        }
+        /// ```
+        /// use float::Float;
+        /// assert_eq!(1.0_f64.next_up(), 1.0 + f64::EPSILON);
+        /// assert_eq!(1.0_f32.next_down(), 1.0 - f32::EPSILON / 2.0);
+        /// assert_eq!((-0.0_f64).next_up(), 5e-324);
+        /// assert_eq!(5e-324_f64.next_down(), 0.0);
+        /// assert_eq!(f32::MAX.next_up(), f32::INFINITY);
+        /// assert_eq!(f64::INFINITY.next_down(), f64::MAX);
+        /// assert_eq!(f64::NEG_INFINITY.next_down(), f64::NEG_INFINITY);
+        ///
+        /// assert_eq!(1.0_f64.next_after(&0.0), 0.9999999999999999);
+        /// assert_eq!(0.0_f32.next_after(&-1.0), -1e-45);
+        /// assert!(0.0_f64.next_after(&-0.0).is_sign_negative());
+        /// assert!(1.0_f64.next_after(&f64::NAN).is_nan());
+        /// ```
+        #[inline]
+        fn next_up(&self) -> Self {
+            if self.is_nan() || *self == ::core::$T::INFINITY {
+                return *self;
+            }
+            let bits = Float::to_bits(self);
+            Float::from_bits(if *self == 0.0 {
+                1
+            } else if *self > 0.0 {
+                bits + 1
+            } else {
+                bits - 1
+            })
+        }
+        #[inline(always)]
+        fn next_down(&self) -> Self {
+            -(-*self).next_up()
+        }
+        #[inline]
+        fn next_after(&self, toward: &Self) -> Self {
+            if self.is_nan() || toward.is_nan() {
+                *self + *toward
+            } else if *toward > *self {
+                self.next_up()
+            } else if *toward < *self {
+                self.next_down()
+            } else {
+                *toward
+            }
+        }
+        /// ```
+        /// use float::Float;
+        /// use std::cmp::Ordering;
+        ///
+        /// assert_eq!(Float::total_cmp(&-0.0_f64, &0.0), Ordering::Less);
+        /// assert_eq!(Float::total_cmp(&f32::NAN, &f32::INFINITY), Ordering::Greater);
+        /// assert_eq!(Float::total_cmp(&-f64::NAN, &f64::NEG_INFINITY), Ordering::Less);
+        /// assert_eq!(Float::total_cmp(&f64::NAN, &f64::NAN), Ordering::Equal);
+        ///
+        /// let mut values = [3.0_f64, f64::NAN, -1.0, -0.0, 0.0, f64::NEG_INFINITY];
+        /// values.sort_by(Float::total_cmp);
+        /// assert_eq!(&values[..5], &[f64::NEG_INFINITY, -1.0, -0.0, 0.0, 3.0]);
+        /// assert!(values[3].is_sign_positive() && values[5].is_nan());
+        /// ```
+        #[inline(always)]
+        fn total_cmp(&self, other: &Self) -> Ordering {
+            total_order_key(*self).cmp(&total_order_key(*other))
         }
     )
 }
 
 
+/// The `k`th root of a positive finite `a` for `k >= 4`: `powf` with
+/// `1 / k` rounded, then a Newton step. For moderate `k` the exponent is
+/// first split into a multiple of `k`, whose root is exact, and a remainder
+/// below `k`, so `y^k` in the step stays normal.
+fn root_f64(a: f64, k: i64) -> f64 {
+    // the rounding of 1 / k costs at most |ln a| / k ulps, under one here
+    if k > 900 {
+        return Float::powf(&a, &(1.0 / k as f64));
+    }
+
+    let (mantissa, exponent, _) = Float::integer_decode(&a);
+    let exponent = exponent as i64;
+    let q = if exponent >= 0 { exponent / k } else { -((k - 1 - exponent) / k) };
+    let reduced: f64 = Float::integer_encode(mantissa, (exponent - q * k) as i16, 1);
+
+    let y = Float::powf(&reduced, &(1.0 / k as f64));
+    let power = Float::powi(&y, k as i32);
+    let y = y - y * (1.0 - reduced / power) / k as f64;
+    y * <f64 as Float>::integer_encode(1, q as i16, 1)
+}
+
+
+/// With the `nan-debug` feature, debug-asserts that an operation neither
+/// received nor produced a NaN, unless running inside `nan_tolerant`.
+#[cfg(feature = "nan-debug")]
+#[inline(always)]
+fn check_nan<T: Float>(name: &'static str, inputs: &[T], output: T) -> T {
+    if !nan_debug::is_tolerant() {
+        debug_assert!(!inputs.iter().any(|x| x.is_nan()), "Float::{} received a NaN", name);
+        debug_assert!(!output.is_nan(), "Float::{} produced a NaN", name);
+    }
+    output
+}
+#[cfg(not(feature = "nan-debug"))]
+#[inline(always)]
+fn check_nan<T: Float>(_: &'static str, _: &[T], output: T) -> T {
+    output
+}
+
+/// Computes `x^n` by repeated squaring.
+///
+/// `x^0` is `1` for every `x`, including NaN. Negative exponents compute
+/// `1 / x^|n|`, falling back to `(1 / x)^|n|` when `x^|n|` overflows or
+/// underflows so that results in the subnormal range are not flushed to zero.
+/// The magnitude of `n` is taken as a `u32`, so `i32::MIN` means
+/// `x^-2147483648` rather than overflowing.
+#[inline]
+fn powi_by_squaring<T: Float + MulIdentity>(x: T, n: i32) -> T {
+    if n >= 0 {
+        pow_usize(x, n as usize)
+    } else {
+        let m = n.wrapping_neg() as u32 as usize;
+        let p = pow_usize(x, m);
+
+        if x.is_finite() && x != T::from_f64(0.0) && (p.is_infinite() || p == T::from_f64(0.0)) {
+            pow_usize(x.recip(), m)
+        } else {
+            p.recip()
+        }
+    }
+}
+
+/// Same as `powi_by_squaring` but returns `None` when a finite non-zero `x`
+/// produces an infinite (overflow) or zero (underflow) result.
+#[inline]
+fn powi_checked<T: Float + MulIdentity>(x: T, n: i32) -> Option<T> {
+    let result = powi_by_squaring(x, n);
+    let zero = T::from_f64(0.0);
+
+    if x.is_finite() && x != zero && (result.is_infinite() || result == zero) {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+/// Compares `x` with `i` through the integer decoding of `x`, so neither
+/// side is rounded.
+fn cmp_int_exact<T: Float>(x: T, i: i64) -> Option<Ordering> {
+    if x.is_nan() {
+        return None;
+    } else if x.is_infinite() {
+        return Some(if x > T::from_f64(0.0) { Ordering::Greater } else { Ordering::Less });
+    }
+
+    let (mantissa, exponent, sign) = x.integer_decode();
+    let negative = sign < 0 && mantissa != 0;
+    if negative != (i < 0) {
+        return Some(if negative { Ordering::Less } else { Ordering::Greater });
+    }
+
+    let n = if i < 0 { (i as u64).wrapping_neg() } else { i as u64 };
+    let magnitude = if mantissa == 0 {
+        0_u64.cmp(&n)
+    } else if exponent >= 0 {
+        let shift = exponent as u32;
+        if shift > mantissa.leading_zeros() {
+            Ordering::Greater
+        } else {
+            (mantissa << shift).cmp(&n)
+        }
+    } else {
+        let shift = -exponent as u32;
+        let (integer, fraction) = if shift >= 64 {
+            (0, mantissa)
+        } else {
+            (mantissa >> shift, mantissa & ((1 << shift) - 1))
+        };
+        integer.cmp(&n).then(if fraction != 0 { Ordering::Greater } else { Ordering::Equal })
+    };
+
+    Some(if negative { magnitude.reverse() } else { magnitude })
+}
+
+/// Maps `x` onto a signed integer that is ordered like the float, with both
+/// zeros mapping to `0` and adjacent floats mapping to adjacent integers.
+#[inline]
+fn ordered_bits_f32(x: f32) -> i64 {
+    let bits = Float::to_bits(&x);
+    let magnitude = (bits & 0x7fffffff) as i64;
+    if bits >> 31 == 0 { magnitude } else { -magnitude }
+}
+
+#[inline]
+fn ordered_bits_f64(x: f64) -> i64 {
+    let bits = Float::to_bits(&x);
+    let magnitude = (bits & 0x7fffffffffffffff) as i64;
+    if bits >> 63 == 0 { magnitude } else { -magnitude }
+}
+
+
 impl Float for f32 {
     impl_core_float!(f32);
 
@@ -122,7 +940,7 @@ impl Float for f32 {
         const EXP_MASK: u32 = 0x7f800000;
         const MAN_MASK: u32 = 0x007fffff;
 
-        let bits: u32 = unsafe { mem::transmute(*self) };
+        let bits = Float::to_bits(self);
         match (bits & MAN_MASK, bits & EXP_MASK) {
             (0, 0) => FpCategory::Zero,
             (_, 0) => FpCategory::Subnormal,
@@ -131,118 +949,520 @@ impl Float for f32 {
             _ => FpCategory::Normal,
         }
     }
+    /// ```
+    /// use float::Float;
+    /// assert!(Float::is_subnormal(&1e-45_f32) && Float::is_subnormal(&-1e-45_f32));
+    /// assert!(Float::is_subnormal(&(f32::MIN_POSITIVE - 1e-45)));
+    /// assert!(!Float::is_subnormal(&f32::MIN_POSITIVE) && !Float::is_subnormal(&0.0_f32));
+    /// assert!(!Float::is_subnormal(&-0.0_f32) && !Float::is_subnormal(&f32::NAN));
+    /// ```
+    #[inline(always)]
+    fn is_subnormal(&self) -> bool {
+        // a magnitude in [1, MAN_MASK], and zero wraps around
+        let magnitude = Float::to_bits(self) & 0x7fffffff;
+        magnitude.wrapping_sub(1) < 0x007fffff
+    }
     #[inline(always)]
     fn trunc(&self) -> Self {
-        unsafe {
-            intrinsics::truncf32(*self)
-        }
+        check_nan("trunc", &[*self], unsafe {
+            math::truncf32(*self)
+        })
     }
     #[inline(always)]
+    fn floor(&self) -> Self {
+        check_nan("floor", &[*self], unsafe {
+            math::floorf32(*self)
+        })
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::ceil(&-1.5_f32), -1.0);
+    /// assert_eq!(Float::round(&-2.5_f32), -3.0);
+    /// assert_eq!(Float::round_ties_even(&2.5_f32), 2.0);
+    /// assert_eq!(Float::round_ties_even(&3.5_f32), 4.0);
+    /// assert!(Float::round_ties_even(&-0.5_f32).is_sign_negative());
+    /// ```
+    #[inline(always)]
+    fn ceil(&self) -> Self {
+        check_nan("ceil", &[*self], unsafe {
+            math::ceilf32(*self)
+        })
+    }
+    #[inline(always)]
+    fn round(&self) -> Self {
+        check_nan("round", &[*self], unsafe {
+            math::roundf32(*self)
+        })
+    }
+    #[inline]
+    fn round_ties_even(&self) -> Self {
+        let rounded = Float::round(self);
+        if (rounded - *self).abs() == 0.5 {
+            2.0 * Float::round(&(*self * 0.5))
+        } else {
+            rounded
+        }
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::powi(&2.0_f32, 127), 1.7014118e38_f32);
+    /// assert_eq!(Float::powi(&2.0_f32, -149), 1e-45_f32);
+    /// assert_eq!(Float::powi(&-1.0_f32, i32::MIN), 1.0_f32);
+    /// assert_eq!(Float::powi(&0.5_f32, i32::MIN), f32::INFINITY);
+    /// ```
+    #[inline]
     fn powi(&self, n: i32) -> Self {
-         unsafe {
-             intrinsics::powif32(*self, n)
-         }
+        check_nan("powi", &[*self], powi_by_squaring(*self, n))
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(2.0_f32.powi_checked(127), Some(1.7014118e38_f32));
+    /// assert_eq!(2.0_f32.powi_checked(128), None);
+    /// assert_eq!(2.0_f32.powi_checked(-149), Some(1e-45_f32));
+    /// assert_eq!(2.0_f32.powi_checked(-150), None);
+    /// ```
+    #[inline]
+    fn powi_checked(&self, n: i32) -> Option<Self> {
+        powi_checked(*self, n)
     }
     #[inline(always)]
     fn powf(&self, n: &Self) -> Self {
-        unsafe {
-            intrinsics::powf32(*self, *n)
-        }
+        check_nan("powf", &[*self, *n], unsafe {
+            math::powf32(*self, *n)
+        })
     }
     #[cfg(target_env = "msvc")]
     #[inline(always)]
     fn exp(&self) -> Self {
-        (*self as f64).exp() as f32
+        check_nan("exp", &[*self], (*self as f64).exp() as f32)
     }
     #[cfg(not(target_env = "msvc"))]
     #[inline(always)]
     fn exp(&self) -> Self {
-        unsafe {
-            intrinsics::expf32(*self)
-        }
+        check_nan("exp", &[*self], unsafe {
+            math::expf32(*self)
+        })
     }
     #[inline(always)]
     fn exp2(&self) -> Self {
-        unsafe {
-            intrinsics::exp2f32(*self)
-        }
+        check_nan("exp2", &[*self], unsafe {
+            math::exp2f32(*self)
+        })
     }
     #[cfg(target_env = "msvc")]
     #[inline(always)]
     fn ln(&self) -> Self {
-        (*self as f64).ln() as f32
+        check_nan("ln", &[*self], (*self as f64).ln() as f32)
     }
     #[cfg(not(target_env = "msvc"))]
     #[inline(always)]
     fn ln(&self) -> Self {
-        unsafe {
-            intrinsics::logf32(*self)
-        }
+        check_nan("ln", &[*self], unsafe {
+            math::logf32(*self)
+        })
     }
     #[cfg(target_os = "android")]
     #[inline(always)]
     fn log2(&self) -> Self {
-        ::sys::android::log2f32(*self)
+        check_nan("log2", &[*self], ::sys::android::log2f32(*self))
     }
     #[cfg(not(target_os = "android"))]
     #[inline(always)]
     fn log2(&self) -> Self {
-        unsafe {
-            intrinsics::log2f32(*self)
-        }
+        check_nan("log2", &[*self], unsafe {
+            math::log2f32(*self)
+        })
     }
     #[cfg(target_env = "msvc")]
     #[inline(always)]
     fn log10(&self) -> Self {
-        (*self as f64).log10() as f32
+        check_nan("log10", &[*self], (*self as f64).log10() as f32)
     }
     #[cfg(not(target_env = "msvc"))]
     #[inline(always)]
     fn log10(&self) -> Self {
-        unsafe {
-            intrinsics::log10f32(*self)
-        }
+        check_nan("log10", &[*self], unsafe {
+            math::log10f32(*self)
+        })
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::sqrt(&2.0_f32), 1.4142135);
+    /// assert!(Float::sqrt(&-0.0_f32).is_sign_negative());
+    /// assert!(float::nan_tolerant(|| Float::sqrt(&-1e-30_f32)).is_nan());
+    /// assert_eq!(Float::sqrt(&f32::INFINITY), f32::INFINITY);
+    /// assert!(float::nan_tolerant(|| Float::sqrt(&f32::NEG_INFINITY)).is_nan());
+    /// ```
+    #[inline(always)]
+    fn sqrt(&self) -> Self {
+        check_nan("sqrt", &[*self], unsafe {
+            math::sqrtf32(*self)
+        })
     }
     /// ```
     /// assert_eq!(1.0_f32.cbrt(), 1.0_f32);
     /// ```
     #[inline(always)]
     fn cbrt(&self) -> Self {
-        unsafe {
-            cbrtf(*self)
-        }
+        check_nan("cbrt", &[*self], unsafe {
+            math::cbrtf(*self)
+        })
     }
     /// ```
     /// assert_eq!(1.0_f32.hypot(1.0_f32), 1.4142135_f32);
     /// ```
     #[inline(always)]
     fn hypot(&self, other: &Self) -> Self {
-        unsafe {
-            hypotf(*self, *other)
-        }
+        check_nan("hypot", &[*self, *other], unsafe {
+            math::hypotf(*self, *other)
+        })
+    }
+    /// ```
+    /// use float::Float;
+    /// let x = 1.0_f32 + 2.0_f32.powi(-12);
+    /// assert_eq!(Float::mul_add(&x, &x, &-(x * x)), 2.0_f32.powi(-24));
+    /// assert_eq!(Float::mul_add(&2.0_f32, &3.0, &4.0), 10.0);
+    /// ```
+    #[inline(always)]
+    fn mul_add(&self, a: &Self, b: &Self) -> Self {
+        check_nan("mul_add", &[*self, *a, *b], unsafe {
+            math::fmaf32(*self, *a, *b)
+        })
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::sin(&0.5_f32), 0.47942555);
+    /// assert_eq!(Float::atan2(&1.0_f32, &-1.0), 2.3561945);
+    /// assert_eq!(Float::atan2(&-0.0_f32, &-1.0), -std::f32::consts::PI);
+    /// assert_eq!(Float::sin_cos(&0.0_f32), (0.0, 1.0));
+    /// ```
+    #[inline(always)]
+    fn sin(&self) -> Self {
+        check_nan("sin", &[*self], unsafe {
+            math::sinf32(*self)
+        })
+    }
+    #[inline(always)]
+    fn cos(&self) -> Self {
+        check_nan("cos", &[*self], unsafe {
+            math::cosf32(*self)
+        })
+    }
+    #[inline(always)]
+    fn tan(&self) -> Self {
+        check_nan("tan", &[*self], unsafe {
+            math::tanf(*self)
+        })
+    }
+    #[inline(always)]
+    fn asin(&self) -> Self {
+        check_nan("asin", &[*self], unsafe {
+            math::asinf(*self)
+        })
+    }
+    #[inline(always)]
+    fn acos(&self) -> Self {
+        check_nan("acos", &[*self], unsafe {
+            math::acosf(*self)
+        })
+    }
+    #[inline(always)]
+    fn atan(&self) -> Self {
+        check_nan("atan", &[*self], unsafe {
+            math::atanf(*self)
+        })
+    }
+    #[inline(always)]
+    fn atan2(&self, other: &Self) -> Self {
+        check_nan("atan2", &[*self, *other], unsafe {
+            math::atan2f(*self, *other)
+        })
+    }
+    #[inline(always)]
+    fn sin_cos(&self) -> (Self, Self) {
+        (Float::sin(self), Float::cos(self))
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::sinh(&1.0_f32), 1.1752012);
+    /// assert_eq!(Float::cosh(&-1.0_f32), 1.5430806);
+    /// assert_eq!(Float::tanh(&-0.0_f32).is_sign_negative(), true);
+    /// assert_eq!(Float::tanh(&20.0_f32), 1.0);
+    /// ```
+    #[inline(always)]
+    fn sinh(&self) -> Self {
+        check_nan("sinh", &[*self], unsafe {
+            math::sinhf(*self)
+        })
+    }
+    #[inline(always)]
+    fn cosh(&self) -> Self {
+        check_nan("cosh", &[*self], unsafe {
+            math::coshf(*self)
+        })
+    }
+    #[inline(always)]
+    fn tanh(&self) -> Self {
+        check_nan("tanh", &[*self], unsafe {
+            math::tanhf(*self)
+        })
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::asinh(&1.0_f32), 0.8813736);
+    /// assert_eq!(Float::acosh(&1.0_f32), 0.0);
+    /// assert!(float::nan_tolerant(|| Float::acosh(&0.5_f32)).is_nan());
+    /// assert_eq!(Float::atanh(&1.0_f32), f32::INFINITY);
+    /// ```
+    #[inline(always)]
+    fn asinh(&self) -> Self {
+        check_nan("asinh", &[*self], unsafe {
+            math::asinhf(*self)
+        })
+    }
+    #[inline(always)]
+    fn acosh(&self) -> Self {
+        check_nan("acosh", &[*self], unsafe {
+            math::acoshf(*self)
+        })
+    }
+    #[inline(always)]
+    fn atanh(&self) -> Self {
+        check_nan("atanh", &[*self], unsafe {
+            math::atanhf(*self)
+        })
     }
     /// ```
     /// assert_eq!(1.0_f32.exp_m1(), 1.7182817_f32);
     /// ```
     #[inline(always)]
     fn exp_m1(&self) -> Self {
-        unsafe {
-            expm1f(*self)
-        }
+        check_nan("exp_m1", &[*self], unsafe {
+            math::expm1f(*self)
+        })
     }
     /// ```
     /// assert_eq!(1.0_f32.ln_1p(), 0.6931472_f32);
     /// ```
     #[inline(always)]
     fn ln_1p(&self) -> Self {
-        unsafe {
-            log1pf(*self)
-        }
+        check_nan("ln_1p", &[*self], unsafe {
+            math::log1pf(*self)
+        })
     }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::copysign(&3.0_f32, &-0.0), -3.0);
+    /// assert!(Float::copysign(&f32::NAN, &-1.0).is_sign_negative());
+    /// assert_eq!(Float::flip_sign(&-2.0_f32, &-5.0), 2.0);
+    /// assert_eq!(Float::flip_sign(&-2.0_f32, &0.0), -2.0);
+    /// ```
     #[inline(always)]
+    fn copysign(&self, sign: &Self) -> Self {
+        let magnitude = Float::to_bits(self);
+        let sign = Float::to_bits(sign);
+        Float::from_bits((magnitude & !0x80000000) | (sign & 0x80000000))
+    }
+    #[inline(always)]
+    fn flip_sign(&self, sign: &Self) -> Self {
+        let bits = Float::to_bits(self);
+        let sign = Float::to_bits(sign);
+        Float::from_bits(bits ^ (sign & 0x80000000))
+    }
+    /// Like `f64`, subnormals and zero share the exponent of the smallest
+    /// normal numbers with a doubled mantissa.
+    ///
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(1.0_f32.integer_decode(), (0x800000, -23, 1));
+    /// assert_eq!((-0.15625_f32).integer_decode(), (0xa00000, -26, -1));
+    /// assert_eq!(f32::MAX.integer_decode(), (0xffffff, 104, 1));
+    /// assert_eq!(f32::MIN_POSITIVE.integer_decode(), (0x800000, -149, 1));
+    /// assert_eq!(1e-45_f32.integer_decode(), (2, -150, 1));
+    /// assert_eq!((f32::MIN_POSITIVE - 1e-45).integer_decode(), (0xfffffe, -150, 1));
+    /// assert_eq!(0.0_f32.integer_decode(), (0, -150, 1));
+    /// assert_eq!((-0.0_f32).integer_decode(), (0, -150, -1));
+    /// ```
+    #[inline]
     fn integer_decode(&self) -> (u64, i16, i8) {
-        // TODO: write f32 specific integer decode
-        Float::integer_decode(&(*self as f64))
+        let bits = Float::to_bits(self);
+        let sign: i8 = if bits >> 31 == 0 { 1 } else { -1 };
+        let mut exponent: i16 = ((bits >> 23) & 0xff) as i16;
+        let mantissa = if exponent == 0 {
+            (bits & 0x7fffff) << 1
+        } else {
+            (bits & 0x7fffff) | 0x800000
+        };
+
+        exponent -= 127 + 23;
+        (mantissa as u64, exponent, sign)
+    }
+    /// ```
+    /// use float::{Float, FloatParts};
+    /// assert_eq!(1e-45_f32.into_parts(), FloatParts::new(false, -149, 1));
+    /// assert_eq!(f32::NAN.into_raw_parts().exponent, 0xff);
+    /// ```
+    #[inline]
+    fn into_parts(&self) -> FloatParts {
+        float_parts::normalize(self.into_raw_parts(), 23, 8)
+    }
+    #[inline]
+    fn into_raw_parts(&self) -> FloatParts {
+        let bits = Float::to_bits(self);
+        float_parts::raw_parts(bits as u64, 23, 8)
+    }
+    #[inline]
+    fn parts(&self) -> FloatFields {
+        FloatFields::new(self.into_raw_parts(), 8, 23)
+    }
+    /// Rounds `mantissa * 2^exponent` to nearest even, so any mantissa width
+    /// is accepted. NaNs can only be built with `from_raw_parts`.
+    ///
+    /// ```
+    /// use float::{Float, FloatParts};
+    /// assert_eq!(f32::from_parts(FloatParts::new(true, 0, 3)), -3.0);
+    /// assert_eq!(f32::from_parts(FloatParts::new(false, -40, (1 << 40) + (1 << 16))), 1.0);
+    /// assert_eq!(f32::from_parts(FloatParts::new(false, -40, (1 << 40) + (3 << 16))), 1.0000002);
+    /// assert_eq!(f32::from_parts(FloatParts::new(false, -150, 3)), 3e-45);
+    /// assert_eq!(f32::from_parts(FloatParts::new(false, 104, 1 << 24)), f32::INFINITY);
+    /// ```
+    #[inline]
+    fn from_parts(parts: FloatParts) -> Self {
+        let bits = float_parts::round_parts(parts, 23, 8) as u32;
+        Float::from_bits(bits)
+    }
+    #[inline]
+    fn from_raw_parts(parts: FloatParts) -> Self {
+        let bits = float_parts::from_raw_parts(parts, 23, 8) as u32;
+        Float::from_bits(bits)
+    }
+
+    type Bits = u32;
+
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::to_bits(&1.0_f32), 0x3f800000);
+    /// assert_eq!(<f32 as Float>::from_bits(0x80000000), -0.0);
+    /// ```
+    #[inline(always)]
+    fn to_bits(&self) -> u32 {
+        f32::to_bits(*self)
+    }
+    #[inline(always)]
+    fn from_bits(bits: u32) -> Self {
+        f32::from_bits(bits)
+    }
+    /// ```
+    /// use float::Float;
+    /// let snan = f32::signaling_nan();
+    /// assert!(snan.is_nan() && snan.is_signaling_nan() && !f32::NAN.is_signaling_nan());
+    /// assert_eq!(snan.nan_payload(), Some(0x200000));
+    ///
+    /// let x = f32::nan_with_payload(0x2a);
+    /// assert!(x.is_nan() && !x.is_signaling_nan());
+    /// assert_eq!(Float::to_bits(&x), 0x7fc0002a);
+    /// assert_eq!(x.nan_payload(), Some(0x2a));
+    /// assert_eq!((-x).nan_payload(), Some(0x2a));
+    /// assert_eq!(f32::nan_with_payload(0x3fffff).nan_payload(), Some(0x3fffff));
+    /// assert_eq!(f32::INFINITY.nan_payload(), None);
+    /// assert!(!f32::INFINITY.is_signaling_nan());
+    /// ```
+    #[inline(always)]
+    fn signaling_nan() -> Self {
+        Float::from_bits(0x7fa00000)
+    }
+    #[inline(always)]
+    fn is_signaling_nan(&self) -> bool {
+        self.is_nan() && Float::to_bits(self) & 0x00400000 == 0
+    }
+    #[inline]
+    fn nan_with_payload(payload: u32) -> Self {
+        assert!(payload <= 0x003fffff, "NaN payload wider than 22 bits");
+        Float::from_bits(0x7f800000 | 0x00400000 | payload)
+    }
+    #[inline]
+    fn nan_payload(&self) -> Option<u32> {
+        if self.is_nan() {
+            Some(Float::to_bits(self) & 0x003fffff)
+        } else {
+            None
+        }
+    }
+
+    type Wider = f64;
+
+    #[inline(always)]
+    fn widen(&self) -> f64 {
+        *self as f64
+    }
+    #[inline(always)]
+    fn narrow(wide: f64) -> Self {
+        wide as f32
+    }
+    /// Rounded once in `f64`, which is exact unless the exponents differ by
+    /// more than 29.
+    ///
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(1.0_f32.widening_add(&1e-8), 1.0 + 1e-8_f32 as f64);
+    /// ```
+    #[inline(always)]
+    fn widening_add(&self, other: &Self) -> f64 {
+        *self as f64 + *other as f64
+    }
+    /// Always exact.
+    ///
+    /// ```
+    /// use float::Float;
+    /// let x = 1.0_f32 + f32::EPSILON;
+    /// assert_eq!(x.widening_mul(&x), 1.0 + 2.0 * f32::EPSILON as f64 + (f32::EPSILON as f64).powi(2));
+    /// ```
+    #[inline(always)]
+    fn widening_mul(&self, other: &Self) -> f64 {
+        *self as f64 * *other as f64
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(1.0_f32.ulp(), f32::EPSILON);
+    /// assert_eq!((-1.0_f32).ulp(), f32::EPSILON);
+    /// assert_eq!(0.0_f32.ulp(), 1e-45_f32);
+    /// assert_eq!(f32::MIN_POSITIVE.ulp(), 1e-45_f32);
+    /// assert_eq!(f32::MAX.ulp(), 2.028241e31_f32);
+    /// assert_eq!(f32::INFINITY.ulp(), f32::INFINITY);
+    /// ```
+    #[inline]
+    fn ulp(&self) -> Self {
+        let bits = Float::to_bits(self);
+        let exp = (bits >> 23) & 0xff;
+
+        if exp == 0xff {
+            return if bits & 0x007fffff == 0 { f32::INFINITY } else { *self };
+        }
+
+        let ulp: u32 = if exp <= 23 {
+            1 << (if exp == 0 { 0 } else { exp - 1 })
+        } else {
+            (exp - 23) << 23
+        };
+        Float::from_bits(ulp)
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(1.0_f32.ulps_between(&1.0000001_f32), 1);
+    /// assert_eq!(0.0_f32.ulps_between(&-0.0_f32), 0);
+    /// assert_eq!(1e-45_f32.ulps_between(&-1e-45_f32), 2);
+    /// assert_eq!(f32::MAX.ulps_between(&f32::INFINITY), 1);
+    /// assert_eq!(f32::NAN.ulps_between(&1.0_f32), u64::MAX);
+    /// ```
+    #[inline]
+    fn ulps_between(&self, other: &Self) -> u64 {
+        if self.is_nan() || other.is_nan() {
+            return u64::max_value();
+        }
+
+        let a = ordered_bits_f32(*self);
+        let b = ordered_bits_f32(*other);
+        if a > b { a.wrapping_sub(b) as u64 } else { b.wrapping_sub(a) as u64 }
     }
 }
 
@@ -254,7 +1474,7 @@ impl Float for f64 {
         const EXP_MASK: u64 = 0x7ff0000000000000;
         const MAN_MASK: u64 = 0x000fffffffffffff;
 
-        let bits: u64 = unsafe { mem::transmute(*self) };
+        let bits = Float::to_bits(self);
         match (bits & MAN_MASK, bits & EXP_MASK) {
             (0, 0) => FpCategory::Zero,
             (_, 0) => FpCategory::Subnormal,
@@ -263,93 +1483,307 @@ impl Float for f64 {
             _ => FpCategory::Normal,
         }
     }
+    /// ```
+    /// use float::Float;
+    /// assert!(Float::is_subnormal(&5e-324_f64) && Float::is_subnormal(&-5e-324_f64));
+    /// assert!(Float::is_subnormal(&(f64::MIN_POSITIVE - 5e-324)));
+    /// assert!(!Float::is_subnormal(&f64::MIN_POSITIVE) && !Float::is_subnormal(&-f64::MIN_POSITIVE));
+    /// assert!(!Float::is_subnormal(&0.0_f64) && !Float::is_subnormal(&f64::INFINITY));
+    /// ```
+    #[inline(always)]
+    fn is_subnormal(&self) -> bool {
+        // a magnitude in [1, MAN_MASK], and zero wraps around
+        let magnitude = Float::to_bits(self) & 0x7fffffffffffffff;
+        magnitude.wrapping_sub(1) < 0x000fffffffffffff
+    }
     #[inline(always)]
     fn trunc(&self) -> Self {
-        unsafe {
-            intrinsics::truncf64(*self)
-        }
+        check_nan("trunc", &[*self], unsafe {
+            math::truncf64(*self)
+        })
     }
     #[inline(always)]
+    fn floor(&self) -> Self {
+        check_nan("floor", &[*self], unsafe {
+            math::floorf64(*self)
+        })
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::ceil(&0.1_f64), 1.0);
+    /// assert_eq!(Float::round(&0.49999999999999994_f64), 0.0);
+    /// assert_eq!(Float::round_ties_even(&-4.5_f64), -4.0);
+    /// assert_eq!(Float::round_ties_even(&4503599627370497.0_f64), 4503599627370497.0);
+    /// ```
+    #[inline(always)]
+    fn ceil(&self) -> Self {
+        check_nan("ceil", &[*self], unsafe {
+            math::ceilf64(*self)
+        })
+    }
+    #[inline(always)]
+    fn round(&self) -> Self {
+        check_nan("round", &[*self], unsafe {
+            math::roundf64(*self)
+        })
+    }
+    #[inline]
+    fn round_ties_even(&self) -> Self {
+        let rounded = Float::round(self);
+        if (rounded - *self).abs() == 0.5 {
+            2.0 * Float::round(&(*self * 0.5))
+        } else {
+            rounded
+        }
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::powi(&2.0_f64, 1023), 8.98846567431158e307_f64);
+    /// assert_eq!(Float::powi(&2.0_f64, -1074), 5e-324_f64);
+    /// assert_eq!(Float::powi(&2.0_f64, i32::MIN), 0.0_f64);
+    /// assert_eq!(Float::powi(&-1.0_f64, i32::MAX), -1.0_f64);
+    /// assert_eq!(float::nan_tolerant(|| Float::powi(&f64::NAN, 0)), 1.0_f64);
+    /// ```
+    #[inline]
     fn powi(&self, n: i32) -> Self {
-         unsafe {
-             intrinsics::powif64(*self as f64, n)
-         }
+        check_nan("powi", &[*self], powi_by_squaring(*self, n))
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(2.0_f64.powi_checked(1023), Some(8.98846567431158e307_f64));
+    /// assert_eq!(2.0_f64.powi_checked(1024), None);
+    /// assert_eq!(2.0_f64.powi_checked(-1074), Some(5e-324_f64));
+    /// assert_eq!(2.0_f64.powi_checked(-1075), None);
+    /// assert_eq!(0.0_f64.powi_checked(-1), Some(f64::INFINITY));
+    /// assert_eq!(1.5_f64.powi_checked(i32::MIN), None);
+    /// ```
+    #[inline]
+    fn powi_checked(&self, n: i32) -> Option<Self> {
+        powi_checked(*self, n)
     }
     #[inline(always)]
     fn powf(&self, n: &Self) -> Self {
-        unsafe {
-            intrinsics::powf64(*self as f64, *n)
-        }
+        check_nan("powf", &[*self, *n], unsafe {
+            math::powf64(*self as f64, *n)
+        })
     }
     #[inline(always)]
     fn exp(&self) -> Self {
-        unsafe {
-            intrinsics::expf64(*self)
-        }
+        check_nan("exp", &[*self], unsafe {
+            math::expf64(*self)
+        })
     }
     #[inline(always)]
     fn exp2(&self) -> Self {
-        unsafe {
-            intrinsics::exp2f64(*self)
-        }
+        check_nan("exp2", &[*self], unsafe {
+            math::exp2f64(*self)
+        })
     }
     #[inline(always)]
     fn ln(&self) -> Self {
-        unsafe {
-            intrinsics::logf64(*self)
-        }
+        check_nan("ln", &[*self], unsafe {
+            math::logf64(*self)
+        })
     }
     #[inline(always)]
     fn log2(&self) -> Self {
-        unsafe {
-            intrinsics::log2f64(*self)
-        }
+        check_nan("log2", &[*self], unsafe {
+            math::log2f64(*self)
+        })
     }
     #[inline(always)]
     fn log10(&self) -> Self {
-        unsafe {
-            intrinsics::log10f64(*self)
-        }
+        check_nan("log10", &[*self], unsafe {
+            math::log10f64(*self)
+        })
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::sqrt(&2.0_f64), 1.4142135623730951);
+    /// assert_eq!(Float::sqrt(&5e-324_f64), 2.2227587494850775e-162);
+    /// assert!(Float::sqrt(&-0.0_f64).is_sign_negative());
+    /// assert!(float::nan_tolerant(|| Float::sqrt(&-1.0_f64)).is_nan());
+    /// assert_eq!(Float::sqrt(&f64::INFINITY), f64::INFINITY);
+    /// ```
+    #[inline(always)]
+    fn sqrt(&self) -> Self {
+        check_nan("sqrt", &[*self], unsafe {
+            math::sqrtf64(*self)
+        })
     }
     /// ```
     /// assert_eq!(1.0_f64.cbrt(), 1.0_f64);
     /// ```
     #[inline(always)]
     fn cbrt(&self) -> Self {
-        unsafe {
-            cbrt(*self)
-        }
+        check_nan("cbrt", &[*self], unsafe {
+            math::cbrt(*self)
+        })
     }
     /// ```
     /// assert_eq!(1.0_f64.hypot(1.0_f64), 1.4142135623730951_f64);
     /// ```
     #[inline(always)]
     fn hypot(&self, other: &Self) -> Self {
-        unsafe {
-            hypot(*self, *other)
-        }
+        check_nan("hypot", &[*self, *other], unsafe {
+            math::hypot(*self, *other)
+        })
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::mul_add(&0.1_f64, &10.0, &-1.0), 5.551115123125783e-17);
+    /// assert!(float::nan_tolerant(|| Float::mul_add(&f64::INFINITY, &0.0, &1.0)).is_nan());
+    /// ```
+    #[inline(always)]
+    fn mul_add(&self, a: &Self, b: &Self) -> Self {
+        check_nan("mul_add", &[*self, *a, *b], unsafe {
+            math::fmaf64(*self, *a, *b)
+        })
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::sin(&1e-300_f64), 1e-300);
+    /// assert_eq!(Float::acos(&-1.0_f64), std::f64::consts::PI);
+    /// assert_eq!(Float::atan(&f64::INFINITY), std::f64::consts::FRAC_PI_2);
+    /// assert!(float::nan_tolerant(|| Float::asin(&1.5_f64)).is_nan());
+    /// let (sin, cos) = Float::sin_cos(&std::f64::consts::FRAC_PI_3);
+    /// assert!((sin - 0.8660254037844386).abs() < 2e-16 && (cos - 0.5).abs() < 2e-16);
+    /// ```
+    #[inline(always)]
+    fn sin(&self) -> Self {
+        check_nan("sin", &[*self], unsafe {
+            math::sinf64(*self)
+        })
+    }
+    #[inline(always)]
+    fn cos(&self) -> Self {
+        check_nan("cos", &[*self], unsafe {
+            math::cosf64(*self)
+        })
+    }
+    #[inline(always)]
+    fn tan(&self) -> Self {
+        check_nan("tan", &[*self], unsafe {
+            math::tan(*self)
+        })
+    }
+    #[inline(always)]
+    fn asin(&self) -> Self {
+        check_nan("asin", &[*self], unsafe {
+            math::asin(*self)
+        })
+    }
+    #[inline(always)]
+    fn acos(&self) -> Self {
+        check_nan("acos", &[*self], unsafe {
+            math::acos(*self)
+        })
+    }
+    #[inline(always)]
+    fn atan(&self) -> Self {
+        check_nan("atan", &[*self], unsafe {
+            math::atan(*self)
+        })
+    }
+    #[inline(always)]
+    fn atan2(&self, other: &Self) -> Self {
+        check_nan("atan2", &[*self, *other], unsafe {
+            math::atan2(*self, *other)
+        })
+    }
+    #[inline(always)]
+    fn sin_cos(&self) -> (Self, Self) {
+        (Float::sin(self), Float::cos(self))
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::sinh(&1e-300_f64), 1e-300);
+    /// assert_eq!(Float::cosh(&1.0_f64), 1.5430806348152437);
+    /// assert_eq!(Float::tanh(&0.5_f64), 0.46211715726000974);
+    /// assert_eq!(Float::sinh(&1000.0_f64), f64::INFINITY);
+    /// ```
+    #[inline(always)]
+    fn sinh(&self) -> Self {
+        check_nan("sinh", &[*self], unsafe {
+            math::sinh(*self)
+        })
+    }
+    #[inline(always)]
+    fn cosh(&self) -> Self {
+        check_nan("cosh", &[*self], unsafe {
+            math::cosh(*self)
+        })
+    }
+    #[inline(always)]
+    fn tanh(&self) -> Self {
+        check_nan("tanh", &[*self], unsafe {
+            math::tanh(*self)
+        })
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::asinh(&-1e-300_f64), -1e-300);
+    /// assert_eq!(Float::acosh(&2.0_f64), 1.3169578969248166);
+    /// assert!((Float::atanh(&0.5_f64) - 0.5493061443340548).abs() < 2e-16);
+    /// assert_eq!(Float::atanh(&1e-20_f64), 1e-20);
+    /// ```
+    #[inline(always)]
+    fn asinh(&self) -> Self {
+        check_nan("asinh", &[*self], unsafe {
+            math::asinh(*self)
+        })
+    }
+    #[inline(always)]
+    fn acosh(&self) -> Self {
+        check_nan("acosh", &[*self], unsafe {
+            math::acosh(*self)
+        })
+    }
+    #[inline(always)]
+    fn atanh(&self) -> Self {
+        check_nan("atanh", &[*self], unsafe {
+            math::atanh(*self)
+        })
     }
     /// ```
     /// assert_eq!(1.0_f64.exp_m1(), 1.718281828459045_f64);
     /// ```
     #[inline(always)]
     fn exp_m1(&self) -> Self {
-        unsafe {
-            expm1(*self)
-        }
+        check_nan("exp_m1", &[*self], unsafe {
+            math::expm1(*self)
+        })
     }
     /// ```
     /// assert_eq!(1.0_f64.ln_1p(), 0.6931471805599453_f64);
     /// ```
     #[inline(always)]
     fn ln_1p(&self) -> Self {
-        unsafe {
-            log1p(*self)
-        }
+        check_nan("ln_1p", &[*self], unsafe {
+            math::log1p(*self)
+        })
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::copysign(&-1.5_f64, &f64::INFINITY), 1.5);
+    /// assert!(Float::copysign(&0.0_f64, &-f64::NAN).is_sign_negative());
+    /// assert_eq!(Float::flip_sign(&f64::INFINITY, &-1e-300), f64::NEG_INFINITY);
+    /// ```
+    #[inline(always)]
+    fn copysign(&self, sign: &Self) -> Self {
+        let magnitude = Float::to_bits(self);
+        let sign = Float::to_bits(sign);
+        Float::from_bits((magnitude & !0x8000000000000000) | (sign & 0x8000000000000000))
+    }
+    #[inline(always)]
+    fn flip_sign(&self, sign: &Self) -> Self {
+        let bits = Float::to_bits(self);
+        let sign = Float::to_bits(sign);
+        Float::from_bits(bits ^ (sign & 0x8000000000000000))
     }
     #[inline]
     fn integer_decode(&self) -> (u64, i16, i8) {
-        let bits: u64 = unsafe { mem::transmute(self) };
+        let bits = Float::to_bits(self);
         let sign: i8 = if bits >> 63 == 0 { 1 } else { -1 };
         let mut exponent: i16 = ((bits >> 52) & 0x7ff) as i16;
         let mantissa = if exponent == 0 {
@@ -361,4 +1795,182 @@ impl Float for f64 {
         exponent -= 1023 + 52;
         (mantissa, exponent, sign)
     }
+    /// ```
+    /// use float::{Float, FloatParts};
+    /// assert_eq!(5e-324_f64.into_parts(), FloatParts::new(false, -1074, 1));
+    /// assert_eq!((-0.0_f64).into_parts(), FloatParts::new(true, -1074, 0));
+    /// assert_eq!(f64::MAX.into_raw_parts(), FloatParts::new(false, 0x7fe, (1 << 52) - 1));
+    /// ```
+    #[inline]
+    fn into_parts(&self) -> FloatParts {
+        float_parts::normalize(self.into_raw_parts(), 52, 11)
+    }
+    #[inline]
+    fn into_raw_parts(&self) -> FloatParts {
+        let bits = Float::to_bits(self);
+        float_parts::raw_parts(bits, 52, 11)
+    }
+    #[inline]
+    fn parts(&self) -> FloatFields {
+        FloatFields::new(self.into_raw_parts(), 11, 52)
+    }
+    /// Rounds `mantissa * 2^exponent` to nearest even, so any mantissa width
+    /// is accepted. NaNs can only be built with `from_raw_parts`.
+    ///
+    /// ```
+    /// use float::{Float, FloatParts};
+    /// assert_eq!(f64::from_parts(FloatParts::new(false, -1, 1)), 0.5);
+    /// assert_eq!(f64::from_parts(FloatParts::new(false, 0, u64::max_value())), 18446744073709551616.0);
+    /// assert_eq!(f64::from_parts(FloatParts::new(false, -1075, 1)), 0.0);
+    /// assert_eq!(f64::from_parts(FloatParts::new(false, -1075, 3)), 1e-323);
+    /// assert_eq!(f64::from_parts(FloatParts::new(false, -1075, (1 << 53) - 1)), f64::MIN_POSITIVE);
+    /// assert_eq!(f64::from_parts(FloatParts::new(true, 971, (1 << 54) - 1)), f64::NEG_INFINITY);
+    /// assert_eq!(f64::from_parts(f64::MAX.into_parts()), f64::MAX);
+    /// ```
+    #[inline]
+    fn from_parts(parts: FloatParts) -> Self {
+        Float::from_bits(float_parts::round_parts(parts, 52, 11))
+    }
+    /// ```
+    /// use float::{Float, FloatParts};
+    /// let nan = f64::from_raw_parts(FloatParts::new(false, 0x7ff, 42));
+    /// assert!(nan.is_nan());
+    /// assert_eq!(nan.into_raw_parts().mantissa, 42);
+    /// ```
+    #[inline]
+    fn from_raw_parts(parts: FloatParts) -> Self {
+        Float::from_bits(float_parts::from_raw_parts(parts, 52, 11))
+    }
+
+    type Bits = u64;
+
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(Float::to_bits(&-2.0_f64), 0xc000000000000000);
+    /// assert!(<f64 as Float>::from_bits(0x7ff0000000000001).is_nan());
+    /// ```
+    #[inline(always)]
+    fn to_bits(&self) -> u64 {
+        f64::to_bits(*self)
+    }
+    #[inline(always)]
+    fn from_bits(bits: u64) -> Self {
+        f64::from_bits(bits)
+    }
+    /// ```
+    /// use float::Float;
+    /// let snan = f64::signaling_nan();
+    /// assert!(snan.is_nan() && snan.is_signaling_nan() && !f64::NAN.is_signaling_nan());
+    /// assert_eq!(snan.nan_payload(), Some(0x4000000000000));
+    ///
+    /// let x = f64::nan_with_payload(0xdead_beef);
+    /// assert!(x.is_nan() && !x.is_signaling_nan());
+    /// assert_eq!(Float::to_bits(&x), 0x7ff80000deadbeef);
+    /// assert_eq!(x.nan_payload(), Some(0xdead_beef));
+    /// assert_eq!(f64::nan_with_payload(0).nan_payload(), Some(0));
+    /// assert_eq!(Float::to_bits(&f64::nan_with_payload(0)), Float::to_bits(&f64::NAN));
+    /// assert_eq!(1.0_f64.nan_payload(), None);
+    ///
+    /// // the payload survives narrowing to f32 in its high bits
+    /// assert_eq!((x as f32).nan_payload(), Some(0xdead_beef >> 29));
+    /// ```
+    #[inline(always)]
+    fn signaling_nan() -> Self {
+        Float::from_bits(0x7ff4000000000000)
+    }
+    #[inline(always)]
+    fn is_signaling_nan(&self) -> bool {
+        self.is_nan() && Float::to_bits(self) & 0x0008000000000000 == 0
+    }
+    #[inline]
+    fn nan_with_payload(payload: u64) -> Self {
+        assert!(payload <= 0x0007ffffffffffff, "NaN payload wider than 51 bits");
+        Float::from_bits(0x7ff0000000000000 | 0x0008000000000000 | payload)
+    }
+    #[inline]
+    fn nan_payload(&self) -> Option<u64> {
+        if self.is_nan() {
+            Some(Float::to_bits(self) & 0x0007ffffffffffff)
+        } else {
+            None
+        }
+    }
+
+    type Wider = DoubleDouble;
+
+    #[inline(always)]
+    fn widen(&self) -> DoubleDouble {
+        DoubleDouble::from_f64(*self)
+    }
+    #[inline(always)]
+    fn narrow(wide: DoubleDouble) -> Self {
+        wide.to_f64()
+    }
+    /// Exact unless the sum overflows.
+    ///
+    /// ```
+    /// use float::Float;
+    /// let sum = 1e16_f64.widening_add(&1.0);
+    /// assert_eq!((sum.hi(), sum.lo()), (1e16, 1.0));
+    /// ```
+    #[inline]
+    fn widening_add(&self, other: &Self) -> DoubleDouble {
+        let (hi, lo) = two_sum(*self, *other);
+        DoubleDouble::new(hi, lo)
+    }
+    /// Exact unless the product overflows or underflows.
+    ///
+    /// ```
+    /// use float::Float;
+    /// let x = 1.0_f64 + f64::EPSILON;
+    /// let product = x.widening_mul(&x);
+    /// assert_eq!((product.hi(), product.lo()), (1.0 + 2.0 * f64::EPSILON, f64::EPSILON * f64::EPSILON));
+    /// ```
+    #[inline]
+    fn widening_mul(&self, other: &Self) -> DoubleDouble {
+        let (hi, lo) = two_product(*self, *other);
+        DoubleDouble::new(hi, lo)
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(1.0_f64.ulp(), f64::EPSILON);
+    /// assert_eq!(0.0_f64.ulp(), 5e-324_f64);
+    /// assert_eq!(f64::MIN_POSITIVE.ulp(), 5e-324_f64);
+    /// assert_eq!((f64::MIN_POSITIVE * 2.0_f64.powi(53)).ulp(), f64::MIN_POSITIVE * 2.0);
+    /// assert_eq!(f64::MAX.ulp(), 1.99584030953472e292_f64);
+    /// assert!(f64::NAN.ulp().is_nan());
+    /// ```
+    #[inline]
+    fn ulp(&self) -> Self {
+        let bits = Float::to_bits(self);
+        let exp = (bits >> 52) & 0x7ff;
+
+        if exp == 0x7ff {
+            return if bits & 0x000fffffffffffff == 0 { f64::INFINITY } else { *self };
+        }
+
+        let ulp: u64 = if exp <= 52 {
+            1 << (if exp == 0 { 0 } else { exp - 1 })
+        } else {
+            (exp - 52) << 52
+        };
+        Float::from_bits(ulp)
+    }
+    /// ```
+    /// use float::Float;
+    /// assert_eq!(1.0_f64.ulps_between(&(1.0 + f64::EPSILON)), 1);
+    /// assert_eq!(1.0_f64.ulps_between(&0.9999999999999999), 1);
+    /// assert_eq!((-0.0_f64).ulps_between(&5e-324), 1);
+    /// assert_eq!((-f64::MAX).ulps_between(&f64::MAX), 0xffdffffffffffffe);
+    /// ```
+    #[inline]
+    fn ulps_between(&self, other: &Self) -> u64 {
+        if self.is_nan() || other.is_nan() {
+            return u64::max_value();
+        }
+
+        let a = ordered_bits_f64(*self);
+        let b = ordered_bits_f64(*other);
+        if a > b { a.wrapping_sub(b) as u64 } else { b.wrapping_sub(a) as u64 }
+    }
 }
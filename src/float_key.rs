@@ -0,0 +1,70 @@
+use core::mem;
+
+use float::Float;
+
+
+/// An integer ordered like the IEEE 754 total order of `x`: negative NaNs,
+/// negative infinity, the negative numbers, `-0.0`, `0.0`, the positive
+/// numbers, positive infinity and positive NaNs.
+///
+/// Unlike `partial_cmp` it orders every value, so it can serve as a sort key.
+///
+/// ```
+/// use float::total_order_key;
+///
+/// let mut values = [2.5_f64, f64::NAN, 0.0, -0.0, f64::NEG_INFINITY, -f64::NAN];
+/// values.sort_by_key(|&x| total_order_key(x));
+/// assert!(values[0].is_nan() && values[0].is_sign_negative());
+/// assert_eq!(&values[1..5], &[f64::NEG_INFINITY, -0.0, 0.0, 2.5]);
+/// assert!(values[3].is_sign_positive() && values[5].is_nan());
+///
+/// assert!(total_order_key(1.0_f32) < total_order_key(1.0_f32 + f32::EPSILON));
+/// assert!(total_order_key(f32::from_bits(0x7fa00000)) < total_order_key(f32::NAN));
+/// ```
+#[inline]
+pub fn total_order_key<T: Float>(x: T) -> i64 {
+    // the encoding moved up to the sign bit; widening to f64 instead would
+    // quiet signaling NaNs
+    let width = 8 * mem::size_of::<T::Bits>() as u32;
+    let bits = (Float::to_bits(&x).into() << (64 - width)) as i64;
+    // negative values order backwards, so flip all but their sign bit
+    bits ^ ((bits >> 63) as u64 >> 1) as i64
+}
+
+
+/// Builds a `sort_by_key` key closure from a field path or a closure
+/// returning a float, through `total_order_key`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate float;
+///
+/// struct Reading {
+///     name: &'static str,
+///     value: f64,
+/// }
+///
+/// fn main() {
+///     let mut readings = vec![
+///         Reading { name: "b", value: f64::NAN },
+///         Reading { name: "a", value: 2.0 },
+///         Reading { name: "c", value: -1.0 },
+///     ];
+///
+///     readings.sort_by_key(float_key!(value));
+///     let names: Vec<_> = readings.iter().map(|r| r.name).collect();
+///     assert_eq!(names, ["c", "a", "b"]);
+///
+///     readings.sort_by_key(float_key!(|r| -r.value.abs()));
+///     assert_eq!(readings[1].name, "a");
+/// }
+/// ```
+#[macro_export]
+macro_rules! float_key {
+    (|$item:ident| $key:expr) => (
+        |$item: &_| $crate::total_order_key($key)
+    );
+    ($($field:ident).+) => (
+        |item: &_| $crate::total_order_key(item.$($field).+)
+    );
+}
@@ -0,0 +1,228 @@
+use core::fmt;
+
+
+/// The sign, exponent and mantissa of a float as separate fields.
+///
+/// `Float::into_parts` gives the normalized view, where the value is
+/// `(-1)^sign * mantissa * 2^exponent` with the implicit bit included in
+/// `mantissa` and the exponent unbiased. `Float::into_raw_parts` gives the
+/// stored fields: the biased exponent and the fraction bits without the
+/// implicit bit.
+///
+/// ```
+/// use float::{Float, FloatParts};
+///
+/// let parts = 1.5_f64.into_parts();
+/// assert_eq!(parts, FloatParts { sign: false, exponent: -52, mantissa: 3 << 51 });
+/// assert_eq!(f64::from_parts(parts), 1.5);
+///
+/// let raw = (-1.5_f32).into_raw_parts();
+/// assert_eq!(raw, FloatParts { sign: true, exponent: 127, mantissa: 1 << 22 });
+/// assert_eq!(f32::from_raw_parts(raw), -1.5);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FloatParts {
+    /// `true` for negative values, including `-0.0` and negative NaNs.
+    pub sign: bool,
+    pub exponent: i32,
+    pub mantissa: u64,
+}
+
+impl FloatParts {
+    #[inline(always)]
+    pub fn new(sign: bool, exponent: i32, mantissa: u64) -> Self {
+        FloatParts {
+            sign: sign,
+            exponent: exponent,
+            mantissa: mantissa,
+        }
+    }
+}
+
+
+/// The stored fields of a float together with their widths, for looking at
+/// an encoding: `Display` prints the fields in binary, `Debug` in binary and
+/// hex.
+///
+/// ```
+/// use float::Float;
+///
+/// let fields = (-1.5_f32).parts();
+/// assert_eq!(fields.raw().exponent, 127);
+/// assert_eq!(format!("{}", fields), "1 01111111 10000000000000000000000");
+/// assert_eq!(format!("{:?}", fields),
+///            "FloatFields { sign: 0b1 (0x1), exponent: 0b01111111 (0x7f), \
+///             mantissa: 0b10000000000000000000000 (0x400000) }");
+///
+/// let fields = 5e-324_f64.parts();
+/// assert_eq!((fields.exponent_bits(), fields.fraction_bits()), (11, 52));
+/// assert_eq!(format!("{}", fields),
+///            "0 00000000000 0000000000000000000000000000000000000000000000000001");
+/// assert!(format!("{:?}", f64::NAN.parts()).contains("exponent: 0b11111111111 (0x7ff)"));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FloatFields {
+    raw: FloatParts,
+    exponent_bits: u32,
+    fraction_bits: u32,
+}
+
+impl FloatFields {
+    #[inline(always)]
+    pub fn new(raw: FloatParts, exponent_bits: u32, fraction_bits: u32) -> Self {
+        FloatFields {
+            raw: raw,
+            exponent_bits: exponent_bits,
+            fraction_bits: fraction_bits,
+        }
+    }
+
+    /// The fields as `Float::into_raw_parts` gives them.
+    #[inline(always)]
+    pub fn raw(&self) -> FloatParts {
+        self.raw
+    }
+
+    #[inline(always)]
+    pub fn exponent_bits(&self) -> u32 {
+        self.exponent_bits
+    }
+
+    #[inline(always)]
+    pub fn fraction_bits(&self) -> u32 {
+        self.fraction_bits
+    }
+
+    #[inline]
+    fn fields(&self) -> [Field; 3] {
+        [
+            Field { value: self.raw.sign as u64, width: 1 },
+            Field { value: self.raw.exponent as u64, width: self.exponent_bits },
+            Field { value: self.raw.mantissa, width: self.fraction_bits },
+        ]
+    }
+}
+
+impl fmt::Display for FloatFields {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fields = self.fields();
+        write!(f, "{:b} {:0e$b} {:0m$b}", fields[0].value, fields[1].value, fields[2].value,
+               e = fields[1].width as usize, m = fields[2].width as usize)
+    }
+}
+
+impl fmt::Debug for FloatFields {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fields = self.fields();
+        f.debug_struct("FloatFields")
+            .field("sign", &fields[0])
+            .field("exponent", &fields[1])
+            .field("mantissa", &fields[2])
+            .finish()
+    }
+}
+
+/// A field value printed in binary at its full width, then in hex.
+struct Field {
+    value: u64,
+    width: u32,
+}
+
+impl fmt::Debug for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0b{:0w$b} (0x{:x})", self.value, self.value, w = self.width as usize)
+    }
+}
+
+
+/// Splits the bits of a float with `fraction_bits` stored mantissa bits and
+/// `exponent_bits` exponent bits into its raw fields.
+#[inline]
+pub(crate) fn raw_parts(bits: u64, fraction_bits: u32, exponent_bits: u32) -> FloatParts {
+    FloatParts {
+        sign: (bits >> (fraction_bits + exponent_bits)) & 1 == 1,
+        exponent: ((bits >> fraction_bits) & ((1 << exponent_bits) - 1)) as i32,
+        mantissa: bits & ((1 << fraction_bits) - 1),
+    }
+}
+
+/// Inverse of `raw_parts`, keeping only the bits that fit each field.
+#[inline]
+pub(crate) fn from_raw_parts(parts: FloatParts, fraction_bits: u32, exponent_bits: u32) -> u64 {
+    let sign = (parts.sign as u64) << (fraction_bits + exponent_bits);
+    let exponent = (parts.exponent as u64 & ((1 << exponent_bits) - 1)) << fraction_bits;
+    sign | exponent | (parts.mantissa & ((1 << fraction_bits) - 1))
+}
+
+/// Converts raw fields to the normalized view.
+#[inline]
+pub(crate) fn normalize(raw: FloatParts, fraction_bits: u32, exponent_bits: u32) -> FloatParts {
+    let bias = (1 << (exponent_bits - 1)) - 1;
+
+    if raw.exponent == 0 {
+        FloatParts::new(raw.sign, 1 - bias - fraction_bits as i32, raw.mantissa)
+    } else {
+        FloatParts::new(raw.sign, raw.exponent - bias - fraction_bits as i32,
+                        raw.mantissa | 1 << fraction_bits)
+    }
+}
+
+/// Returns the bits of `(-1)^sign * mantissa * 2^exponent` rounded to nearest
+/// even, overflowing to infinity and underflowing through the subnormals to
+/// zero.
+pub(crate) fn round_parts(parts: FloatParts, fraction_bits: u32, exponent_bits: u32) -> u64 {
+    let sign = (parts.sign as u64) << (fraction_bits + exponent_bits);
+    let infinity = ((1_u64 << exponent_bits) - 1) << fraction_bits;
+    let bias = (1_i64 << (exponent_bits - 1)) - 1;
+    let digits = fraction_bits as i64 + 1;
+
+    if parts.mantissa == 0 {
+        return sign;
+    }
+
+    // exponent of the leading bit, and how many bits of precision it has left
+    let length = 64 - parts.mantissa.leading_zeros() as i64;
+    let leading = parts.exponent as i64 + length - 1;
+    let min_normal = 1 - bias;
+    let precision = if leading >= min_normal { digits } else { digits - (min_normal - leading) };
+
+    if leading > bias {
+        return sign | infinity;
+    }
+
+    let shift = length - precision;
+    let mantissa = if shift <= 0 {
+        parts.mantissa << -shift
+    } else {
+        round_shift(parts.mantissa, shift)
+    };
+
+    // a mantissa rounded up to the next power of two carries into the exponent
+    let bits = if leading >= min_normal {
+        (((leading - min_normal) as u64) << fraction_bits) + mantissa
+    } else {
+        mantissa
+    };
+
+    sign | if bits >= infinity { infinity } else { bits }
+}
+
+/// `x / 2^shift` rounded to nearest even, for `shift >= 1`.
+#[inline]
+fn round_shift(x: u64, shift: i64) -> u64 {
+    if shift > 64 {
+        return 0;
+    }
+
+    let (quotient, remainder, half) = if shift == 64 {
+        (0, x, 1 << 63)
+    } else {
+        (x >> shift, x & ((1 << shift) - 1), 1 << (shift - 1))
+    };
+
+    if remainder > half || (remainder == half && quotient & 1 == 1) {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
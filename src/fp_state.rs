@@ -0,0 +1,181 @@
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64",
+          all(target_arch = "arm", target_feature = "vfp2"),
+          all(any(target_arch = "riscv32", target_arch = "riscv64"), target_feature = "f")))]
+use core::arch::asm;
+
+
+/// Snapshot of the floating-point control and status registers of the
+/// current core, for code that runs the crate's math from interrupt handlers
+/// and must hand the interrupted context its rounding mode, exception flags
+/// and flush-to-zero settings back untouched.
+///
+/// | target | saved registers |
+/// |--------|-----------------|
+/// | x86, x86_64 | MXCSR (with SSE) and the x87 control word |
+/// | aarch64 | FPCR and FPSR |
+/// | arm with VFP | FPSCR |
+/// | riscv with F | FCSR |
+///
+/// On other targets there is no state to save and both operations are no-ops.
+///
+/// ```
+/// use float::FpState;
+///
+/// let state = FpState::save();
+/// let x = FpState::preserve(|| 1.0_f64 / 3.0);
+/// assert_eq!(x, 1.0 / 3.0);
+/// assert_eq!(FpState::save(), state);
+/// state.restore();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FpState {
+    registers: [u64; 2],
+}
+
+impl FpState {
+    /// Reads the current floating-point environment.
+    #[inline]
+    pub fn save() -> Self {
+        FpState {
+            registers: read_registers(),
+        }
+    }
+
+    /// Writes this snapshot back to the floating-point environment.
+    #[inline]
+    pub fn restore(&self) {
+        write_registers(&self.registers);
+    }
+
+    /// Runs `f` and restores the environment that was active before it,
+    /// whatever `f` did to the rounding mode or exception flags.
+    #[inline]
+    pub fn preserve<F, R>(f: F) -> R
+        where F: FnOnce() -> R,
+    {
+        let state = FpState::save();
+        let result = f();
+        state.restore();
+        result
+    }
+
+    /// Raw register contents, in the order listed in the type documentation.
+    #[inline(always)]
+    pub fn registers(&self) -> [u64; 2] {
+        self.registers
+    }
+}
+
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+fn read_registers() -> [u64; 2] {
+    let mut control_word: u16 = 0;
+    unsafe {
+        asm!("fnstcw [{}]", in(reg) &mut control_word, options(nostack, preserves_flags));
+    }
+    [read_mxcsr() as u64, control_word as u64]
+}
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+fn write_registers(registers: &[u64; 2]) {
+    let control_word = registers[1] as u16;
+    write_mxcsr(registers[0] as u32);
+    unsafe {
+        asm!("fldcw [{}]", in(reg) &control_word, options(nostack, preserves_flags));
+    }
+}
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse"))]
+#[inline]
+fn read_mxcsr() -> u32 {
+    let mut mxcsr: u32 = 0;
+    unsafe {
+        asm!("stmxcsr [{}]", in(reg) &mut mxcsr, options(nostack, preserves_flags));
+    }
+    mxcsr
+}
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse"))]
+#[inline]
+fn write_mxcsr(mxcsr: u32) {
+    unsafe {
+        asm!("ldmxcsr [{}]", in(reg) &mxcsr, options(nostack, preserves_flags));
+    }
+}
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(target_feature = "sse")))]
+#[inline(always)]
+fn read_mxcsr() -> u32 {
+    0
+}
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(target_feature = "sse")))]
+#[inline(always)]
+fn write_mxcsr(_: u32) {}
+
+#[cfg(target_arch = "aarch64")]
+#[inline]
+fn read_registers() -> [u64; 2] {
+    let fpcr: u64;
+    let fpsr: u64;
+    unsafe {
+        asm!("mrs {}, fpcr", out(reg) fpcr, options(nomem, nostack, preserves_flags));
+        asm!("mrs {}, fpsr", out(reg) fpsr, options(nomem, nostack, preserves_flags));
+    }
+    [fpcr, fpsr]
+}
+#[cfg(target_arch = "aarch64")]
+#[inline]
+fn write_registers(registers: &[u64; 2]) {
+    unsafe {
+        asm!("msr fpcr, {}", in(reg) registers[0], options(nomem, nostack, preserves_flags));
+        asm!("msr fpsr, {}", in(reg) registers[1], options(nomem, nostack, preserves_flags));
+    }
+}
+
+#[cfg(all(target_arch = "arm", target_feature = "vfp2"))]
+#[inline]
+fn read_registers() -> [u64; 2] {
+    let fpscr: u32;
+    unsafe {
+        asm!("vmrs {}, fpscr", out(reg) fpscr, options(nomem, nostack, preserves_flags));
+    }
+    [fpscr as u64, 0]
+}
+#[cfg(all(target_arch = "arm", target_feature = "vfp2"))]
+#[inline]
+fn write_registers(registers: &[u64; 2]) {
+    let fpscr = registers[0] as u32;
+    unsafe {
+        asm!("vmsr fpscr, {}", in(reg) fpscr, options(nomem, nostack, preserves_flags));
+    }
+}
+
+#[cfg(all(any(target_arch = "riscv32", target_arch = "riscv64"), target_feature = "f"))]
+#[inline]
+fn read_registers() -> [u64; 2] {
+    let fcsr: usize;
+    unsafe {
+        asm!("frcsr {}", out(reg) fcsr, options(nomem, nostack, preserves_flags));
+    }
+    [fcsr as u64, 0]
+}
+#[cfg(all(any(target_arch = "riscv32", target_arch = "riscv64"), target_feature = "f"))]
+#[inline]
+fn write_registers(registers: &[u64; 2]) {
+    let fcsr = registers[0] as usize;
+    unsafe {
+        asm!("fscsr {}", in(reg) fcsr, options(nomem, nostack, preserves_flags));
+    }
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64",
+              all(target_arch = "arm", target_feature = "vfp2"),
+              all(any(target_arch = "riscv32", target_arch = "riscv64"), target_feature = "f"))))]
+#[inline(always)]
+fn read_registers() -> [u64; 2] {
+    [0, 0]
+}
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64",
+              all(target_arch = "arm", target_feature = "vfp2"),
+              all(any(target_arch = "riscv32", target_arch = "riscv64"), target_feature = "f"))))]
+#[inline(always)]
+fn write_registers(_: &[u64; 2]) {}
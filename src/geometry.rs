@@ -0,0 +1,454 @@
+//! Geometric helpers written to stay correct on the degenerate and
+//! borderline inputs real meshes and scenes are full of.
+//!
+//! Points are `(x, y)` tuples in the plane and `[x, y, z]` arrays in space;
+//! the normalization helpers take vectors of any length as slices.
+
+use expansion::{two_product, two_sum};
+use float::Float;
+use float_parts::FloatParts;
+
+
+/// Barycentric coordinates `[wa, wb, wc]` of `p` in the triangle `a, b, c`,
+/// so that `p = wa a + wb b + wc c` and the weights sum to one. Up to
+/// rounding they are all in `[0, 1]` exactly when `p` is inside or on the
+/// triangle.
+///
+/// The areas are evaluated with error-free products, which removes the
+/// cancellation between them, but the coordinate differences they are taken
+/// of are rounded, so this is not an exact orientation test: a point within a
+/// few ulps of an edge may get a weight of the wrong sign. The triangle
+/// counts as degenerate, and `None` is returned, when its doubled area is
+/// within `tolerance_ulps` units in the last place of the products it is the
+/// difference of; a tolerance of zero rejects only vertices that are
+/// collinear after rounding the edge vectors.
+///
+/// ```
+/// use float::geometry;
+///
+/// let weights = geometry::barycentric((1.0_f64, 1.0), (0.0, 0.0), (4.0, 0.0), (0.0, 4.0), 4).unwrap();
+/// assert_eq!(weights, [0.5, 0.25, 0.25]);
+/// assert_eq!(geometry::interpolate_barycentric(weights, 10.0, 20.0, 40.0), 20.0);
+///
+/// // collinear, and collinear but for rounding
+/// assert_eq!(geometry::barycentric((1.0_f64, 1.0), (0.0, 0.0), (1.0, 1.0), (2.0, 2.0), 0), None);
+/// let sliver = ((0.0_f64, 0.0), (0.1, 0.3), (0.3, 0.9000000000000001));
+/// assert!(geometry::barycentric((0.0, 0.0), sliver.0, sliver.1, sliver.2, 0).is_some());
+/// assert_eq!(geometry::barycentric((0.0, 0.0), sliver.0, sliver.1, sliver.2, 4), None);
+/// ```
+pub fn barycentric<T: Float>(p: (T, T), a: (T, T), b: (T, T), c: (T, T), tolerance_ulps: u32) -> Option<[T; 3]> {
+    let ab = (b.0 - a.0, b.1 - a.1);
+    let ac = (c.0 - a.0, c.1 - a.1);
+    let (area, bound) = cross(ab, ac);
+    if !(area.abs() > T::from_f64(tolerance_ulps as f64) * T::epsilon() * bound) {
+        return None;
+    }
+
+    let (wa, _) = cross((b.0 - p.0, b.1 - p.1), (c.0 - p.0, c.1 - p.1));
+    let (wb, _) = cross((c.0 - p.0, c.1 - p.1), (a.0 - p.0, a.1 - p.1));
+    let (wc, _) = cross((a.0 - p.0, a.1 - p.1), (b.0 - p.0, b.1 - p.1));
+
+    // the sub-areas add up to the area only up to rounding, normalizing by
+    // their sum keeps the weights summing to one
+    let total = wa + wb + wc;
+    Some([wa / total, wb / total, wc / total])
+}
+
+/// Interpolates the vertex values `va, vb, vc` with barycentric `weights`.
+#[inline]
+pub fn interpolate_barycentric<T: Float>(weights: [T; 3], va: T, vb: T, vc: T) -> T {
+    weights[0] * va + weights[1] * vb + weights[2] * vc
+}
+
+
+/// Bilinear coordinates `(u, v)` of `p` in the quadrilateral with corners
+/// `p00, p10, p11, p01` in order around it, the inverse of
+/// `p = (1 - u)(1 - v) p00 + u (1 - v) p10 + u v p11 + (1 - u) v p01`.
+///
+/// Both coordinates are in `[0, 1]` for points inside a convex quad. The
+/// quadratic for `v` is solved in the form that avoids cancellation and falls
+/// back to the linear solution for parallelograms. `None` is returned for a
+/// quad whose area is within `tolerance_ulps` units in the last place of
+/// zero, as for `barycentric`, and for points with no real solution.
+///
+/// ```
+/// use float::geometry;
+///
+/// let quad = [(0.0_f64, 0.0), (4.0, 0.0), (6.0, 4.0), (0.0, 2.0)];
+/// let (u, v) = geometry::inverse_bilinear((2.5, 1.5), quad, 4).unwrap();
+/// assert!((u - 0.5).abs() < 1e-15 && (v - 0.5).abs() < 1e-15);
+/// assert_eq!(geometry::interpolate_bilinear(u, v, [0.0, 1.0, 3.0, 2.0]), 1.5);
+///
+/// let parallelogram = [(0.0_f64, 0.0), (2.0, 0.0), (3.0, 1.0), (1.0, 1.0)];
+/// assert_eq!(geometry::inverse_bilinear((1.5, 0.5), parallelogram, 4), Some((0.5, 0.5)));
+/// assert_eq!(geometry::inverse_bilinear((1.0, 1.0), [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 3.0)], 4), None);
+/// ```
+pub fn inverse_bilinear<T: Float>(p: (T, T), quad: [(T, T); 4], tolerance_ulps: u32) -> Option<(T, T)> {
+    let (p00, p10, p11, p01) = (quad[0], quad[1], quad[2], quad[3]);
+    let zero = T::from_f64(0.0);
+    let one = T::from_f64(1.0);
+    let tolerance = T::from_f64(tolerance_ulps as f64) * T::epsilon();
+
+    let (d1, b1) = cross((p11.0 - p00.0, p11.1 - p00.1), (p01.0 - p10.0, p01.1 - p10.1));
+    if !(d1.abs() > tolerance * b1) {
+        return None;
+    }
+
+    let e = (p10.0 - p00.0, p10.1 - p00.1);
+    let f = (p01.0 - p00.0, p01.1 - p00.1);
+    let g = (p00.0 - p10.0 + p11.0 - p01.0, p00.1 - p10.1 + p11.1 - p01.1);
+    let h = (p.0 - p00.0, p.1 - p00.1);
+
+    // k2 v^2 + k1 v + k0 = 0
+    let (k2, k2_bound) = cross(g, f);
+    let k1 = cross(e, f).0 + cross(h, g).0;
+    let k0 = cross(h, e).0;
+
+    let v = if !(k2.abs() > tolerance * k2_bound) {
+        -k0 / k1
+    } else {
+        let discriminant = k1 * k1 - T::from_f64(4.0) * k0 * k2;
+        if discriminant < zero {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        let q = T::from_f64(-0.5) * (k1 + if k1 < zero { -root } else { root });
+        let (v1, v2) = (q / k2, k0 / q);
+
+        // prefer the root inside the quad, otherwise the nearer one
+        let distance = |v: T| if v < zero { -v } else if v > one { v - one } else { zero };
+        if distance(v2) < distance(v1) { v2 } else { v1 }
+    };
+
+    // u from the better conditioned coordinate
+    let (dx, dy) = (e.0 + g.0 * v, e.1 + g.1 * v);
+    let u = if dx.abs() > dy.abs() {
+        (h.0 - f.0 * v) / dx
+    } else {
+        (h.1 - f.1 * v) / dy
+    };
+    Some((u, v))
+}
+
+/// Interpolates the corner values `[v00, v10, v11, v01]` at the bilinear
+/// coordinates `(u, v)`.
+#[inline]
+pub fn interpolate_bilinear<T: Float>(u: T, v: T, values: [T; 4]) -> T {
+    let (v00, v10, v11, v01) = (values[0], values[1], values[2], values[3]);
+    let bottom = v00 + u * (v10 - v00);
+    let top = v01 + u * (v11 - v01);
+    bottom + v * (top - bottom)
+}
+
+
+/// The part of the ray `origin + t direction`, `t >= 0`, inside the axis
+/// aligned box `[box_min, box_max]`, as the parameters `(t_enter, t_exit)`
+/// where it enters and leaves, or `None` if it misses.
+///
+/// This is the slab method with the pitfalls handled explicitly. Direction
+/// components of either zero divide to the matching signed infinity, which
+/// the slab bounds handle correctly, and the `0 * inf` NaN of an origin
+/// exactly on a slab plane is discarded rather than poisoning the interval,
+/// so rays along a face count as hitting it. The exit parameter is widened by
+/// `1 + 2 gamma(3)` (Ize, "Robust BVH Ray Traversal") so rounding never makes
+/// a grazing hit miss. A NaN in the ray or the box is a miss.
+///
+/// ```
+/// use float::geometry;
+///
+/// let (min, max) = ([-1.0_f64, -1.0, -1.0], [1.0, 1.0, 1.0]);
+/// let (enter, exit) = geometry::ray_aabb([-3.0, 0.0, 0.0], [1.0, 0.0, 0.0], min, max).unwrap();
+/// assert_eq!(enter, 2.0);
+/// assert!(exit >= 4.0 && exit < 4.0 + 1e-14);
+///
+/// // parallel to a slab, with the origin on its plane, with either signed zero
+/// assert!(geometry::ray_aabb([-3.0, 1.0, 0.0], [1.0, 0.0, 0.0], min, max).is_some());
+/// assert!(geometry::ray_aabb([-3.0, 1.0, 0.0], [1.0, -0.0, 0.0], min, max).is_some());
+/// assert_eq!(geometry::ray_aabb([-3.0, 1.5, 0.0], [1.0, -0.0, 0.0], min, max), None);
+///
+/// // from inside, and pointing away
+/// assert_eq!(geometry::ray_aabb([0.0, 0.0, 0.0], [0.0, 0.0, 1.0], min, max).unwrap().0, 0.0);
+/// assert_eq!(geometry::ray_aabb([0.0, 0.0, 3.0], [0.0, 0.0, 1.0], min, max), None);
+/// ```
+pub fn ray_aabb<T: Float>(origin: [T; 3], direction: [T; 3], box_min: [T; 3], box_max: [T; 3]) -> Option<(T, T)> {
+    let zero = T::from_f64(0.0);
+    let mut enter = zero;
+    let mut exit = T::infinity();
+
+    for axis in 0..3 {
+        if origin[axis].is_nan() || direction[axis].is_nan() || box_min[axis].is_nan() || box_max[axis].is_nan() {
+            return None;
+        }
+        let inverse = direction[axis].recip();
+        let near = (box_min[axis] - origin[axis]) * inverse;
+        let far = (box_max[axis] - origin[axis]) * inverse;
+
+        // ordered by the sign of the inverse rather than by comparing, a NaN
+        // only comes from an origin on the plane of a parallel slab and must
+        // stay on its side, where it leaves the interval as it is
+        let (near, far) = if inverse.is_sign_negative() { (far, near) } else { (near, far) };
+        if near > enter {
+            enter = near;
+        }
+        if far < exit {
+            exit = far;
+        }
+    }
+
+    // gamma(3) = 3u / (1 - 3u) with the unit roundoff u
+    let three_u = T::from_f64(1.5) * T::epsilon();
+    let exit = exit * (T::from_f64(1.0) + T::from_f64(2.0) * three_u / (T::from_f64(1.0) - three_u));
+    if enter <= exit { Some((enter, exit)) } else { None }
+}
+
+/// The part of the ray `origin + t direction`, `t >= 0`, inside the sphere
+/// of `radius` around `centre`, as `(t_enter, t_exit)`, or `None` if it
+/// misses.
+///
+/// The discriminant is computed from the distance of the centre to the ray
+/// instead of as `b^2 - 4ac`, and the roots in the form that avoids
+/// cancellation (Haines et al., "Precision Improvements for Ray/Sphere
+/// Intersection"), which keeps small spheres far from the origin from
+/// breaking up. The direction need not be normalized.
+///
+/// ```
+/// use float::geometry;
+///
+/// let (enter, exit) = geometry::ray_sphere([0.0_f64, 0.0, -5.0], [0.0, 0.0, 2.0], [0.0, 0.0, 0.0], 1.0).unwrap();
+/// assert_eq!((enter, exit), (2.0, 3.0));
+///
+/// // a small sphere far away still shows its full width
+/// let (enter, exit) = geometry::ray_sphere([0.0_f32, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0, 1e4], 0.01).unwrap();
+/// assert!((exit - enter - 0.02).abs() < 2e-3);
+///
+/// assert_eq!(geometry::ray_sphere([0.0_f64, 2.0, -5.0], [0.0, 0.0, 1.0], [0.0, 0.0, 0.0], 1.0), None);
+/// assert_eq!(geometry::ray_sphere([0.0_f64, 0.0, 5.0], [0.0, 0.0, 1.0], [0.0, 0.0, 0.0], 1.0), None);
+/// ```
+pub fn ray_sphere<T: Float>(origin: [T; 3], direction: [T; 3], centre: [T; 3], radius: T) -> Option<(T, T)> {
+    let zero = T::from_f64(0.0);
+    let f = [origin[0] - centre[0], origin[1] - centre[1], origin[2] - centre[2]];
+    let a = dot(direction, direction);
+    let b = -dot(f, direction);
+    if !(a > zero) {
+        return None;
+    }
+
+    // the offset of the closest point of the ray's line from the centre
+    let s = b / a;
+    let closest = [f[0] + s * direction[0], f[1] + s * direction[1], f[2] + s * direction[2]];
+    let discriminant = radius * radius - dot(closest, closest);
+    if !(discriminant >= zero) {
+        return None;
+    }
+
+    let c = dot(f, f) - radius * radius;
+    let root = (a * discriminant).sqrt();
+    let q = if b < zero { b - root } else { b + root };
+    let (t0, t1) = if q == zero {
+        (zero, zero)
+    } else {
+        let (x, y) = (c / q, q / a);
+        if x < y { (x, y) } else { (y, x) }
+    };
+
+    if t1 < zero {
+        None
+    } else {
+        Some((if t0 > zero { t0 } else { zero }, t1))
+    }
+}
+
+
+/// The Euclidean length of `v`, `sqrt(x0^2 + x1^2 + ...)`, the `hypot` of
+/// any number of components, rounded nearly correctly and without overflow
+/// or underflow in the squares. Zero for an empty slice.
+///
+/// As in `normalize_exact` the components are scaled by the power of two of
+/// the largest and the squares summed with error-free products, and the
+/// square root gets one Newton step on the exact residual, which leaves it
+/// correctly rounded in all but rare near-halfway cases. As for the C
+/// `hypot` an infinite component gives infinity even beside a NaN.
+///
+/// ```
+/// use float::geometry;
+///
+/// assert_eq!(geometry::hypot_n(&[3.0_f64, 4.0, 12.0]), 13.0);
+/// assert_eq!(geometry::hypot_n(&[1e300_f64, 1e300]), 1.4142135623730952e300);
+/// assert_eq!(geometry::hypot_n(&[3e-40_f32, 4e-40]), 5e-40);
+/// assert_eq!(geometry::hypot_n(&[0.1_f64; 10]), 0.31622776601683794);
+/// assert_eq!(geometry::hypot_n::<f32>(&[]), 0.0);
+/// assert_eq!(geometry::hypot_n(&[f64::NAN, f64::NEG_INFINITY]), f64::INFINITY);
+/// assert!(geometry::hypot_n(&[f64::NAN, 1.0]).is_nan());
+///
+/// // the naive sum of squares overflows
+/// let v = [1e200_f64; 100];
+/// assert_eq!(v.iter().map(|x| x * x).sum::<f64>().sqrt(), f64::INFINITY);
+/// assert_eq!(geometry::hypot_n(&v), 9.999999999999999e200);
+/// ```
+pub fn hypot_n<T: Float>(v: &[T]) -> T {
+    let zero = T::from_f64(0.0);
+
+    let mut max = zero;
+    let mut nan = false;
+    for &x in v {
+        let x = x.abs();
+        if x.is_nan() {
+            nan = true;
+        } else if x > max {
+            max = x;
+        }
+    }
+    if max.is_infinite() {
+        return max;
+    }
+    if nan {
+        return T::nan();
+    }
+    if max == zero || v.len() == 1 {
+        return max;
+    }
+
+    // by a power of two putting the largest component in [1, 2)
+    let exponent = max.exponent();
+    let mut sum = zero;
+    let mut error = zero;
+    for &x in v {
+        let x = scale_by_power_of_two(x, -exponent);
+        let (p, pe) = two_product(x, x);
+        let (s, se) = two_sum(sum, p);
+        sum = s;
+        error = error + se + pe;
+    }
+
+    let mut length = (sum + error).sqrt();
+    let (square, square_error) = two_product(length, length);
+    let residual = ((sum - square) - square_error) + error;
+    length = length + residual / (length + length);
+    scale_by_power_of_two(length, exponent)
+}
+
+/// Scales `v` to unit length with one reciprocal square root, returning the
+/// length it had. Zero vectors are left alone.
+///
+/// The length may be a few units in the last place off one, and components
+/// whose squares overflow or underflow give infinite or zero results; use
+/// `normalize_exact` where that matters.
+///
+/// ```
+/// use float::geometry;
+///
+/// let mut v = [3.0_f32, 4.0];
+/// assert_eq!(geometry::normalize_fast(&mut v), 5.0);
+/// assert_eq!(v, [0.6, 0.8]);
+///
+/// let mut zero = [0.0_f64; 3];
+/// assert_eq!(geometry::normalize_fast(&mut zero), 0.0);
+/// assert_eq!(zero, [0.0; 3]);
+/// ```
+#[inline]
+pub fn normalize_fast<T: Float>(v: &mut [T]) -> T {
+    let squared = v.iter().fold(T::from_f64(0.0), |sum, &x| sum + x * x);
+    if squared == T::from_f64(0.0) {
+        return squared;
+    }
+    let length = squared.sqrt();
+    let scale = length.recip();
+    for x in v.iter_mut() {
+        *x = *x * scale;
+    }
+    length
+}
+
+/// Scales `v` to unit length so that its exact length is within one unit
+/// in the last place of one, returning the length it had. Zero vectors are
+/// left alone.
+///
+/// The vector is first scaled by its largest component, so no square
+/// overflows or underflows, and after the reciprocal square root its
+/// squared length is summed with error-free products and corrected by one
+/// more multiply, a Newton step on the exact residual. NaN components give
+/// NaNs, infinite ones NaN too.
+///
+/// ```
+/// use float::geometry;
+///
+/// let mut v = [1e30_f32, 1e30, 1e30];
+/// geometry::normalize_exact(&mut v);
+/// let length = v.iter().map(|&x| x as f64 * x as f64).sum::<f64>().sqrt();
+/// assert!((length - 1.0).abs() <= f32::EPSILON as f64);
+///
+/// let mut tiny = [3e-320_f64, -4e-320];
+/// assert!((geometry::normalize_exact(&mut tiny) - 5e-320).abs() < 1e-322);
+/// assert!((tiny[0] - 0.6).abs() < 1e-15 && (tiny[1] + 0.8).abs() < 1e-15);
+///
+/// let mut w = [0.1_f64, 0.2, 0.3, 0.4];
+/// let length = geometry::normalize_exact(&mut w);
+/// assert!((length - 0.3_f64.sqrt()).abs() < 1e-15);
+/// ```
+pub fn normalize_exact<T: Float>(v: &mut [T]) -> T {
+    let zero = T::from_f64(0.0);
+    let one = T::from_f64(1.0);
+
+    let max = v.iter().fold(zero, |max, &x| if x.abs() > max || x.is_nan() { x.abs() } else { max });
+    if max == zero || !max.is_finite() {
+        return if max == zero { zero } else { max + T::nan() };
+    }
+
+    // by a power of two, which is exact but for components too small to
+    // matter
+    let exponent = max.exponent();
+    for x in v.iter_mut() {
+        *x = scale_by_power_of_two(*x, -exponent);
+    }
+
+    let length = exact_squared_length(v).sqrt();
+    let inverse = length.recip();
+    for x in v.iter_mut() {
+        *x = *x * inverse;
+    }
+
+    // |v|^2 = 1 + d, and (1 + d)^-1/2 = 1 - d / 2 to first order
+    let d = exact_squared_length(v) - one;
+    let correction = one - d / T::from_f64(2.0);
+    for x in v.iter_mut() {
+        *x = *x * correction;
+    }
+    scale_by_power_of_two(length, exponent)
+}
+
+/// `x * 2^exponent` through the parts, for exponents whose power of two is
+/// out of range.
+#[inline]
+fn scale_by_power_of_two<T: Float>(x: T, exponent: i32) -> T {
+    let parts = x.into_parts();
+    T::from_parts(FloatParts::new(parts.sign, parts.exponent + exponent, parts.mantissa))
+}
+
+/// The squared length with each square split exactly and the parts summed
+/// with compensation, so the result is off by about one rounding.
+#[inline]
+fn exact_squared_length<T: Float>(v: &[T]) -> T {
+    let mut sum = T::from_f64(0.0);
+    let mut error = T::from_f64(0.0);
+    for &x in v {
+        let (p, pe) = two_product(x, x);
+        let (s, se) = two_sum(sum, p);
+        sum = s;
+        error = error + se + pe;
+    }
+    sum + error
+}
+
+#[inline(always)]
+fn dot<T: Float>(u: [T; 3], v: [T; 3]) -> T {
+    u[0] * v[0] + u[1] * v[1] + u[2] * v[2]
+}
+
+/// `u.x v.y - u.y v.x` with the products split exactly, and the sum of the
+/// magnitudes of the products, the scale its rounding error is relative to.
+#[inline]
+fn cross<T: Float>(u: (T, T), v: (T, T)) -> (T, T) {
+    let (p, pe) = two_product(u.0, v.1);
+    let (q, qe) = two_product(u.1, v.0);
+    ((p - q) + (pe - qe), p.abs() + q.abs())
+}
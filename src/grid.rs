@@ -0,0 +1,407 @@
+//! Sampling and analysis of row-major float grids, such as lookup tables,
+//! scalar fields and images.
+//!
+//! A grid is a `&[T]` of `width * height` values with `x` running along a
+//! row, so the sample at column `x` and row `y` is `values[y * width + x]`.
+//! Coordinates are in samples: integer coordinates hit the stored values.
+
+use collections::vec::Vec;
+
+use expansion::two_sum;
+use float::Float;
+
+
+/// What sampling does with coordinates outside the grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Border {
+    /// Repeats the edge samples.
+    Clamp,
+    /// Tiles the grid, the sample after the last one being the first.
+    Wrap,
+}
+
+impl Default for Border {
+    #[inline(always)]
+    fn default() -> Self {
+        Border::Clamp
+    }
+}
+
+
+/// Bilinear interpolation of the grid at `(x, y)`. Infinite coordinates
+/// give the edge samples with `Clamp` and NaN with `Wrap`.
+///
+/// ```
+/// use float::grid::{self, Border};
+///
+/// let values = [0.0_f64, 1.0,
+///               2.0, 3.0];
+/// assert_eq!(grid::bilinear(&values, 2, 0.5, 0.5, Border::Clamp), 1.5);
+/// assert_eq!(grid::bilinear(&values, 2, 5.0, -1.0, Border::Clamp), 1.0);
+/// assert_eq!(grid::bilinear(&values, 2, 1.5, 0.0, Border::Wrap), 0.5);
+///
+/// assert_eq!(grid::bilinear(&values, 2, f64::INFINITY, 1e300, Border::Clamp), 3.0);
+/// assert_eq!(grid::bilinear(&values, 2, -1e300, 0.0, Border::Wrap), 0.0);
+/// assert!(grid::bilinear(&values, 2, f64::INFINITY, 0.0, Border::Wrap).is_nan());
+/// ```
+pub fn bilinear<T: Float>(values: &[T], width: usize, x: T, y: T, border: Border) -> T {
+    let height = grid_height(values, width);
+    if undefined(x, y, border) {
+        return T::nan();
+    }
+
+    let (column, fx) = split(x, width, border);
+    let (row, fy) = split(y, height, border);
+    let at = |i: i64, j: i64| {
+        values[index(j, height, border) * width + index(i, width, border)]
+    };
+
+    let (next_column, next_row) = (column.saturating_add(1), row.saturating_add(1));
+    let top = lerp(fx, at(column, row), at(next_column, row));
+    let bottom = lerp(fx, at(column, next_row), at(next_column, next_row));
+    lerp(fy, top, bottom)
+}
+
+/// Bicubic interpolation of the grid at `(x, y)` with the Catmull-Rom
+/// kernel, which passes through the samples, has a continuous gradient and
+/// reproduces linear data away from the borders.
+///
+/// ```
+/// use float::grid::{self, Border};
+///
+/// // f(x, y) = x + 2 y on a 4 x 4 grid
+/// let values: Vec<f32> = (0..16).map(|i| (i % 4) as f32 + 2.0 * (i / 4) as f32).collect();
+/// assert_eq!(grid::bicubic(&values, 4, 1.5, 1.25, Border::Clamp), 4.0);
+/// assert_eq!(grid::bicubic(&values, 4, 3.0, 2.0, Border::Clamp), 7.0);
+/// assert_eq!(grid::bicubic(&values, 4, -f32::INFINITY, 1e30, Border::Clamp), 6.0);
+/// ```
+pub fn bicubic<T: Float>(values: &[T], width: usize, x: T, y: T, border: Border) -> T {
+    let height = grid_height(values, width);
+    if undefined(x, y, border) {
+        return T::nan();
+    }
+
+    let (column, fx) = split(x, width, border);
+    let (row, fy) = split(y, height, border);
+    let wx = catmull_rom(fx);
+    let wy = catmull_rom(fy);
+
+    let mut sum = T::from_f64(0.0);
+    for (j, &weight_y) in wy.iter().enumerate() {
+        let offset = index(row.saturating_add(j as i64 - 1), height, border) * width;
+        let mut line = T::from_f64(0.0);
+        for (i, &weight_x) in wx.iter().enumerate() {
+            line = line + weight_x * values[offset + index(column.saturating_add(i as i64 - 1), width, border)];
+        }
+        sum = sum + weight_y * line;
+    }
+    sum
+}
+
+
+#[inline]
+// `usize::is_multiple_of` needs Rust 1.87
+#[allow(clippy::manual_is_multiple_of)]
+fn grid_height<T>(values: &[T], width: usize) -> usize {
+    assert!(width > 0 && !values.is_empty() && values.len() % width == 0,
+            "grid length must be a non-zero multiple of its width");
+    values.len() / width
+}
+
+/// Whether `(x, y)` has no sample: NaN, or infinite on a tiled grid.
+#[inline]
+fn undefined<T: Float>(x: T, y: T, border: Border) -> bool {
+    match border {
+        Border::Clamp => x.is_nan() || y.is_nan(),
+        Border::Wrap => !(x.is_finite() && y.is_finite()),
+    }
+}
+
+/// Splits a coordinate into the sample before it and the offset from it,
+/// after moving it within one period of the grid for `Wrap`, which the
+/// remainder does exactly, or to just past its ends for `Clamp`, so the
+/// sample index always fits.
+#[inline]
+fn split<T: Float>(x: T, len: usize, border: Border) -> (i64, T) {
+    let len = T::from_f64(len as f64);
+    let x = match border {
+        Border::Clamp => {
+            let low = T::from_f64(-1.0);
+            if x < low { low } else if x > len { len } else { x }
+        },
+        Border::Wrap => x % len,
+    };
+    let floor = x.floor();
+    (floor.to_f64() as i64, x - floor)
+}
+
+#[inline]
+fn index(i: i64, len: usize, border: Border) -> usize {
+    let len = len as i64;
+    match border {
+        Border::Clamp => if i < 0 { 0 } else if i >= len { len as usize - 1 } else { i as usize },
+        Border::Wrap => (((i % len) + len) % len) as usize,
+    }
+}
+
+#[inline(always)]
+fn lerp<T: Float>(t: T, a: T, b: T) -> T {
+    a + t * (b - a)
+}
+
+/// The Catmull-Rom weights of the samples at `-1, 0, 1, 2` for the offset
+/// `t` in `[0, 1)`.
+#[inline]
+fn catmull_rom<T: Float>(t: T) -> [T; 4] {
+    let half = T::from_f64(0.5);
+    let two = T::from_f64(2.0);
+    let three = T::from_f64(3.0);
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    [
+        half * (-t3 + two * t2 - t),
+        half * (three * t3 - T::from_f64(5.0) * t2 + two),
+        half * (-three * t3 + T::from_f64(4.0) * t2 + t),
+        half * (t3 - t2),
+    ]
+}
+
+
+/// Marching squares: calls `segment` with the two end points of every line
+/// segment of the contour of the grid at `threshold`, cell by cell.
+///
+/// Samples at or above `threshold` count as inside. The end points lie on
+/// the cell edges, placed by linear interpolation between the samples, so
+/// segments of neighbouring cells meet exactly; ambiguous saddle cells are
+/// resolved by the average of their four samples. Cells with a NaN sample
+/// are skipped. Nothing is allocated, collect the segments in the callback
+/// if they are needed together.
+///
+/// ```
+/// use float::grid;
+///
+/// let values = [0.0_f64, 0.0, 0.0,
+///               0.0, 1.0, 0.0,
+///               0.0, 0.0, 0.0];
+/// let mut segments = Vec::new();
+/// grid::marching_squares(&values, 3, 0.5, |a, b| segments.push((a, b)));
+///
+/// assert_eq!(segments.len(), 4);
+/// assert!(segments.contains(&((0.5, 1.0), (1.0, 0.5))));
+/// for &((x0, y0), (x1, y1)) in segments.iter() {
+///     assert_eq!((x0 - 1.0).abs() + (y0 - 1.0).abs(), 0.5);
+///     assert_eq!((x1 - 1.0).abs() + (y1 - 1.0).abs(), 0.5);
+/// }
+/// ```
+pub fn marching_squares<T, F>(values: &[T], width: usize, threshold: T, mut segment: F)
+    where T: Float,
+          F: FnMut((T, T), (T, T)),
+{
+    let height = grid_height(values, width);
+    let quarter = T::from_f64(0.25);
+
+    for y in 0..height.saturating_sub(1) {
+        for x in 0..width - 1 {
+            let top_left = values[y * width + x];
+            let top_right = values[y * width + x + 1];
+            let bottom_left = values[(y + 1) * width + x];
+            let bottom_right = values[(y + 1) * width + x + 1];
+            if top_left.is_nan() || top_right.is_nan() || bottom_left.is_nan() || bottom_right.is_nan() {
+                continue;
+            }
+
+            let case = ((top_left >= threshold) as u8) << 3 |
+                ((top_right >= threshold) as u8) << 2 |
+                ((bottom_right >= threshold) as u8) << 1 |
+                (bottom_left >= threshold) as u8;
+            if case == 0 || case == 15 {
+                continue;
+            }
+
+            let (fx, fy) = (T::from_f64(x as f64), T::from_f64(y as f64));
+            let one = T::from_f64(1.0);
+            let top = (fx + crossing(top_left, top_right, threshold), fy);
+            let bottom = (fx + crossing(bottom_left, bottom_right, threshold), fy + one);
+            let left = (fx, fy + crossing(top_left, bottom_left, threshold));
+            let right = (fx + one, fy + crossing(top_right, bottom_right, threshold));
+
+            let centre_inside = (top_left + top_right + bottom_left + bottom_right) * quarter >= threshold;
+
+            match case {
+                1 | 14 => segment(left, bottom),
+                2 | 13 => segment(bottom, right),
+                3 | 12 => segment(left, right),
+                4 | 11 => segment(top, right),
+                6 | 9 => segment(top, bottom),
+                7 | 8 => segment(left, top),
+                5 => if centre_inside {
+                    segment(left, top);
+                    segment(bottom, right);
+                } else {
+                    segment(top, right);
+                    segment(left, bottom);
+                },
+                _ => if centre_inside {
+                    segment(top, right);
+                    segment(left, bottom);
+                } else {
+                    segment(left, top);
+                    segment(bottom, right);
+                },
+            }
+        }
+    }
+}
+
+/// Where the line from `a` to `b` crosses `threshold`, as a fraction of it.
+#[inline]
+fn crossing<T: Float>(a: T, b: T, threshold: T) -> T {
+    (threshold - a) / (b - a)
+}
+
+
+/// Integral image of a grid: every entry holds the sum of the samples above
+/// and to the left of it, so the sum over any rectangle takes four lookups.
+///
+/// The running sums quickly dwarf single samples, and the four-corner
+/// difference then cancels most of their digits. Every entry is therefore
+/// kept as an unevaluated sum of two `T` built with error-free additions, and
+/// rectangle sums are formed from both halves, leaving them accurate to
+/// about the precision of `T` relative to the rectangle itself.
+///
+/// ```
+/// use float::grid::SummedAreaTable;
+///
+/// let width = 200;
+/// let values: Vec<f32> = (0..width * 200).map(|i| 1e4 + (i % 3) as f32 * 0.25).collect();
+/// let table = SummedAreaTable::new(&values, width);
+///
+/// let mut exact = 0.0;
+/// for y in 197..200 {
+///     for x in 197..200 {
+///         exact += values[y * width + x];
+///     }
+/// }
+/// assert_eq!(table.sum(197, 197, 200, 200), exact);
+/// assert_eq!(table.mean(0, 0, 2, 1), 1e4 + 0.125);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SummedAreaTable<T> {
+    width: usize,
+    height: usize,
+    // (width + 1) * (height + 1) entries with a zero first row and column
+    sums: Vec<(T, T)>,
+}
+
+impl<T: Float> SummedAreaTable<T> {
+    pub fn new(values: &[T], width: usize) -> Self {
+        let height = grid_height(values, width);
+        let zero = (T::from_f64(0.0), T::from_f64(0.0));
+        let stride = width + 1;
+
+        let mut sums = Vec::with_capacity(stride * (height + 1));
+        for _ in 0..stride {
+            sums.push(zero);
+        }
+        for y in 0..height {
+            let mut row = zero;
+            sums.push(zero);
+            for x in 0..width {
+                row = add_pair(row, (values[y * width + x], T::from_f64(0.0)));
+                let above = sums[y * stride + x + 1];
+                sums.push(add_pair(above, row));
+            }
+        }
+
+        SummedAreaTable {
+            width: width,
+            height: height,
+            sums: sums,
+        }
+    }
+
+    #[inline(always)]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline(always)]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The sum of the samples in columns `x0..x1` of rows `y0..y1`.
+    pub fn sum(&self, x0: usize, y0: usize, x1: usize, y1: usize) -> T {
+        assert!(x0 <= x1 && x1 <= self.width && y0 <= y1 && y1 <= self.height,
+                "rectangle outside the summed area table");
+
+        let stride = self.width + 1;
+        let a = self.sums[y0 * stride + x0];
+        let b = self.sums[y0 * stride + x1];
+        let c = self.sums[y1 * stride + x0];
+        let d = self.sums[y1 * stride + x1];
+
+        let (s, e1) = two_sum(d.0, -b.0);
+        let (s, e2) = two_sum(s, -c.0);
+        let (s, e3) = two_sum(s, a.0);
+        s + (((e1 + e2) + e3) + ((d.1 - b.1) - (c.1 - a.1)))
+    }
+
+    /// The mean of the samples in columns `x0..x1` of rows `y0..y1`, NaN for
+    /// an empty rectangle.
+    #[inline]
+    pub fn mean(&self, x0: usize, y0: usize, x1: usize, y1: usize) -> T {
+        let area = (x1 - x0) * (y1 - y0);
+        if area == 0 {
+            T::nan()
+        } else {
+            self.sum(x0, y0, x1, y1) / T::from_f64(area as f64)
+        }
+    }
+}
+
+
+/// Box blur: writes to `output` the mean of the samples within `radius` of
+/// each sample in both directions, read from a `SummedAreaTable` so the cost
+/// does not depend on the radius. Near the edges only the samples inside the
+/// grid are averaged.
+///
+/// ```
+/// use float::grid;
+///
+/// let values = [0.0_f64, 0.0, 0.0,
+///               0.0, 9.0, 0.0,
+///               0.0, 0.0, 0.0];
+/// let mut blurred = [0.0; 9];
+/// grid::box_blur(&values, 3, 1, &mut blurred);
+/// assert_eq!(blurred, [2.25, 1.5, 2.25,
+///                      1.5, 1.0, 1.5,
+///                      2.25, 1.5, 2.25]);
+///
+/// // a radius wider than the grid averages all of it
+/// grid::box_blur(&values, 3, usize::max_value(), &mut blurred);
+/// assert_eq!(blurred, [1.0; 9]);
+/// ```
+pub fn box_blur<T: Float>(values: &[T], width: usize, radius: usize, output: &mut [T]) {
+    assert!(output.len() == values.len(), "output length differs from the grid length");
+
+    let table = SummedAreaTable::new(values, width);
+    let height = table.height();
+
+    for y in 0..height {
+        let (y0, y1) = (y.saturating_sub(radius), y.saturating_add(radius).saturating_add(1).min(height));
+        for x in 0..width {
+            let (x0, x1) = (x.saturating_sub(radius), x.saturating_add(radius).saturating_add(1).min(width));
+            output[y * width + x] = table.mean(x0, y0, x1, y1);
+        }
+    }
+}
+
+
+/// The sum of two unevaluated pairs, renormalized.
+#[inline]
+fn add_pair<T: Float>(a: (T, T), b: (T, T)) -> (T, T) {
+    let (s, e) = two_sum(a.0, b.0);
+    two_sum(s, e + (a.1 + b.1))
+}
@@ -0,0 +1,236 @@
+use expansion::two_sum;
+use float::Float;
+
+
+/// Number of elements `FloatIteratorExt::pairwise_sum` adds in order before
+/// combining pairwise.
+pub const PAIRWISE_BLOCK: usize = 8;
+
+
+/// Adapters and consumers for iterators over floats.
+///
+/// ```
+/// use float::FloatIteratorExt;
+///
+/// let values = [1e100_f64, 1.0, f64::NAN, -1e100, f64::INFINITY, 2.0];
+/// assert_eq!(values.iter().cloned().finite_only().compensated_sum(), 3.0);
+///
+/// let clamped: Vec<f32> = [-2.0_f32, 0.5, 7.0].iter().cloned().clamped(0.0, 1.0).collect();
+/// assert_eq!(clamped, [0.0, 0.5, 1.0]);
+///
+/// let running: Vec<f64> = [0.1_f64, 0.2, 0.3].iter().cloned().running_sum().collect();
+/// assert_eq!(running, [0.1, 0.30000000000000004, 0.6]);
+///
+/// let weights: Vec<f64> = [1.0_f64, 3.0, 4.0].iter().cloned().normalized().collect();
+/// assert_eq!(weights, [0.125, 0.375, 0.5]);
+/// ```
+pub trait FloatIteratorExt<T: Float>: Iterator<Item = T> + Sized {
+    /// The sum with a running compensation of the rounding errors, exact up
+    /// to a final rounding unless the compensation itself rounds.
+    #[inline]
+    fn compensated_sum(self) -> T {
+        let mut sum = T::from_f64(0.0);
+        let mut error = T::from_f64(0.0);
+        for x in self {
+            let (s, e) = two_sum(sum, x);
+            sum = s;
+            error = error + e;
+        }
+        sum + error
+    }
+
+    /// Pairwise summation in a single pass: blocks of `PAIRWISE_BLOCK`
+    /// elements are added in order and the block sums are combined as a
+    /// balanced tree, one stack slot per level, so the error grows with the
+    /// logarithm of the length and nothing is allocated.
+    ///
+    /// ```
+    /// use float::FloatIteratorExt;
+    ///
+    /// let tenths = || (0..1_000_000).map(|_| 0.1_f64);
+    /// assert!((tenths().pairwise_sum() - 100000.0).abs() < 1e-9);
+    /// assert!((tenths().fold(0.0, |sum, x| sum + x) - 100000.0).abs() > 1e-6);
+    /// assert_eq!((0..0).map(|i| i as f32).pairwise_sum(), 0.0);
+    /// ```
+    #[inline]
+    fn pairwise_sum(self) -> T {
+        let zero = T::from_f64(0.0);
+        // level k holds the sum of 2^k blocks, present when bit k of count is
+        let mut levels = [zero; 64];
+        let mut count = 0_u64;
+
+        let mut iter = self;
+        loop {
+            let mut block = zero;
+            let mut length = 0;
+            while length < PAIRWISE_BLOCK {
+                match iter.next() {
+                    Some(x) => block = block + x,
+                    None => break,
+                }
+                length += 1;
+            }
+            if length == 0 {
+                break;
+            }
+
+            let mut carry = block;
+            let mut level = 0;
+            while count & (1 << level) != 0 {
+                carry = levels[level] + carry;
+                level += 1;
+            }
+            levels[level] = carry;
+            count += 1;
+
+            if length < PAIRWISE_BLOCK {
+                break;
+            }
+        }
+
+        let mut sum = zero;
+        for (level, &partial) in levels.iter().enumerate() {
+            if count & (1 << level) != 0 {
+                sum = partial + sum;
+            }
+        }
+        sum
+    }
+
+    /// The compensated sums of the prefixes, one per element.
+    #[inline]
+    fn running_sum(self) -> RunningSum<Self, T> {
+        RunningSum {
+            iter: self,
+            sum: T::from_f64(0.0),
+            error: T::from_f64(0.0),
+        }
+    }
+
+    /// Skips NaNs and infinities.
+    #[inline]
+    fn finite_only(self) -> FiniteOnly<Self> {
+        FiniteOnly {
+            iter: self,
+        }
+    }
+
+    /// Restricts every element to `[min, max]` with `Float::clamp`, keeping
+    /// NaNs.
+    #[inline]
+    fn clamped(self, min: T, max: T) -> Clamped<Self, T> {
+        Clamped {
+            iter: self,
+            min: min,
+            max: max,
+        }
+    }
+
+    /// Divides every element by the compensated sum of all of them, so they
+    /// add up to one. The sum is taken on a clone of the iterator before the
+    /// first element is yielded.
+    #[inline]
+    fn normalized(self) -> Normalized<Self, T> where Self: Clone {
+        let sum = self.clone().compensated_sum();
+        Normalized {
+            iter: self,
+            sum: sum,
+        }
+    }
+}
+
+impl<T: Float, I: Iterator<Item = T>> FloatIteratorExt<T> for I {}
+
+
+/// Iterator returned by `FloatIteratorExt::running_sum`.
+#[derive(Clone, Debug)]
+pub struct RunningSum<I, T> {
+    iter: I,
+    sum: T,
+    error: T,
+}
+
+impl<T: Float, I: Iterator<Item = T>> Iterator for RunningSum<I, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|x| {
+            let (s, e) = two_sum(self.sum, x);
+            self.sum = s;
+            self.error = self.error + e;
+            self.sum + self.error
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+
+/// Iterator returned by `FloatIteratorExt::finite_only`.
+#[derive(Clone, Debug)]
+pub struct FiniteOnly<I> {
+    iter: I,
+}
+
+impl<T: Float, I: Iterator<Item = T>> Iterator for FiniteOnly<I> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.iter.find(|x| x.is_finite())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+
+/// Iterator returned by `FloatIteratorExt::clamped`.
+#[derive(Clone, Debug)]
+pub struct Clamped<I, T> {
+    iter: I,
+    min: T,
+    max: T,
+}
+
+impl<T: Float, I: Iterator<Item = T>> Iterator for Clamped<I, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|x| x.clamp(&self.min, &self.max))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+
+/// Iterator returned by `FloatIteratorExt::normalized`.
+#[derive(Clone, Debug)]
+pub struct Normalized<I, T> {
+    iter: I,
+    sum: T,
+}
+
+impl<T: Float, I: Iterator<Item = T>> Iterator for Normalized<I, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|x| x / self.sum)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
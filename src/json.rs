@@ -0,0 +1,180 @@
+//! JSON number text for code that can not use a full serializer.
+//!
+//! Finite values are written with the shortest digits that read back to the
+//! same value, so a write followed by a parse is exact. JSON has no NaN or
+//! infinities, what happens to those is chosen with `NonFinite`.
+
+use core::fmt;
+use core::str::FromStr;
+
+use float::Float;
+
+
+/// How NaN and the infinities are written and read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NonFinite {
+    /// Written as `null`, which reads back as NaN.
+    Null,
+    /// Written as the strings `"NaN"`, `"Infinity"` and `"-Infinity"`.
+    String,
+    /// Writing fails with `fmt::Error`, reading accepts numbers only.
+    Error,
+}
+
+impl Default for NonFinite {
+    #[inline(always)]
+    fn default() -> Self {
+        NonFinite::Null
+    }
+}
+
+
+/// Reasons a JSON number can not be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JsonError {
+    /// The text is not a JSON number, nor a non-finite value allowed by the
+    /// `NonFinite` policy.
+    Syntax,
+    /// The number is too large in magnitude for the type.
+    Overflow,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            JsonError::Syntax => write!(f, "malformed JSON number"),
+            JsonError::Overflow => write!(f, "JSON number out of range"),
+        }
+    }
+}
+
+
+/// Writes `x` as a JSON value.
+///
+/// Magnitudes in `[1e-6, 1e21)` are written in positional notation like
+/// JavaScript does, everything else with an exponent.
+///
+/// ```
+/// use float::json::{self, NonFinite};
+///
+/// let mut out = String::new();
+/// for &x in [0.1_f64, -0.0, 1.0, 1e21, 5e-324, f64::NAN].iter() {
+///     json::write_number(&mut out, x, NonFinite::Null).unwrap();
+///     out.push(' ');
+/// }
+/// assert_eq!(out, "0.1 -0 1 1e21 5e-324 null ");
+///
+/// let mut out = String::new();
+/// json::write_number(&mut out, 16777216.0_f32, NonFinite::String).unwrap();
+/// json::write_number(&mut out, -f32::INFINITY, NonFinite::String).unwrap();
+/// assert_eq!(out, "16777216\"-Infinity\"");
+///
+/// assert!(json::write_number(&mut out, f32::INFINITY, NonFinite::Error).is_err());
+/// ```
+pub fn write_number<W, T>(out: &mut W, x: T, non_finite: NonFinite) -> fmt::Result
+    where W: fmt::Write,
+          T: Float + fmt::Display + fmt::LowerExp,
+{
+    if x.is_finite() {
+        let magnitude = x.abs();
+        if magnitude == T::from_f64(0.0) || (magnitude >= T::from_f64(1e-6) && magnitude < T::from_f64(1e21)) {
+            write!(out, "{}", x)
+        } else {
+            write!(out, "{:e}", x)
+        }
+    } else {
+        match non_finite {
+            NonFinite::Null => out.write_str("null"),
+            NonFinite::String => out.write_str(if x.is_nan() {
+                "\"NaN\""
+            } else if x.is_sign_positive() {
+                "\"Infinity\""
+            } else {
+                "\"-Infinity\""
+            }),
+            NonFinite::Error => Err(fmt::Error),
+        }
+    }
+}
+
+/// Reads a JSON number, or a non-finite value as written by `write_number`
+/// under the same policy.
+///
+/// Only the strict JSON grammar is accepted: no leading `+`, no leading
+/// zeros, digits on both sides of the point and no surrounding whitespace.
+///
+/// ```
+/// use float::json::{self, JsonError, NonFinite};
+///
+/// assert_eq!(json::parse_number::<f64>("0.1", NonFinite::Null), Ok(0.1));
+/// assert_eq!(json::parse_number::<f64>("-2.5E+3", NonFinite::Null), Ok(-2500.0));
+/// assert!(json::parse_number::<f64>("null", NonFinite::Null).unwrap().is_nan());
+/// assert_eq!(json::parse_number::<f32>("\"-Infinity\"", NonFinite::String), Ok(-f32::INFINITY));
+///
+/// assert_eq!(json::parse_number::<f64>("null", NonFinite::Error), Err(JsonError::Syntax));
+/// assert_eq!(json::parse_number::<f64>(".5", NonFinite::Null), Err(JsonError::Syntax));
+/// assert_eq!(json::parse_number::<f64>("01", NonFinite::Null), Err(JsonError::Syntax));
+/// assert_eq!(json::parse_number::<f32>("1e39", NonFinite::Null), Err(JsonError::Overflow));
+/// ```
+pub fn parse_number<T>(text: &str, non_finite: NonFinite) -> Result<T, JsonError>
+    where T: Float + FromStr,
+{
+    match (non_finite, text) {
+        (NonFinite::Null, "null") | (NonFinite::String, "\"NaN\"") => return Ok(T::nan()),
+        (NonFinite::String, "\"Infinity\"") => return Ok(T::infinity()),
+        (NonFinite::String, "\"-Infinity\"") => return Ok(T::neg_infinity()),
+        _ => (),
+    }
+
+    if !is_json_number(text.as_bytes()) {
+        return Err(JsonError::Syntax);
+    }
+    match text.parse::<T>() {
+        Ok(x) if x.is_infinite() => Err(JsonError::Overflow),
+        Ok(x) => Ok(x),
+        Err(_) => Err(JsonError::Syntax),
+    }
+}
+
+
+/// Checks `-? (0 | [1-9][0-9]*) (. [0-9]+)? ([eE] [+-]? [0-9]+)?`.
+fn is_json_number(text: &[u8]) -> bool {
+    let mut i = 0;
+    if text.get(i) == Some(&b'-') {
+        i += 1;
+    }
+
+    match text.get(i) {
+        Some(&b'0') => i += 1,
+        Some(&c) if (b'1'..=b'9').contains(&c) => i += digits(&text[i..]),
+        _ => return false,
+    }
+
+    if text.get(i) == Some(&b'.') {
+        i += 1;
+        let count = digits(&text[i..]);
+        if count == 0 {
+            return false;
+        }
+        i += count;
+    }
+
+    if text.get(i) == Some(&b'e') || text.get(i) == Some(&b'E') {
+        i += 1;
+        if text.get(i) == Some(&b'+') || text.get(i) == Some(&b'-') {
+            i += 1;
+        }
+        let count = digits(&text[i..]);
+        if count == 0 {
+            return false;
+        }
+        i += count;
+    }
+
+    i == text.len()
+}
+
+#[inline]
+fn digits(text: &[u8]) -> usize {
+    text.iter().take_while(|&&c| c.is_ascii_digit()).count()
+}
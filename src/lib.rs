@@ -1,16 +1,98 @@
-#![feature(collections)]
-#![feature(core_intrinsics)]
+#![cfg_attr(feature = "unstable", feature(core_intrinsics))]
 #![no_std]
 
 
 extern crate libc;
-extern crate collections;
+#[cfg(feature = "std")]
+extern crate std;
+extern crate alloc as collections;
 
 extern crate approx_eq;
+#[cfg(feature = "libm")]
+extern crate libm;
 extern crate signed;
 
 
+mod angle_accumulator;
+mod backend;
+mod binned_sum;
+mod checksum;
+mod context;
+mod double_double;
+#[cfg(feature = "ffi-export")]
+mod ffi_export;
 mod float;
+mod float_key;
+mod float_parts;
+mod fp_state;
+mod iter_ext;
+mod long_accumulator;
+mod low_discrepancy;
+mod mat_view;
+mod math;
+mod nan_debug;
+mod nan_trace;
+mod nco;
+mod pow;
+mod promoted;
+mod rounding;
+#[cfg(feature = "self-test")]
+mod self_test;
+mod weighted_alias;
+mod wrap;
 
+pub mod conformance;
+pub mod decimal;
+pub mod dsp;
+pub mod expansion;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod geometry;
+pub mod grid;
+pub mod json;
+pub mod linalg;
+pub mod ml;
+pub mod noise;
+pub mod parse;
+pub mod pcm;
+pub mod polynomial;
+pub mod precision;
+pub mod probability;
+pub mod pymath;
+#[cfg(feature = "rng")]
+pub mod rng;
+pub mod special;
+pub mod stats;
+pub mod sys;
+pub mod test_vectors;
 
+
+pub use angle_accumulator::AngleAccumulator;
+pub use backend::{Backend, MathBackend, current_backend, with_backend, set_math_backend, clear_math_backend};
+pub use binned_sum::BinnedSum;
+pub use checksum::checksum_canonical;
+pub use context::{ContextFloat, MathContext, NanPolicy};
+pub use double_double::DoubleDouble;
 pub use float::Float;
+pub use float_key::total_order_key;
+pub use float_parts::{FloatParts, FloatFields};
+pub use fp_state::FpState;
+pub use iter_ext::{FloatIteratorExt, RunningSum, FiniteOnly, Clamped, Normalized, PAIRWISE_BLOCK};
+pub use long_accumulator::LongAccumulator;
+pub use low_discrepancy::{Halton, Sobol, SOBOL_MAX_DIMENSIONS, radical_inverse};
+pub use mat_view::{MatView, MatViewMut};
+pub use nan_debug::nan_tolerant;
+pub use nan_trace::{NanTrace, NanOrigin, set_nan_hook, clear_nan_hook};
+pub use nco::Nco;
+pub use pow::{MulIdentity, pow_usize};
+pub use promoted::Promoted;
+pub use rounding::RoundingMode;
+#[cfg(feature = "self-test")]
+pub use self_test::self_test;
+pub use weighted_alias::WeightedAlias;
+pub use wrap::{wrap_range, fold_range, ping_pong};
+
+// the Rust fallbacks for the C math functions wasm32 lacks, for the fuzz
+// targets
+#[doc(hidden)]
+pub use math::soft as soft_math;
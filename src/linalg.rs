@@ -0,0 +1,1205 @@
+//! Dense factorizations of small and medium matrices, in place on matrix
+//! views and without allocating.
+//!
+//! LU and Cholesky take square matrices, QR also tall ones for least
+//! squares; shapes that do not fit panic. Vectors are plain slices, except
+//! for the fixed size eigensolvers, which take and return arrays.
+
+use core::cmp::Ordering;
+use core::fmt;
+
+use float::Float;
+use mat_view::{MatView, MatViewMut};
+
+
+/// Sweeps allowed to the fixed size SVD users; far more than any small
+/// matrix needs.
+const SMALL_SWEEPS: usize = 60;
+
+
+/// Reasons a factorization can not be completed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LinalgError {
+    /// No non-zero pivot was left for the given column.
+    Singular(usize),
+    /// The diagonal element of the given column was not positive.
+    NotPositiveDefinite(usize),
+    /// An iteration did not converge in the allowed number of steps.
+    NotConverged,
+}
+
+impl fmt::Display for LinalgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LinalgError::Singular(column) => write!(f, "matrix is singular in column {}", column),
+            LinalgError::NotPositiveDefinite(column) => {
+                write!(f, "matrix is not positive definite in column {}", column)
+            },
+            LinalgError::NotConverged => f.write_str("iteration did not converge"),
+        }
+    }
+}
+
+
+/// The 1-norm, the largest sum of absolute values of a column.
+///
+/// Condition estimates need the norm of the original matrix, so take it
+/// before factoring in place.
+#[inline]
+pub fn norm_1<T: Float>(a: &MatView<T>) -> T {
+    let mut norm = T::from_f64(0.0);
+    for col in 0..a.cols() {
+        let mut sum = T::from_f64(0.0);
+        for row in 0..a.rows() {
+            sum = sum + a[(row, col)].abs();
+        }
+        if sum > norm || sum.is_nan() {
+            norm = sum;
+        }
+    }
+    norm
+}
+
+
+/// LU factorization with partial pivoting, `P A = L U`, in place.
+///
+/// `L` is unit lower triangular and stored below the diagonal, `U` on and
+/// above it. `pivots[k]` is the row swapped with row `k` at step `k`, as in
+/// LAPACK. A singular matrix is still factored completely, the error names
+/// the first column without a usable pivot.
+///
+/// ```
+/// use float::{MatView, MatViewMut};
+/// use float::linalg;
+///
+/// let mut a = [2.0_f64, 1.0, 1.0,
+///              4.0, -6.0, 0.0,
+///              -2.0, 7.0, 2.0];
+/// let norm = linalg::norm_1(&MatView::new(&a, 3, 3));
+/// let mut pivots = [0; 3];
+/// linalg::lu_decompose(&mut MatViewMut::new(&mut a, 3, 3), &mut pivots).unwrap();
+///
+/// let lu = MatView::new(&a, 3, 3);
+/// assert_eq!(pivots, [1, 1, 2]);
+/// assert_eq!(linalg::lu_determinant(&lu, &pivots), -16.0);
+///
+/// let mut b = [5.0, -2.0, 9.0];
+/// linalg::lu_solve(&lu, &pivots, &mut b);
+/// assert_eq!(b, [1.0, 1.0, 2.0]);
+///
+/// let mut work = [0.0; 6];
+/// let condition = linalg::lu_condition(&lu, &pivots, norm, &mut work);
+/// // exact here, 14 for the norm of A times 2.25 for that of its inverse
+/// assert!((condition - 31.5).abs() < 1e-12);
+///
+/// let mut singular = [1.0_f32, 2.0, 2.0, 4.0];
+/// let result = linalg::lu_decompose(&mut MatViewMut::new(&mut singular, 2, 2), &mut pivots);
+/// assert_eq!(result, Err(linalg::LinalgError::Singular(1)));
+/// ```
+pub fn lu_decompose<T: Float>(a: &mut MatViewMut<T>, pivots: &mut [usize]) -> Result<(), LinalgError> {
+    let n = square_size(a.rows(), a.cols());
+    assert!(pivots.len() >= n, "lu_decompose needs a pivot per row");
+
+    let zero = T::from_f64(0.0);
+    let mut singular = None;
+
+    for k in 0..n {
+        let mut pivot = k;
+        let mut max = zero;
+        for row in k..n {
+            let magnitude = a[(row, k)].abs();
+            if magnitude > max {
+                max = magnitude;
+                pivot = row;
+            }
+        }
+        pivots[k] = pivot;
+
+        if !(max > zero) {
+            if singular.is_none() {
+                singular = Some(k);
+            }
+            continue;
+        }
+
+        if pivot != k {
+            for col in 0..n {
+                let t = a[(k, col)];
+                a[(k, col)] = a[(pivot, col)];
+                a[(pivot, col)] = t;
+            }
+        }
+
+        let diagonal = a[(k, k)];
+        for row in k + 1..n {
+            let factor = a[(row, k)] / diagonal;
+            a[(row, k)] = factor;
+            for col in k + 1..n {
+                a[(row, col)] = a[(row, col)] - factor * a[(k, col)];
+            }
+        }
+    }
+
+    match singular {
+        Some(column) => Err(LinalgError::Singular(column)),
+        None => Ok(()),
+    }
+}
+
+/// Solves `A x = b` in place from the factors of `lu_decompose`.
+pub fn lu_solve<T: Float>(lu: &MatView<T>, pivots: &[usize], b: &mut [T]) {
+    let n = square_size(lu.rows(), lu.cols());
+    assert!(pivots.len() >= n && b.len() == n, "lu_solve shapes differ");
+
+    for (k, &pivot) in pivots.iter().enumerate().take(n) {
+        b.swap(k, pivot);
+    }
+    solve_lower(lu, b, true);
+    solve_upper(lu, b);
+}
+
+/// Solves `A^T x = b` in place from the factors of `lu_decompose`.
+pub fn lu_solve_transpose<T: Float>(lu: &MatView<T>, pivots: &[usize], b: &mut [T]) {
+    let n = square_size(lu.rows(), lu.cols());
+    assert!(pivots.len() >= n && b.len() == n, "lu_solve_transpose shapes differ");
+
+    // U^T L^T P x = b
+    solve_lower(&lu.transpose(), b, false);
+    for row in (0..n).rev() {
+        let mut sum = b[row];
+        for col in row + 1..n {
+            sum = sum - lu[(col, row)] * b[col];
+        }
+        b[row] = sum;
+    }
+    for k in (0..n).rev() {
+        b.swap(k, pivots[k]);
+    }
+}
+
+/// The determinant from the factors of `lu_decompose`.
+pub fn lu_determinant<T: Float>(lu: &MatView<T>, pivots: &[usize]) -> T {
+    let n = square_size(lu.rows(), lu.cols());
+    let mut determinant = T::from_f64(1.0);
+    for k in 0..n {
+        determinant = determinant * lu[(k, k)];
+        if pivots[k] != k {
+            determinant = -determinant;
+        }
+    }
+    determinant
+}
+
+/// Estimates the 1-norm condition number `||A|| ||A^-1||` from the factors
+/// of `lu_decompose` and the 1-norm of the original matrix, with Hager's
+/// method as refined by Higham. The estimate of `||A^-1||` never exceeds it
+/// and is rarely below a third of it. `work` needs `2 n` elements. Infinite
+/// for a singular factorization.
+pub fn lu_condition<T: Float>(lu: &MatView<T>, pivots: &[usize], norm: T, work: &mut [T]) -> T {
+    let n = square_size(lu.rows(), lu.cols());
+    if (0..n).any(|k| lu[(k, k)] == T::from_f64(0.0)) {
+        return T::infinity();
+    }
+    norm * inverse_norm_1(n, work, |x| lu_solve(lu, pivots, x), |x| lu_solve_transpose(lu, pivots, x))
+}
+
+
+/// Cholesky factorization `A = L L^T` of a symmetric positive definite
+/// matrix, in place.
+///
+/// Only the lower triangle is read and replaced by `L`; the part above the
+/// diagonal is left untouched.
+///
+/// ```
+/// use float::{MatView, MatViewMut};
+/// use float::linalg;
+///
+/// let mut a = [4.0_f64, 12.0, -16.0,
+///              12.0, 37.0, -43.0,
+///              -16.0, -43.0, 98.0];
+/// let norm = linalg::norm_1(&MatView::new(&a, 3, 3));
+/// linalg::cholesky_decompose(&mut MatViewMut::new(&mut a, 3, 3)).unwrap();
+/// let l = MatView::new(&a, 3, 3);
+/// assert_eq!([l[(0, 0)], l[(1, 0)], l[(1, 1)], l[(2, 0)], l[(2, 1)], l[(2, 2)]],
+///            [2.0, 6.0, 1.0, -8.0, 5.0, 3.0]);
+///
+/// let mut b = [-36.0, -105.0, 168.0];
+/// linalg::cholesky_solve(&l, &mut b);
+/// assert_eq!(b, [1.0, -2.0, 1.0]);
+///
+/// let mut work = [0.0; 6];
+/// let condition = linalg::cholesky_condition(&l, norm, &mut work);
+/// assert!((condition - 10209.361).abs() < 1e-3);
+///
+/// let mut indefinite = [1.0_f32, 2.0, 2.0, 1.0];
+/// let result = linalg::cholesky_decompose(&mut MatViewMut::new(&mut indefinite, 2, 2));
+/// assert_eq!(result, Err(linalg::LinalgError::NotPositiveDefinite(1)));
+/// ```
+pub fn cholesky_decompose<T: Float>(a: &mut MatViewMut<T>) -> Result<(), LinalgError> {
+    let n = square_size(a.rows(), a.cols());
+    let zero = T::from_f64(0.0);
+
+    for j in 0..n {
+        let mut diagonal = a[(j, j)];
+        for k in 0..j {
+            diagonal = diagonal - a[(j, k)] * a[(j, k)];
+        }
+        if !(diagonal > zero) {
+            return Err(LinalgError::NotPositiveDefinite(j));
+        }
+        let diagonal = diagonal.sqrt();
+        a[(j, j)] = diagonal;
+
+        for row in j + 1..n {
+            let mut sum = a[(row, j)];
+            for k in 0..j {
+                sum = sum - a[(row, k)] * a[(j, k)];
+            }
+            a[(row, j)] = sum / diagonal;
+        }
+    }
+    Ok(())
+}
+
+/// Solves `A x = b` in place from the factor of `cholesky_decompose`.
+pub fn cholesky_solve<T: Float>(l: &MatView<T>, b: &mut [T]) {
+    let n = square_size(l.rows(), l.cols());
+    assert!(b.len() == n, "cholesky_solve shapes differ");
+
+    solve_lower(l, b, false);
+    solve_upper(&l.transpose(), b);
+}
+
+/// Estimates the 1-norm condition number like `lu_condition`, from the
+/// factor of `cholesky_decompose`. `work` needs `2 n` elements.
+pub fn cholesky_condition<T: Float>(l: &MatView<T>, norm: T, work: &mut [T]) -> T {
+    let n = square_size(l.rows(), l.cols());
+    // A is symmetric, so solving with its transpose is solving with A
+    norm * inverse_norm_1(n, work, |x| cholesky_solve(l, x), |x| cholesky_solve(l, x))
+}
+
+
+/// Householder QR factorization `A = Q R` of an `m x n` matrix with
+/// `m >= n`, in place.
+///
+/// `R` is stored on and above the diagonal, the Householder vectors below
+/// it with their implicit leading one, and their scale factors in `tau`,
+/// as in LAPACK. Column norms are accumulated with scaling, so the
+/// factorization neither overflows nor underflows where the result does
+/// not. Rank deficiency shows up as small elements on the diagonal of `R`
+/// and is reported by `qr_solve`.
+///
+/// ```
+/// use float::MatViewMut;
+/// use float::linalg;
+///
+/// // fits y = c0 + c1 x through (0, 1), (1, 3), (2, 5), (3, 8)
+/// let mut a = [1.0_f64, 0.0,
+///              1.0, 1.0,
+///              1.0, 2.0,
+///              1.0, 3.0];
+/// let mut tau = [0.0; 2];
+/// let mut qr = MatViewMut::new(&mut a, 4, 2);
+/// linalg::qr_decompose(&mut qr, &mut tau);
+///
+/// let mut b = [1.0, 3.0, 5.0, 8.0];
+/// linalg::qr_solve(&qr.view(), &tau, &mut b).unwrap();
+/// assert!((b[0] - 0.8).abs() < 1e-14 && (b[1] - 2.3).abs() < 1e-14);
+/// // the rest of b holds the residual in the rotated basis
+/// assert!((b[2] * b[2] + b[3] * b[3] - 0.3).abs() < 1e-14);
+///
+/// // squaring these would overflow
+/// let mut huge = [1e300_f64, 1e300];
+/// let mut qr = MatViewMut::new(&mut huge, 2, 1);
+/// linalg::qr_decompose(&mut qr, &mut tau[..1]);
+/// let mut b = [2e300, 2e300];
+/// linalg::qr_solve(&qr.view(), &tau[..1], &mut b).unwrap();
+/// assert!((b[0] - 2.0).abs() < 1e-15);
+///
+/// let mut rank_one = [1.0_f32, 2.0, 2.0, 4.0, 3.0, 6.0];
+/// let mut qr = MatViewMut::new(&mut rank_one, 3, 2);
+/// let mut tau = [0.0_f32; 2];
+/// linalg::qr_decompose(&mut qr, &mut tau);
+/// let mut b = [1.0_f32, 2.0, 3.0];
+/// assert_eq!(linalg::qr_solve(&qr.view(), &tau, &mut b), Err(linalg::LinalgError::Singular(1)));
+/// ```
+pub fn qr_decompose<T: Float>(a: &mut MatViewMut<T>, tau: &mut [T]) {
+    let (m, n) = (a.rows(), a.cols());
+    assert!(m >= n, "qr_decompose needs at least as many rows as columns");
+    assert!(tau.len() >= n, "qr_decompose needs a scale factor per column");
+
+    let zero = T::from_f64(0.0);
+    let one = T::from_f64(1.0);
+
+    for k in 0..n {
+        let alpha = a[(k, k)];
+        let tail = scaled_norm((k + 1..m).map(|row| a[(row, k)]));
+        if tail == zero {
+            tau[k] = zero;
+            continue;
+        }
+
+        let beta = -alpha.hypot(&tail).copysign(&alpha);
+        tau[k] = (beta - alpha) / beta;
+        let scale = one / (alpha - beta);
+        for row in k + 1..m {
+            a[(row, k)] = a[(row, k)] * scale;
+        }
+        a[(k, k)] = beta;
+
+        for col in k + 1..n {
+            let mut dot = a[(k, col)];
+            for row in k + 1..m {
+                dot = dot + a[(row, k)] * a[(row, col)];
+            }
+            let w = tau[k] * dot;
+            a[(k, col)] = a[(k, col)] - w;
+            for row in k + 1..m {
+                a[(row, col)] = a[(row, col)] - w * a[(row, k)];
+            }
+        }
+    }
+}
+
+/// Replaces `b` by `Q^T b` with the factors of `qr_decompose`.
+pub fn qr_apply_qt<T: Float>(qr: &MatView<T>, tau: &[T], b: &mut [T]) {
+    let (m, n) = (qr.rows(), qr.cols());
+    assert!(tau.len() >= n && b.len() == m, "qr_apply_qt shapes differ");
+
+    for k in 0..n {
+        let mut dot = b[k];
+        for row in k + 1..m {
+            dot = dot + qr[(row, k)] * b[row];
+        }
+        let w = tau[k] * dot;
+        b[k] = b[k] - w;
+        for row in k + 1..m {
+            b[row] = b[row] - w * qr[(row, k)];
+        }
+    }
+}
+
+/// Solves `A x = b` in the least squares sense, in place, with the factors
+/// of `qr_decompose`. `b` has a row per row of `A`; afterwards its first
+/// `n` elements hold `x` and the norm of the others is the norm of the
+/// residual `A x - b`.
+///
+/// `A` counts as rank deficient when an element on the diagonal of `R` is
+/// no larger than `m` epsilons of the largest one, the rounding error of
+/// the factorization; the error names the first such column.
+pub fn qr_solve<T: Float>(qr: &MatView<T>, tau: &[T], b: &mut [T]) -> Result<(), LinalgError> {
+    let (m, n) = (qr.rows(), qr.cols());
+    qr_apply_qt(qr, tau, b);
+
+    let largest = (0..n).fold(T::from_f64(0.0), |max, k| max.max(&qr[(k, k)].abs()));
+    let tolerance = largest * T::epsilon() * T::from_f64(m as f64);
+    if let Some(column) = (0..n).find(|&k| !(qr[(k, k)].abs() > tolerance)) {
+        return Err(LinalgError::Singular(column));
+    }
+    solve_upper(&qr.submatrix(0, 0, n, n), &mut b[..n]);
+    Ok(())
+}
+
+
+/// Eigenvalues and eigenvectors of the symmetric matrix `[[a, b], [b, c]]`,
+/// from the one rotation that diagonalizes it.
+///
+/// Eigenvalues are in ascending order and `vectors[i]` is the unit
+/// eigenvector of `values[i]`. Close or equal eigenvalues are no problem,
+/// the vectors stay orthonormal.
+///
+/// ```
+/// use float::linalg;
+///
+/// let (values, vectors) = linalg::symmetric_eigen_2x2(2.0_f64, 1.0, 2.0);
+/// assert_eq!(values, [1.0, 3.0]);
+/// let h = std::f64::consts::FRAC_1_SQRT_2;
+/// assert!((vectors[0][0].abs() - h).abs() < 1e-15 && (vectors[0][0] + vectors[0][1]).abs() < 1e-15);
+///
+/// let (values, vectors) = linalg::symmetric_eigen_2x2(1.0_f32, 1e-30, 1.0);
+/// assert_eq!(values, [1.0, 1.0]);
+/// assert_eq!(vectors[0][0] * vectors[1][0] + vectors[0][1] * vectors[1][1], 0.0);
+///
+/// // entries near the overflow threshold
+/// let (values, _) = linalg::symmetric_eigen_2x2(1e308_f64, 1e308, -1e308);
+/// let root2 = std::f64::consts::SQRT_2;
+/// assert!((values[0] / -1e308 - root2).abs() < 1e-15 && (values[1] / 1e308 - root2).abs() < 1e-15);
+/// ```
+pub fn symmetric_eigen_2x2<T: Float>(a: T, b: T, c: T) -> ([T; 2], [[T; 2]; 2]) {
+    let t = jacobi_tangent(a, b, c);
+    let (cos, sin) = rotation_from_tangent(t);
+    // the rotation puts the eigenvalue of (cos, -sin) first
+    let first = a - t * b;
+    let second = c + t * b;
+    let first_vector = [cos, -sin];
+    let second_vector = [sin, cos];
+
+    if first <= second {
+        ([first, second], [first_vector, second_vector])
+    } else {
+        ([second, first], [second_vector, first_vector])
+    }
+}
+
+/// Eigenvalues and eigenvectors of a symmetric 3x3 matrix, in closed form.
+///
+/// Only the upper triangle is read. Eigenvalues are in ascending order and
+/// `vectors[i]` is the unit eigenvector of `values[i]`. The matrix is
+/// scaled by its largest element first. As in Eberly's "A Robust
+/// Eigensolver for 3x3 Symmetric Matrices", only the best separated
+/// eigenvalue comes from the characteristic polynomial and its eigenvector
+/// from cross products; the other two come from the 2x2 problem orthogonal
+/// to it, so they stay orthonormal and accurate for repeated and nearly
+/// repeated eigenvalues.
+///
+/// ```
+/// use float::linalg;
+///
+/// let (values, vectors) = linalg::symmetric_eigen_3x3([[2.0_f64, 0.0, 0.0],
+///                                                      [0.0, 3.0, 4.0],
+///                                                      [0.0, 4.0, 9.0]]);
+/// assert!((values[0] - 1.0).abs() < 1e-14 && (values[1] - 2.0).abs() < 1e-14);
+/// assert!((values[2] - 11.0).abs() < 1e-14);
+/// assert!((vectors[2][1].abs() - 0.4472135954999579).abs() < 1e-15);
+/// assert!((vectors[1][0].abs() - 1.0).abs() < 1e-15);
+///
+/// // the same matrix scaled into the subnormals
+/// let (tiny, _) = linalg::symmetric_eigen_3x3([[2e-310_f64, 0.0, 0.0],
+///                                              [0.0, 3e-310, 4e-310],
+///                                              [0.0, 4e-310, 9e-310]]);
+/// assert!((tiny[0] / 1e-310 - 1.0).abs() < 1e-10 && (tiny[2] / 11e-310 - 1.0).abs() < 1e-10);
+///
+/// // a covariance with two nearly equal axes
+/// let m = [[1.0_f32, 1e-7, 0.0], [1e-7, 1.0, 0.0], [0.0, 0.0, 5.0]];
+/// let (values, vectors) = linalg::symmetric_eigen_3x3(m);
+/// assert!((values[0] - 1.0).abs() < 1e-6 && (values[2] - 5.0).abs() < 1e-6);
+/// for i in 0..3 {
+///     for j in 0..3 {
+///         let dot: f32 = (0..3).map(|k| vectors[i][k] * vectors[j][k]).sum();
+///         assert!((dot - if i == j { 1.0 } else { 0.0 }).abs() < 1e-6);
+///     }
+/// }
+/// ```
+pub fn symmetric_eigen_3x3<T: Float>(m: [[T; 3]; 3]) -> ([T; 3], [[T; 3]; 3]) {
+    let zero = T::from_f64(0.0);
+    let one = T::from_f64(1.0);
+    let identity = [[one, zero, zero], [zero, one, zero], [zero, zero, one]];
+
+    let max = [m[0][0], m[0][1], m[0][2], m[1][1], m[1][2], m[2][2]].iter()
+        .fold(zero, |max, x| max.max(&x.abs()));
+    if max == zero || !max.is_finite() {
+        let values = if max == zero { [zero; 3] } else { [T::nan(); 3] };
+        return (values, identity);
+    }
+
+    // dividing rather than multiplying by 1 / max, which overflows for a
+    // subnormal max
+    let a = [[m[0][0] / max, m[0][1] / max, m[0][2] / max],
+             [m[0][1] / max, m[1][1] / max, m[1][2] / max],
+             [m[0][2] / max, m[1][2] / max, m[2][2] / max]];
+
+    let off = a[0][1] * a[0][1] + a[0][2] * a[0][2] + a[1][2] * a[1][2];
+    if off == zero {
+        let mut order = [0, 1, 2];
+        order.sort_by(|&i, &j| a[i][i].partial_cmp(&a[j][j]).unwrap_or(Ordering::Equal));
+        let values = [m[order[0]][order[0]], m[order[1]][order[1]], m[order[2]][order[2]]];
+        return (values, [identity[order[0]], identity[order[1]], identity[order[2]]]);
+    }
+
+    let three = T::from_f64(3.0);
+    let q = (a[0][0] + a[1][1] + a[2][2]) / three;
+    let b00 = a[0][0] - q;
+    let b11 = a[1][1] - q;
+    let b22 = a[2][2] - q;
+    let p = ((b00 * b00 + b11 * b11 + b22 * b22 + T::from_f64(2.0) * off) / T::from_f64(6.0)).sqrt();
+    let c00 = b11 * b22 - a[1][2] * a[1][2];
+    let c01 = a[0][1] * b22 - a[1][2] * a[0][2];
+    let c02 = a[0][1] * a[1][2] - b11 * a[0][2];
+    let det = (b00 * c00 - a[0][1] * c01 + a[0][2] * c02) / (p * p * p);
+    let half_det = (det / T::from_f64(2.0)).clamp(&-one, &one);
+
+    // the roots of the characteristic polynomial of (A - q I) / p are
+    // 2 cos(angle + 2 pi k / 3); the largest is furthest from the middle one
+    // when half_det >= 0 and the smallest otherwise
+    let angle = half_det.acos() / three;
+    let angle = if half_det >= zero { angle } else { angle + T::tau() / three };
+    let separated = q + p * T::from_f64(2.0) * angle.cos();
+
+    // the angle loses half the digits near a repeated eigenvalue, so the
+    // Rayleigh quotient of the vector replaces it
+    let w = eigenvector_by_cross(&a, separated);
+    let (others, vectors) = eigen_in_complement(&a, w);
+
+    let mut pairs = [(dot(w, mul_vector(&a, w)), w), (others[0], vectors[0]), (others[1], vectors[1])];
+    pairs.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap_or(Ordering::Equal));
+    ([pairs[0].0 * max, pairs[1].0 * max, pairs[2].0 * max], [pairs[0].1, pairs[1].1, pairs[2].1])
+}
+
+/// Eigenvalues and eigenvectors of a small symmetric matrix with cyclic
+/// Jacobi rotations, in place.
+///
+/// Afterwards the diagonal of `a` holds the eigenvalues in ascending order
+/// and the columns of `vectors` the matching unit eigenvectors; the rest of
+/// `a` is left with what the rotations did not zero. Converges
+/// quadratically, typically in well under ten sweeps, and computes even
+/// small eigenvalues to high relative accuracy.
+///
+/// ```
+/// use float::{MatView, MatViewMut};
+/// use float::linalg;
+///
+/// let mut a = [4.0_f64, 1.0, 0.0, 0.0,
+///              1.0, 3.0, 1.0, 0.0,
+///              0.0, 1.0, 2.0, 1.0,
+///              0.0, 0.0, 1.0, 1.0];
+/// let mut v = [0.0; 16];
+/// linalg::symmetric_eigen_jacobi(&mut MatViewMut::new(&mut a, 4, 4), &mut MatViewMut::new(&mut v, 4, 4), 20)
+///     .unwrap();
+///
+/// let expected = [0.25471875982586093, 1.8227170808871082, 3.177282919112892, 4.745281240174139];
+/// for i in 0..4 {
+///     assert!((a[5 * i] - expected[i]).abs() < 1e-13);
+/// }
+/// let v = MatView::new(&v, 4, 4);
+/// let dot: f64 = (0..4).map(|k| v[(k, 0)] * v[(k, 3)]).sum();
+/// assert!(dot.abs() < 1e-15);
+/// ```
+pub fn symmetric_eigen_jacobi<T: Float>(a: &mut MatViewMut<T>, vectors: &mut MatViewMut<T>,
+                                        max_sweeps: usize) -> Result<(), LinalgError> {
+    let n = square_size(a.rows(), a.cols());
+    assert!(vectors.rows() == n && vectors.cols() == n, "symmetric_eigen_jacobi shapes differ");
+
+    let zero = T::from_f64(0.0);
+    for row in 0..n {
+        for col in 0..n {
+            vectors[(row, col)] = if row == col { T::from_f64(1.0) } else { zero };
+        }
+    }
+
+    let mut converged = false;
+    for _ in 0..max_sweeps {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = a[(p, q)];
+                // negligible next to both diagonal elements, which also
+                // keeps the relative accuracy of small eigenvalues
+                let threshold = T::epsilon() * (a[(p, p)].abs() * a[(q, q)].abs()).sqrt();
+                if apq.abs() <= threshold {
+                    continue;
+                }
+                rotated = true;
+
+                let (cos, sin) = rotation_from_tangent(jacobi_tangent(a[(p, p)], apq, a[(q, q)]));
+                for k in 0..n {
+                    let (akp, akq) = (a[(k, p)], a[(k, q)]);
+                    a[(k, p)] = cos * akp - sin * akq;
+                    a[(k, q)] = sin * akp + cos * akq;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (a[(p, k)], a[(q, k)]);
+                    a[(p, k)] = cos * apk - sin * aqk;
+                    a[(q, k)] = sin * apk + cos * aqk;
+                }
+                a[(p, q)] = zero;
+                a[(q, p)] = zero;
+                for k in 0..n {
+                    let (vkp, vkq) = (vectors[(k, p)], vectors[(k, q)]);
+                    vectors[(k, p)] = cos * vkp - sin * vkq;
+                    vectors[(k, q)] = sin * vkp + cos * vkq;
+                }
+            }
+        }
+        if !rotated {
+            converged = true;
+            break;
+        }
+    }
+    if !converged {
+        return Err(LinalgError::NotConverged);
+    }
+
+    // selection sort, swapping eigenvector columns along
+    for i in 0..n {
+        let mut min = i;
+        for j in i + 1..n {
+            if a[(j, j)] < a[(min, min)] {
+                min = j;
+            }
+        }
+        if min != i {
+            let t = a[(i, i)];
+            a[(i, i)] = a[(min, min)];
+            a[(min, min)] = t;
+            for k in 0..n {
+                let t = vectors[(k, i)];
+                vectors[(k, i)] = vectors[(k, min)];
+                vectors[(k, min)] = t;
+            }
+        }
+    }
+    Ok(())
+}
+
+
+/// Singular value decomposition `A = U S V^T` of an `m x n` matrix with
+/// `m >= n`, by one-sided Jacobi rotations, in place.
+///
+/// Afterwards `a` holds the `n` columns of `U`, `singular_values` the
+/// singular values in descending order and `vectors` the `n x n` matrix
+/// `V`. The matrix is scaled by its largest element first, so it may hold
+/// any finite values, and the columns of `U` for zero singular values are
+/// completed to an orthonormal set. Non-finite elements give NaN singular
+/// values and `NotConverged`. Rotations are applied until every pair
+/// of columns is orthogonal to working precision, which also gives small
+/// singular values to high relative accuracy; meant for small matrices,
+/// each sweep takes `O(m n^2)` operations.
+///
+/// ```
+/// use float::{MatView, MatViewMut};
+/// use float::linalg;
+///
+/// let mut a = [3.0_f64, 0.0,
+///              4.0, 5.0,
+///              0.0, 0.0];
+/// let mut s = [0.0; 2];
+/// let mut v = [0.0; 4];
+/// linalg::svd_jacobi(&mut MatViewMut::new(&mut a, 3, 2), &mut s, &mut MatViewMut::new(&mut v, 2, 2), 30)
+///     .unwrap();
+/// assert!((s[0] - 45.0_f64.sqrt()).abs() < 1e-14 && (s[1] - 5.0_f64.sqrt()).abs() < 1e-14);
+///
+/// let (u, v) = (MatView::new(&a, 3, 2), MatView::new(&v, 2, 2));
+/// let entry = |i: usize, j: usize| (0..2).map(|k| u[(i, k)] * s[k] * v[(j, k)]).sum::<f64>();
+/// assert!((entry(1, 0) - 4.0).abs() < 1e-14 && (entry(1, 1) - 5.0).abs() < 1e-14);
+///
+/// // rank one: the second column of U is still a unit vector orthogonal
+/// // to the first
+/// let mut a = [1.0_f32, 2.0, 2.0, 4.0];
+/// let mut s = [0.0_f32; 2];
+/// let mut v = [0.0_f32; 4];
+/// linalg::svd_jacobi(&mut MatViewMut::new(&mut a, 2, 2), &mut s, &mut MatViewMut::new(&mut v, 2, 2), 30)
+///     .unwrap();
+/// assert!(s[1].abs() < 1e-6);
+/// assert!((a[0] * a[1] + a[2] * a[3]).abs() < 1e-6 && (a[1] * a[1] + a[3] * a[3] - 1.0).abs() < 1e-6);
+///
+/// // subnormal elements
+/// let mut a = [1e-310_f64, 0.0, 0.0, 2e-310];
+/// let mut s = [0.0; 2];
+/// let mut v = [0.0; 4];
+/// linalg::svd_jacobi(&mut MatViewMut::new(&mut a, 2, 2), &mut s, &mut MatViewMut::new(&mut v, 2, 2), 30)
+///     .unwrap();
+/// assert_eq!(s, [2e-310, 1e-310]);
+/// ```
+pub fn svd_jacobi<T: Float>(a: &mut MatViewMut<T>, singular_values: &mut [T], vectors: &mut MatViewMut<T>,
+                            max_sweeps: usize) -> Result<(), LinalgError> {
+    let (m, n) = (a.rows(), a.cols());
+    assert!(m >= n, "svd_jacobi needs at least as many rows as columns");
+    assert!(singular_values.len() == n, "svd_jacobi needs a singular value per column");
+    assert!(vectors.rows() == n && vectors.cols() == n, "svd_jacobi shapes differ");
+
+    let zero = T::from_f64(0.0);
+    let one = T::from_f64(1.0);
+    for row in 0..n {
+        for col in 0..n {
+            vectors[(row, col)] = if row == col { one } else { zero };
+        }
+    }
+
+    let mut max = zero;
+    for row in 0..m {
+        for col in 0..n {
+            max = max.max(&a[(row, col)].abs());
+        }
+    }
+    if !max.is_finite() {
+        for x in singular_values.iter_mut() {
+            *x = T::nan();
+        }
+        return Err(LinalgError::NotConverged);
+    }
+    // dividing rather than multiplying by 1 / max, which overflows for a
+    // subnormal max
+    let scale = if max == zero { one } else { max };
+    for row in 0..m {
+        for col in 0..n {
+            a[(row, col)] = a[(row, col)] / scale;
+        }
+    }
+
+    let mut norm_squared = zero;
+    for row in 0..m {
+        for col in 0..n {
+            norm_squared = norm_squared + a[(row, col)] * a[(row, col)];
+        }
+    }
+    let negligible = T::epsilon() * T::epsilon() * norm_squared;
+
+    let mut converged = false;
+    for _ in 0..max_sweeps {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let mut alpha = zero;
+                let mut beta = zero;
+                let mut gamma = zero;
+                for k in 0..m {
+                    alpha = alpha + a[(k, p)] * a[(k, p)];
+                    beta = beta + a[(k, q)] * a[(k, q)];
+                    gamma = gamma + a[(k, p)] * a[(k, q)];
+                }
+                // orthogonal to working precision, or one of them is only
+                // rounding noise that further rotations would stir up
+                if gamma.abs() <= T::epsilon() * (alpha * beta).sqrt() || alpha.min(&beta) <= negligible {
+                    continue;
+                }
+                rotated = true;
+
+                // the rotation that diagonalizes the Gram matrix of the pair
+                let (cos, sin) = rotation_from_tangent(jacobi_tangent(alpha, gamma, beta));
+                for k in 0..m {
+                    let (akp, akq) = (a[(k, p)], a[(k, q)]);
+                    a[(k, p)] = cos * akp - sin * akq;
+                    a[(k, q)] = sin * akp + cos * akq;
+                }
+                for k in 0..n {
+                    let (vkp, vkq) = (vectors[(k, p)], vectors[(k, q)]);
+                    vectors[(k, p)] = cos * vkp - sin * vkq;
+                    vectors[(k, q)] = sin * vkp + cos * vkq;
+                }
+            }
+        }
+        if !rotated {
+            converged = true;
+            break;
+        }
+    }
+
+    for col in 0..n {
+        singular_values[col] = scaled_norm((0..m).map(|row| a[(row, col)]));
+    }
+
+    // selection sort into descending order, moving the columns along
+    for i in 0..n {
+        let mut largest = i;
+        for j in i + 1..n {
+            if singular_values[j] > singular_values[largest] {
+                largest = j;
+            }
+        }
+        if largest != i {
+            singular_values.swap(i, largest);
+            for k in 0..m {
+                let t = a[(k, i)];
+                a[(k, i)] = a[(k, largest)];
+                a[(k, largest)] = t;
+            }
+            for k in 0..n {
+                let t = vectors[(k, i)];
+                vectors[(k, i)] = vectors[(k, largest)];
+                vectors[(k, largest)] = t;
+            }
+        }
+    }
+
+    // negligible columns carry no direction, replace them by the first
+    // basis vector that Gram-Schmidt leaves enough of
+    let tolerance = singular_values.first().map_or(zero, |&s| s) * T::epsilon() * T::from_f64(m as f64);
+    for col in 0..n {
+        let sigma = singular_values[col];
+        if sigma > tolerance {
+            for row in 0..m {
+                a[(row, col)] = a[(row, col)] / sigma;
+            }
+        } else {
+            complete_column(a, col);
+        }
+        singular_values[col] = sigma * max;
+    }
+
+    if converged { Ok(()) } else { Err(LinalgError::NotConverged) }
+}
+
+/// Polar decomposition `A = R P` of a square matrix of at most 4x4, into
+/// an orthogonal `R` and a symmetric positive semidefinite `P`, from the
+/// SVD: `R = U V^T` and `P = V S V^T`.
+///
+/// `R` is the orthogonal matrix nearest to `A`, which makes this the usual
+/// way to remove accumulated drift from a rotation matrix. It is a
+/// reflection when the determinant of `A` is negative.
+///
+/// ```
+/// use float::{MatView, MatViewMut};
+/// use float::linalg;
+///
+/// // a rotation by 30 degrees after stretching by 2 and 3
+/// let (c, s) = (30.0_f64.to_radians().cos(), 30.0_f64.to_radians().sin());
+/// let a = [2.0 * c, -3.0 * s,
+///          2.0 * s, 3.0 * c];
+/// let mut r = [0.0; 4];
+/// let mut p = [0.0; 4];
+/// linalg::polar_decompose(&MatView::new(&a, 2, 2), &mut MatViewMut::new(&mut r, 2, 2),
+///                         &mut MatViewMut::new(&mut p, 2, 2)).unwrap();
+///
+/// for (x, y) in r.iter().zip(&[c, -s, s, c]) {
+///     assert!((x - y).abs() < 1e-15);
+/// }
+/// for (x, y) in p.iter().zip(&[2.0, 0.0, 0.0, 3.0]) {
+///     assert!((x - y).abs() < 1e-14);
+/// }
+/// ```
+pub fn polar_decompose<T: Float>(a: &MatView<T>, rotation: &mut MatViewMut<T>, stretch: &mut MatViewMut<T>)
+                                 -> Result<(), LinalgError> {
+    let n = square_size(a.rows(), a.cols());
+    assert!(n <= 4, "polar_decompose takes at most 4x4 matrices");
+    assert!(rotation.rows() == n && rotation.cols() == n && stretch.rows() == n && stretch.cols() == n,
+            "polar_decompose shapes differ");
+
+    let zero = T::from_f64(0.0);
+    let mut u_data = [zero; 16];
+    let mut v_data = [zero; 16];
+    let mut s = [zero; 4];
+    let mut u = MatViewMut::new(&mut u_data[..n * n], n, n);
+    let mut v = MatViewMut::new(&mut v_data[..n * n], n, n);
+    u.copy_from(a);
+    let result = svd_jacobi(&mut u, &mut s[..n], &mut v, SMALL_SWEEPS);
+
+    for i in 0..n {
+        for j in 0..n {
+            let mut r = zero;
+            let mut p = zero;
+            for k in 0..n {
+                r = r + u[(i, k)] * v[(j, k)];
+                p = p + v[(i, k)] * s[k] * v[(j, k)];
+            }
+            rotation[(i, j)] = r;
+            stretch[(i, j)] = p;
+        }
+    }
+    result
+}
+
+/// The rotation `R` and translation `t` minimizing the squared distances
+/// from `R p + t` to `q` over the point pairs, with Kabsch's algorithm.
+///
+/// The result is always a proper rotation, also for coplanar and collinear
+/// points, where the rotation about the degenerate axes is arbitrary but
+/// still a rotation.
+///
+/// ```
+/// use float::linalg;
+///
+/// let from = [[0.0_f64, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 3.0]];
+/// // a quarter turn about z, then a shift
+/// let to: Vec<[f64; 3]> = from.iter().map(|p| [-p[1] + 1.0, p[0] + 2.0, p[2] + 3.0]).collect();
+///
+/// let (r, t) = linalg::kabsch(&from, &to).unwrap();
+/// let expected = [[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]];
+/// for i in 0..3 {
+///     for j in 0..3 {
+///         assert!((r[i][j] - expected[i][j]).abs() < 1e-14);
+///     }
+///     assert!((t[i] - [1.0, 2.0, 3.0][i]).abs() < 1e-14);
+/// }
+///
+/// // a mirror image is matched by a rotation, not a reflection
+/// let mirrored: Vec<[f64; 3]> = from.iter().map(|p| [p[0], p[1], -p[2]]).collect();
+/// let (r, _) = linalg::kabsch(&from, &mirrored).unwrap();
+/// let det = r[0][0] * (r[1][1] * r[2][2] - r[1][2] * r[2][1])
+///     - r[0][1] * (r[1][0] * r[2][2] - r[1][2] * r[2][0])
+///     + r[0][2] * (r[1][0] * r[2][1] - r[1][1] * r[2][0]);
+/// assert!((det - 1.0).abs() < 1e-14);
+/// ```
+pub fn kabsch<T: Float>(from: &[[T; 3]], to: &[[T; 3]]) -> Result<([[T; 3]; 3], [T; 3]), LinalgError> {
+    assert!(from.len() == to.len() && !from.is_empty(), "kabsch needs matching, non-empty point sets");
+
+    let zero = T::from_f64(0.0);
+    let count = T::from_f64(from.len() as f64);
+    let centroid = |points: &[[T; 3]]| {
+        let mut sum = [zero; 3];
+        for p in points {
+            for i in 0..3 {
+                sum[i] = sum[i] + p[i];
+            }
+        }
+        [sum[0] / count, sum[1] / count, sum[2] / count]
+    };
+    let (p0, q0) = (centroid(from), centroid(to));
+
+    // the cross covariance, H = sum (p - p0) (q - q0)^T
+    let mut h = [zero; 9];
+    for (p, q) in from.iter().zip(to) {
+        for i in 0..3 {
+            for j in 0..3 {
+                h[3 * i + j] = h[3 * i + j] + (p[i] - p0[i]) * (q[j] - q0[j]);
+            }
+        }
+    }
+
+    let mut s = [zero; 3];
+    let mut v_data = [zero; 9];
+    let result = {
+        let mut u = MatViewMut::new(&mut h, 3, 3);
+        let mut v = MatViewMut::new(&mut v_data, 3, 3);
+        svd_jacobi(&mut u, &mut s, &mut v, SMALL_SWEEPS)
+    };
+    let (u, v) = (MatView::new(&h, 3, 3), MatView::new(&v_data, 3, 3));
+
+    // R = V U^T, with the smallest singular direction flipped if that is a
+    // reflection
+    let mut r = [[zero; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            r[i][j] = (0..3).fold(zero, |sum, k| sum + v[(i, k)] * u[(j, k)]);
+        }
+    }
+    if dot(r[0], cross(r[1], r[2])) < zero {
+        let two = T::from_f64(2.0);
+        for i in 0..3 {
+            for j in 0..3 {
+                r[i][j] = r[i][j] - two * v[(i, 2)] * u[(j, 2)];
+            }
+        }
+    }
+
+    let rotated = mul_vector(&r, p0);
+    let translation = [q0[0] - rotated[0], q0[1] - rotated[1], q0[2] - rotated[2]];
+    result.map(|_| (r, translation))
+}
+
+#[inline]
+fn square_size(rows: usize, cols: usize) -> usize {
+    assert!(rows == cols, "matrix must be square");
+    rows
+}
+
+/// Forward substitution with the lower triangle of `a`, optionally taking
+/// its diagonal as one.
+fn solve_lower<T: Float>(a: &MatView<T>, b: &mut [T], unit: bool) {
+    for row in 0..b.len() {
+        let mut sum = b[row];
+        for col in 0..row {
+            sum = sum - a[(row, col)] * b[col];
+        }
+        b[row] = if unit { sum } else { sum / a[(row, row)] };
+    }
+}
+
+/// Back substitution with the upper triangle of `a`.
+fn solve_upper<T: Float>(a: &MatView<T>, b: &mut [T]) {
+    for row in (0..b.len()).rev() {
+        let mut sum = b[row];
+        for col in row + 1..b.len() {
+            sum = sum - a[(row, col)] * b[col];
+        }
+        b[row] = sum / a[(row, row)];
+    }
+}
+
+/// The Euclidean norm with a running scale, as LAPACK's `nrm2` does, so
+/// squaring the elements can not overflow or underflow.
+fn scaled_norm<T: Float, I: Iterator<Item = T>>(values: I) -> T {
+    let zero = T::from_f64(0.0);
+    let one = T::from_f64(1.0);
+    let mut scale = zero;
+    let mut sum = one;
+    for x in values {
+        let x = x.abs();
+        if x > scale {
+            sum = one + sum * (scale / x) * (scale / x);
+            scale = x;
+        } else if x != zero {
+            sum = sum + (x / scale) * (x / scale);
+        }
+    }
+    scale * sum.sqrt()
+}
+
+/// The tangent of the Jacobi rotation that zeroes `b` in the symmetric
+/// matrix `[[a, b], [b, c]]`, the root of smaller magnitude.
+#[inline]
+fn jacobi_tangent<T: Float>(a: T, b: T, c: T) -> T {
+    if b == T::from_f64(0.0) {
+        return b;
+    }
+    // halving the difference rather than doubling b, and each side
+    // separately when the difference overflows
+    let half = T::from_f64(0.5);
+    let difference = c - a;
+    let theta = if difference.is_finite() {
+        difference / b * half
+    } else {
+        (c * half - a * half) / b
+    };
+    // hypot keeps a huge theta from overflowing
+    let t = T::from_f64(1.0) / (theta.abs() + theta.hypot(&T::from_f64(1.0)));
+    if theta.is_sign_negative() { -t } else { t }
+}
+
+/// The cosine and sine of the rotation with tangent `t`.
+#[inline]
+fn rotation_from_tangent<T: Float>(t: T) -> (T, T) {
+    let cos = T::from_f64(1.0) / t.hypot(&T::from_f64(1.0));
+    (cos, t * cos)
+}
+
+#[inline]
+fn cross<T: Float>(u: [T; 3], v: [T; 3]) -> [T; 3] {
+    [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]]
+}
+
+#[inline]
+fn dot<T: Float>(u: [T; 3], v: [T; 3]) -> T {
+    u[0] * v[0] + u[1] * v[1] + u[2] * v[2]
+}
+
+#[inline]
+fn mul_vector<T: Float>(a: &[[T; 3]; 3], v: [T; 3]) -> [T; 3] {
+    [dot(a[0], v), dot(a[1], v), dot(a[2], v)]
+}
+
+/// The unit eigenvector of a simple eigenvalue: orthogonal to the rows of
+/// `a - value I`, so the largest cross product of two rows.
+fn eigenvector_by_cross<T: Float>(a: &[[T; 3]; 3], value: T) -> [T; 3] {
+    let rows = [[a[0][0] - value, a[0][1], a[0][2]],
+                [a[1][0], a[1][1] - value, a[1][2]],
+                [a[2][0], a[2][1], a[2][2] - value]];
+    let candidates = [cross(rows[0], rows[1]), cross(rows[0], rows[2]), cross(rows[1], rows[2])];
+
+    let mut best = candidates[0];
+    let mut best_length = dot(best, best);
+    for &candidate in &candidates[1..] {
+        let length = dot(candidate, candidate);
+        if length > best_length {
+            best = candidate;
+            best_length = length;
+        }
+    }
+    if !(best_length > T::from_f64(0.0)) {
+        return [T::from_f64(1.0), T::from_f64(0.0), T::from_f64(0.0)];
+    }
+    let scale = T::from_f64(1.0) / best_length.sqrt();
+    [best[0] * scale, best[1] * scale, best[2] * scale]
+}
+
+/// The other two eigenvectors and eigenvalues, given the unit eigenvector
+/// `w`, from the 2x2 eigenproblem of `a` restricted to the plane orthogonal
+/// to `w`.
+fn eigen_in_complement<T: Float>(a: &[[T; 3]; 3], w: [T; 3]) -> ([T; 2], [[T; 3]; 2]) {
+    let zero = T::from_f64(0.0);
+    let one = T::from_f64(1.0);
+
+    let u = if w[0].abs() > w[1].abs() {
+        let scale = one / w[0].hypot(&w[2]);
+        [-w[2] * scale, zero, w[0] * scale]
+    } else {
+        let scale = one / w[1].hypot(&w[2]);
+        [zero, w[2] * scale, -w[1] * scale]
+    };
+    let v = cross(w, u);
+
+    let av = mul_vector(a, v);
+    let (values, plane) = symmetric_eigen_2x2(dot(u, mul_vector(a, u)), dot(u, av), dot(v, av));
+    let lift = |x: [T; 2]| [x[0] * u[0] + x[1] * v[0], x[0] * u[1] + x[1] * v[1], x[0] * u[2] + x[1] * v[2]];
+    (values, [lift(plane[0]), lift(plane[1])])
+}
+
+/// Overwrites column `col` of `a` with a unit vector orthogonal to the
+/// columns before it, which must be orthonormal.
+fn complete_column<T: Float>(a: &mut MatViewMut<T>, col: usize) {
+    let zero = T::from_f64(0.0);
+    let m = a.rows();
+
+    // the basis vector e_b keeps 1 - sum_o a[b][o]^2 of its squared length
+    // after projecting out the columns, so take the one with the smallest
+    // sum, which keeps at least 1 / m
+    let weight = |a: &MatViewMut<T>, row: usize| {
+        (0..col).fold(zero, |sum, other| sum + a[(row, other)] * a[(row, other)])
+    };
+    let mut basis = 0;
+    for row in 1..m {
+        if weight(a, row) < weight(a, basis) {
+            basis = row;
+        }
+    }
+
+    for row in 0..m {
+        a[(row, col)] = if row == basis { T::from_f64(1.0) } else { zero };
+    }
+    // twice, for the orthogonality lost to rounding in the first pass
+    for _ in 0..2 {
+        for other in 0..col {
+            let projection = (0..m).fold(zero, |sum, row| sum + a[(row, other)] * a[(row, col)]);
+            for row in 0..m {
+                a[(row, col)] = a[(row, col)] - projection * a[(row, other)];
+            }
+        }
+    }
+    let length = scaled_norm((0..m).map(|row| a[(row, col)]));
+    for row in 0..m {
+        a[(row, col)] = a[(row, col)] / length;
+    }
+}
+
+/// Hager's estimate of `||A^-1||_1` with Higham's extra test vector, from
+/// solvers for `A` and `A^T`.
+fn inverse_norm_1<T, S, ST>(n: usize, work: &mut [T], solve: S, solve_transpose: ST) -> T
+    where T: Float,
+          S: Fn(&mut [T]),
+          ST: Fn(&mut [T]),
+{
+    assert!(work.len() >= 2 * n, "condition estimate needs 2 n work elements");
+    if n == 0 {
+        return T::from_f64(0.0);
+    }
+
+    let zero = T::from_f64(0.0);
+    let one = T::from_f64(1.0);
+    let (x, y) = work[..2 * n].split_at_mut(n);
+    for v in x.iter_mut() {
+        *v = one / T::from_f64(n as f64);
+    }
+
+    let mut estimate = zero;
+    for iteration in 0..5 {
+        y.copy_from_slice(x);
+        solve(y);
+        let norm = y.iter().fold(zero, |sum, v| sum + v.abs());
+        if iteration > 0 && norm <= estimate {
+            break;
+        }
+        estimate = norm;
+
+        for v in y.iter_mut() {
+            *v = if v.is_sign_negative() { -one } else { one };
+        }
+        solve_transpose(y);
+
+        let mut j = 0;
+        for i in 1..n {
+            if y[i].abs() > y[j].abs() {
+                j = i;
+            }
+        }
+        let dot = x.iter().zip(y.iter()).fold(zero, |sum, (a, b)| sum + *a * *b);
+        if iteration > 0 && y[j].abs() <= dot {
+            break;
+        }
+
+        for v in x.iter_mut() {
+            *v = zero;
+        }
+        x[j] = one;
+    }
+
+    // catches matrices on which the iteration stalls early
+    let step = if n > 1 { one / T::from_f64((n - 1) as f64) } else { zero };
+    for (i, v) in x.iter_mut().enumerate() {
+        let magnitude = one + step * T::from_f64(i as f64);
+        *v = if i % 2 == 0 { magnitude } else { -magnitude };
+    }
+    solve(x);
+    let norm = x.iter().fold(zero, |sum, v| sum + v.abs());
+    let alternative = T::from_f64(2.0) * norm / T::from_f64(3.0 * n as f64);
+
+    if alternative > estimate { alternative } else { estimate }
+}
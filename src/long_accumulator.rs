@@ -0,0 +1,276 @@
+use float::Float;
+use rounding::RoundingMode;
+
+
+/// Exponent of the least significant bit, low enough for the product of two
+/// subnormals (`integer_decode` reports them with exponent `-1075`).
+const LSB_EXP: i32 = -2150;
+/// 4288 bits: 4198 bits cover every finite product of two `f64`s and the rest
+/// is headroom for carries and the two's complement sign.
+const LIMBS: usize = 134;
+
+
+/// Kulisch style fixed-point accumulator for exact sums of `f64` products.
+///
+/// Products are added to a two's complement integer wide enough to hold any
+/// finite product of two `f64`s, so no rounding happens until `finish`, which
+/// rounds the exact total to nearest, ties to even.
+///
+/// ```
+/// use float::LongAccumulator;
+///
+/// let a = [1e300_f64, 1.0, -1e300, 0.1];
+/// let b = [1e10_f64, 1e-300, 1e10, 10.0];
+///
+/// let mut acc = LongAccumulator::new();
+/// for (&x, &y) in a.iter().zip(b.iter()) {
+///     acc.add_product(x, y);
+/// }
+/// assert_eq!(acc.finish(), 1.0);
+///
+/// let naive = a.iter().zip(b.iter()).fold(0.0, |sum, (&x, &y)| sum + x * y);
+/// assert!(naive.is_nan());
+/// ```
+#[derive(Clone, Copy)]
+pub struct LongAccumulator {
+    limbs: [u32; LIMBS],
+    nan: bool,
+    pos_inf: bool,
+    neg_inf: bool,
+}
+
+impl LongAccumulator {
+    #[inline]
+    pub fn new() -> Self {
+        LongAccumulator {
+            limbs: [0; LIMBS],
+            nan: false,
+            pos_inf: false,
+            neg_inf: false,
+        }
+    }
+
+    /// Adds `x` exactly.
+    #[inline]
+    pub fn add(&mut self, x: f64) {
+        self.add_product(x, 1.0);
+    }
+
+    /// Adds the exact product `a * b`.
+    pub fn add_product(&mut self, a: f64, b: f64) {
+        if !a.is_finite() || !b.is_finite() {
+            let product = a * b;
+
+            if product.is_nan() {
+                self.nan = true;
+            } else if product > 0.0 {
+                self.pos_inf = true;
+            } else {
+                self.neg_inf = true;
+            }
+            return;
+        }
+
+        let (ma, ea, sa) = a.integer_decode();
+        let (mb, eb, sb) = b.integer_decode();
+
+        if ma == 0 || mb == 0 {
+            return;
+        }
+
+        let negative = sa != sb;
+        let exp = ea as i32 + eb as i32 - LSB_EXP;
+        let (a1, a0) = (ma >> 32, ma & 0xffffffff);
+        let (b1, b0) = (mb >> 32, mb & 0xffffffff);
+
+        self.accumulate(a0 * b0, exp, negative);
+        self.accumulate(a1 * b0, exp + 32, negative);
+        self.accumulate(a0 * b1, exp + 32, negative);
+        self.accumulate(a1 * b1, exp + 64, negative);
+    }
+
+    /// Returns the accumulated total rounded to the nearest `f64`, ties to even.
+    #[inline]
+    pub fn finish(&self) -> f64 {
+        self.to_f64_rounded(RoundingMode::NearestEven).0
+    }
+
+    /// Returns the accumulated total rounded to `f64` with `mode`, and whether
+    /// the result is exact.
+    ///
+    /// ```
+    /// use float::{LongAccumulator, RoundingMode};
+    ///
+    /// let mut acc = LongAccumulator::new();
+    /// acc.add(1.0);
+    /// acc.add(1e-30);
+    /// assert_eq!(acc.to_f64_rounded(RoundingMode::NearestEven), (1.0, false));
+    /// assert_eq!(acc.to_f64_rounded(RoundingMode::TowardPositive), (1.0000000000000002, false));
+    ///
+    /// acc.add(-1e-30);
+    /// assert_eq!(acc.to_f64_rounded(RoundingMode::TowardPositive), (1.0, true));
+    ///
+    /// let mut big = LongAccumulator::new();
+    /// big.add_product(1e300, 1e300);
+    /// assert_eq!(big.to_f64_rounded(RoundingMode::TowardZero), (f64::MAX, false));
+    /// assert_eq!(big.to_f64_rounded(RoundingMode::NearestEven), (f64::INFINITY, false));
+    /// ```
+    pub fn to_f64_rounded(&self, mode: RoundingMode) -> (f64, bool) {
+        if self.nan || (self.pos_inf && self.neg_inf) {
+            return (f64::nan(), false);
+        } else if self.pos_inf {
+            return (f64::infinity(), true);
+        } else if self.neg_inf {
+            return (f64::neg_infinity(), true);
+        }
+
+        let negative = self.limbs[LIMBS - 1] >> 31 != 0;
+        let mut magnitude = self.limbs;
+
+        if negative {
+            negate(&mut magnitude);
+        }
+
+        let top = match highest_bit(&magnitude) {
+            Some(top) => top as i32,
+            None => return (0.0, true),
+        };
+
+        let mut lsb = top - 52;
+        if lsb + LSB_EXP < -1074 {
+            lsb = -1074 - LSB_EXP;
+        }
+
+        let mut mantissa = 0_u64;
+        let mut pos = top;
+        while pos >= lsb {
+            mantissa = (mantissa << 1) | bit(&magnitude, pos as usize);
+            pos -= 1;
+        }
+
+        let round = lsb > 0 && bit(&magnitude, (lsb - 1) as usize) != 0;
+        let sticky = lsb > 1 && any_below(&magnitude, (lsb - 1) as usize);
+
+        if mode.rounds_away(negative, mantissa & 1 != 0, round, sticky) {
+            mantissa += 1;
+        }
+
+        let mut value = scale2(mantissa as f64, lsb + LSB_EXP);
+        let mut exact = !round && !sticky;
+
+        if value.is_infinite() {
+            exact = false;
+            if !mode.overflows_to_infinity(negative) {
+                value = f64::MAX;
+            }
+        }
+
+        (if negative { -value } else { value }, exact)
+    }
+
+    /// Adds or subtracts `value * 2^(pos + LSB_EXP)`.
+    #[inline]
+    fn accumulate(&mut self, value: u64, pos: i32, negative: bool) {
+        let limb = (pos / 32) as usize;
+        let offset = (pos % 32) as u32;
+        let lo = (value & 0xffffffff) << offset;
+        let hi = (value >> 32) << offset;
+
+        for &(index, word) in &[(limb, lo), (limb + 1, hi)] {
+            if negative {
+                self.sub_word(index, word as u32);
+                self.sub_word(index + 1, (word >> 32) as u32);
+            } else {
+                self.add_word(index, word as u32);
+                self.add_word(index + 1, (word >> 32) as u32);
+            }
+        }
+    }
+
+    #[inline]
+    fn add_word(&mut self, mut index: usize, word: u32) {
+        let mut carry = word as u64;
+
+        while carry != 0 && index < LIMBS {
+            let sum = self.limbs[index] as u64 + carry;
+            self.limbs[index] = sum as u32;
+            carry = sum >> 32;
+            index += 1;
+        }
+    }
+
+    #[inline]
+    fn sub_word(&mut self, mut index: usize, word: u32) {
+        let mut borrow = word as u64;
+
+        while borrow != 0 && index < LIMBS {
+            let limb = self.limbs[index] as u64;
+            self.limbs[index] = limb.wrapping_sub(borrow) as u32;
+            borrow = if limb < borrow { 1 } else { 0 };
+            index += 1;
+        }
+    }
+}
+
+impl Default for LongAccumulator {
+    #[inline(always)]
+    fn default() -> Self {
+        LongAccumulator::new()
+    }
+}
+
+
+#[inline]
+fn negate(limbs: &mut [u32; LIMBS]) {
+    let mut carry = 1_u64;
+
+    for limb in limbs.iter_mut() {
+        let sum = (!*limb) as u64 + carry;
+        *limb = sum as u32;
+        carry = sum >> 32;
+    }
+}
+
+#[inline]
+fn highest_bit(limbs: &[u32; LIMBS]) -> Option<usize> {
+    for index in (0..LIMBS).rev() {
+        if limbs[index] != 0 {
+            return Some(index * 32 + 31 - limbs[index].leading_zeros() as usize);
+        }
+    }
+    None
+}
+
+#[inline(always)]
+fn bit(limbs: &[u32; LIMBS], pos: usize) -> u64 {
+    ((limbs[pos / 32] >> (pos % 32)) & 1) as u64
+}
+
+/// Returns true if any bit strictly below `pos` is set.
+#[inline]
+fn any_below(limbs: &[u32; LIMBS], pos: usize) -> bool {
+    let limb = pos / 32;
+    let mask = (1_u32 << (pos % 32)).wrapping_sub(1);
+
+    limbs[limb] & mask != 0 || limbs[..limb].iter().any(|&l| l != 0)
+}
+
+/// Computes `x * 2^exp` in steps that never leave the normal range, so the
+/// only rounding is the final one.
+#[inline]
+fn scale2(mut x: f64, mut exp: i32) -> f64 {
+    while exp > 1023 {
+        x *= pow2(1023);
+        exp -= 1023;
+    }
+    while exp < -1022 {
+        x *= pow2(-1022);
+        exp += 1022;
+    }
+    x * pow2(exp)
+}
+
+#[inline(always)]
+fn pow2(exp: i32) -> f64 {
+    Float::from_bits(((exp + 1023) as u64) << 52)
+}
@@ -0,0 +1,212 @@
+use float::Float;
+
+
+/// Largest number of dimensions `Sobol` supports.
+pub const SOBOL_MAX_DIMENSIONS: usize = 8;
+
+/// Joe and Kuo's primitive polynomials (degree `s`, coefficients `a`) and
+/// initial direction numbers `m` for dimensions 2 to 8; dimension 1 is the
+/// van der Corput sequence in base 2.
+const SOBOL_PARAMETERS: [(u32, u32, [u32; 5]); SOBOL_MAX_DIMENSIONS - 1] = [
+    (1, 0, [1, 0, 0, 0, 0]),
+    (2, 1, [1, 3, 0, 0, 0]),
+    (3, 1, [1, 3, 1, 0, 0]),
+    (3, 2, [1, 1, 1, 0, 0]),
+    (4, 1, [1, 1, 3, 3, 0]),
+    (4, 4, [1, 3, 5, 13, 0]),
+    (5, 2, [1, 1, 5, 5, 17]),
+];
+
+
+/// Returns the radical inverse of `index` in `base`, the `index`-th element of
+/// the van der Corput sequence, truncated to the precision of `T`.
+///
+/// The digits are mirrored with integer arithmetic and the quotient is formed
+/// by exact long division, so the result is the largest `T` not above the
+/// true value and always lies in `[0, 1)`.
+///
+/// ```
+/// use float::radical_inverse;
+/// assert_eq!(radical_inverse::<f64>(2, 1), 0.5);
+/// assert_eq!(radical_inverse::<f64>(2, 6), 0.375);
+/// assert_eq!(radical_inverse::<f32>(3, 1), 0.33333331);
+/// assert!(radical_inverse::<f32>(3, u32::max_value()) < 1.0);
+/// ```
+pub fn radical_inverse<T: Float>(base: u32, index: u32) -> T {
+    assert!((2..=1 << 16).contains(&base), "radical_inverse base must be in 2..=65536");
+
+    let base = base as u64;
+    let mut rest = index as u64;
+    let mut numerator = 0_u64;
+    let mut denominator = 1_u64;
+
+    while rest != 0 {
+        numerator = numerator * base + rest % base;
+        denominator *= base;
+        rest /= base;
+    }
+
+    // floor(numerator / denominator * 2^digits), one bit at a time
+    let digits = T::mantissa_digits();
+    let mut quotient = 0_u64;
+    let mut remainder = numerator;
+
+    for _ in 0..digits {
+        remainder <<= 1;
+        quotient <<= 1;
+        if remainder >= denominator {
+            remainder -= denominator;
+            quotient |= 1;
+        }
+    }
+
+    T::from_f64(quotient as f64) * T::from_f64(0.5).powi(digits as i32)
+}
+
+
+/// One dimension of the Halton sequence: successive radical inverses in a
+/// fixed base. Use coprime bases, usually the first primes, for further
+/// dimensions.
+///
+/// ```
+/// use float::Halton;
+/// let mut halton = Halton::new(2);
+/// let points: Vec<f64> = (0..4).map(|_| halton.sample()).collect();
+/// assert_eq!(points, [0.0, 0.5, 0.25, 0.75]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Halton {
+    base: u32,
+    index: u32,
+}
+
+impl Halton {
+    #[inline]
+    pub fn new(base: u32) -> Self {
+        Halton {
+            base: base,
+            index: 0,
+        }
+    }
+
+    /// Continues the sequence from `index`.
+    #[inline(always)]
+    pub fn seek(&mut self, index: u32) {
+        self.index = index;
+    }
+
+    #[inline(always)]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the point at the current index and moves to the next one.
+    #[inline]
+    pub fn sample<T: Float>(&mut self) -> T {
+        let value = radical_inverse(self.base, self.index);
+        self.index = self.index.wrapping_add(1);
+        value
+    }
+}
+
+
+/// Sobol sequence in up to `SOBOL_MAX_DIMENSIONS` dimensions, generated in
+/// Gray code order with 32-bit direction numbers.
+///
+/// Each coordinate is the 32-bit state truncated to the precision of `T`, so
+/// values lie in `[0, 1)` and the first `2^k` points of every dimension hit
+/// each interval `[j / 2^k, (j + 1) / 2^k)` exactly once.
+///
+/// ```
+/// use float::Sobol;
+///
+/// let mut sobol = Sobol::new(3);
+/// let mut point = [0.0_f64; 3];
+///
+/// sobol.sample(&mut point);
+/// assert_eq!(point, [0.0, 0.0, 0.0]);
+/// sobol.sample(&mut point);
+/// assert_eq!(point, [0.5, 0.5, 0.5]);
+/// sobol.sample(&mut point);
+/// assert_eq!(point, [0.75, 0.25, 0.25]);
+/// sobol.sample(&mut point);
+/// assert_eq!(point, [0.25, 0.75, 0.75]);
+/// ```
+#[derive(Clone, Copy)]
+pub struct Sobol {
+    dimensions: usize,
+    index: u32,
+    state: [u32; SOBOL_MAX_DIMENSIONS],
+    directions: [[u32; 32]; SOBOL_MAX_DIMENSIONS],
+}
+
+impl Sobol {
+    pub fn new(dimensions: usize) -> Self {
+        assert!((1..=SOBOL_MAX_DIMENSIONS).contains(&dimensions),
+                "Sobol supports 1 to {} dimensions", SOBOL_MAX_DIMENSIONS);
+
+        let mut directions = [[0_u32; 32]; SOBOL_MAX_DIMENSIONS];
+
+        for (bit, direction) in directions[0].iter_mut().enumerate() {
+            *direction = 1 << (31 - bit);
+        }
+
+        for (dimension, &(s, a, m)) in SOBOL_PARAMETERS.iter().enumerate() {
+            let v = &mut directions[dimension + 1];
+            let s = s as usize;
+
+            for bit in 0..s {
+                v[bit] = m[bit] << (31 - bit);
+            }
+            for bit in s..32 {
+                v[bit] = v[bit - s] ^ (v[bit - s] >> s);
+                for k in 1..s {
+                    if (a >> (s - 1 - k)) & 1 == 1 {
+                        v[bit] ^= v[bit - k];
+                    }
+                }
+            }
+        }
+
+        Sobol {
+            dimensions: dimensions,
+            index: 0,
+            state: [0; SOBOL_MAX_DIMENSIONS],
+            directions: directions,
+        }
+    }
+
+    #[inline(always)]
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    #[inline(always)]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Writes the next point into the first `dimensions` entries of `point`.
+    /// After `2^32` points the sequence starts over.
+    pub fn sample<T: Float>(&mut self, point: &mut [T]) {
+        assert!(point.len() >= self.dimensions, "point buffer smaller than the Sobol dimensions");
+
+        let digits = if T::mantissa_digits() < 32 { T::mantissa_digits() } else { 32 };
+        let scale = T::from_f64(0.5).powi(digits as i32);
+
+        for (value, &state) in point.iter_mut().zip(self.state[..self.dimensions].iter()) {
+            *value = T::from_f64((state >> (32 - digits)) as f64) * scale;
+        }
+
+        let bit = (!self.index).trailing_zeros() as usize;
+        if bit < 32 {
+            for dimension in 0..self.dimensions {
+                self.state[dimension] ^= self.directions[dimension][bit];
+            }
+            self.index += 1;
+        } else {
+            self.index = 0;
+            self.state = [0; SOBOL_MAX_DIMENSIONS];
+        }
+    }
+}
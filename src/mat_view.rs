@@ -0,0 +1,285 @@
+use core::ops::{Index, IndexMut};
+
+
+/// A read-only matrix view into a slice, element `(r, c)` at
+/// `r * row_stride + c * col_stride`.
+///
+/// Strides describe row-major, column-major, transposed or padded layouts
+/// alike, so user buffers are used in place.
+///
+/// ```
+/// use float::MatView;
+///
+/// // a 2x3 matrix stored row-major with a padding element per row
+/// let data = [1.0_f64, 2.0, 3.0, 0.0, 4.0, 5.0, 6.0, 0.0];
+/// let m = MatView::with_strides(&data, 2, 3, 4, 1);
+/// assert_eq!(m[(1, 2)], 6.0);
+///
+/// let t = m.transpose();
+/// assert_eq!((t.rows(), t.cols()), (3, 2));
+/// assert_eq!(t[(2, 1)], 6.0);
+///
+/// let corner = m.submatrix(0, 1, 2, 2);
+/// assert_eq!(corner[(1, 0)], 5.0);
+/// assert_eq!(m.get(2, 0), None);
+/// ```
+#[derive(Debug)]
+pub struct MatView<'a, T: 'a> {
+    data: &'a [T],
+    rows: usize,
+    cols: usize,
+    row_stride: usize,
+    col_stride: usize,
+}
+
+impl<'a, T: 'a> Clone for MatView<'a, T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T: 'a> Copy for MatView<'a, T> {}
+
+impl<'a, T: 'a> MatView<'a, T> {
+    /// A contiguous row-major `rows x cols` view of `data`.
+    #[inline]
+    pub fn new(data: &'a [T], rows: usize, cols: usize) -> Self {
+        MatView::with_strides(data, rows, cols, cols, 1)
+    }
+
+    #[inline]
+    pub fn with_strides(data: &'a [T], rows: usize, cols: usize, row_stride: usize, col_stride: usize) -> Self {
+        check_layout(data.len(), rows, cols, row_stride, col_stride);
+        MatView {
+            data: data,
+            rows: rows,
+            cols: cols,
+            row_stride: row_stride,
+            col_stride: col_stride,
+        }
+    }
+
+    #[inline(always)]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[inline(always)]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    #[inline(always)]
+    pub fn row_stride(&self) -> usize {
+        self.row_stride
+    }
+
+    #[inline(always)]
+    pub fn col_stride(&self) -> usize {
+        self.col_stride
+    }
+
+    #[inline]
+    pub fn get(&self, row: usize, col: usize) -> Option<&'a T> {
+        if row < self.rows && col < self.cols {
+            Some(&self.data[row * self.row_stride + col * self.col_stride])
+        } else {
+            None
+        }
+    }
+
+    /// The same elements with rows and columns exchanged.
+    #[inline]
+    pub fn transpose(&self) -> MatView<'a, T> {
+        MatView {
+            data: self.data,
+            rows: self.cols,
+            cols: self.rows,
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+        }
+    }
+
+    /// The `rows x cols` block starting at `(row, col)`.
+    #[inline]
+    pub fn submatrix(&self, row: usize, col: usize, rows: usize, cols: usize) -> MatView<'a, T> {
+        assert!(row + rows <= self.rows && col + cols <= self.cols, "submatrix out of bounds");
+        let start = if rows == 0 || cols == 0 { 0 } else { row * self.row_stride + col * self.col_stride };
+        MatView {
+            data: &self.data[start..],
+            rows: rows,
+            cols: cols,
+            row_stride: self.row_stride,
+            col_stride: self.col_stride,
+        }
+    }
+
+    #[inline]
+    pub fn row(&self, row: usize) -> MatView<'a, T> {
+        self.submatrix(row, 0, 1, self.cols)
+    }
+
+    #[inline]
+    pub fn column(&self, col: usize) -> MatView<'a, T> {
+        self.submatrix(0, col, self.rows, 1)
+    }
+}
+
+impl<'a, T: 'a> Index<(usize, usize)> for MatView<'a, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(row < self.rows && col < self.cols, "matrix index out of bounds");
+        &self.data[row * self.row_stride + col * self.col_stride]
+    }
+}
+
+
+/// A mutable matrix view into a slice, laid out like `MatView`.
+///
+/// ```
+/// use float::{MatView, MatViewMut};
+///
+/// // column-major storage
+/// let mut data = [0.0_f32; 6];
+/// {
+///     let mut m = MatViewMut::with_strides(&mut data, 2, 3, 1, 2);
+///     m[(0, 1)] = 1.0;
+///     m.submatrix_mut(1, 1, 1, 2).fill(2.0);
+///     assert_eq!(m.view()[(1, 2)], 2.0);
+/// }
+/// assert_eq!(data, [0.0, 0.0, 1.0, 2.0, 0.0, 2.0]);
+///
+/// let source = [1.0_f32, 2.0, 3.0, 4.0];
+/// let mut target = [0.0_f32; 4];
+/// MatViewMut::new(&mut target, 2, 2).copy_from(&MatView::new(&source, 2, 2).transpose());
+/// assert_eq!(target, [1.0, 3.0, 2.0, 4.0]);
+/// ```
+#[derive(Debug)]
+pub struct MatViewMut<'a, T: 'a> {
+    data: &'a mut [T],
+    rows: usize,
+    cols: usize,
+    row_stride: usize,
+    col_stride: usize,
+}
+
+impl<'a, T: 'a> MatViewMut<'a, T> {
+    /// A contiguous row-major `rows x cols` view of `data`.
+    #[inline]
+    pub fn new(data: &'a mut [T], rows: usize, cols: usize) -> Self {
+        MatViewMut::with_strides(data, rows, cols, cols, 1)
+    }
+
+    /// Elements that are reached by several `(row, col)` pairs alias each
+    /// other, which is allowed.
+    #[inline]
+    pub fn with_strides(data: &'a mut [T], rows: usize, cols: usize, row_stride: usize, col_stride: usize) -> Self {
+        check_layout(data.len(), rows, cols, row_stride, col_stride);
+        MatViewMut {
+            data: data,
+            rows: rows,
+            cols: cols,
+            row_stride: row_stride,
+            col_stride: col_stride,
+        }
+    }
+
+    #[inline(always)]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[inline(always)]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    #[inline]
+    pub fn view<'b>(&'b self) -> MatView<'b, T> {
+        MatView {
+            data: self.data,
+            rows: self.rows,
+            cols: self.cols,
+            row_stride: self.row_stride,
+            col_stride: self.col_stride,
+        }
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        if row < self.rows && col < self.cols {
+            Some(&mut self.data[row * self.row_stride + col * self.col_stride])
+        } else {
+            None
+        }
+    }
+
+    /// The `rows x cols` block starting at `(row, col)`, borrowing this view.
+    #[inline]
+    pub fn submatrix_mut<'b>(&'b mut self, row: usize, col: usize, rows: usize, cols: usize) -> MatViewMut<'b, T> {
+        assert!(row + rows <= self.rows && col + cols <= self.cols, "submatrix out of bounds");
+        let start = if rows == 0 || cols == 0 { 0 } else { row * self.row_stride + col * self.col_stride };
+        MatViewMut {
+            data: &mut self.data[start..],
+            rows: rows,
+            cols: cols,
+            row_stride: self.row_stride,
+            col_stride: self.col_stride,
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a> MatViewMut<'a, T> {
+    #[inline]
+    pub fn fill(&mut self, value: T) {
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                self[(row, col)] = value;
+            }
+        }
+    }
+
+    /// Copies a view of the same shape element by element.
+    #[inline]
+    pub fn copy_from(&mut self, source: &MatView<T>) {
+        assert!(source.rows() == self.rows && source.cols() == self.cols, "matrix shapes differ");
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                self[(row, col)] = source[(row, col)];
+            }
+        }
+    }
+}
+
+impl<'a, T: 'a> Index<(usize, usize)> for MatViewMut<'a, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(row < self.rows && col < self.cols, "matrix index out of bounds");
+        &self.data[row * self.row_stride + col * self.col_stride]
+    }
+}
+
+impl<'a, T: 'a> IndexMut<(usize, usize)> for MatViewMut<'a, T> {
+    #[inline]
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(row < self.rows && col < self.cols, "matrix index out of bounds");
+        &mut self.data[row * self.row_stride + col * self.col_stride]
+    }
+}
+
+
+/// Checks that the last element of a `rows x cols` layout lies in a slice
+/// of `len` elements.
+#[inline]
+fn check_layout(len: usize, rows: usize, cols: usize, row_stride: usize, col_stride: usize) {
+    if rows > 0 && cols > 0 {
+        let last = (rows - 1).checked_mul(row_stride)
+            .and_then(|r| (cols - 1).checked_mul(col_stride).and_then(|c| r.checked_add(c)));
+        assert!(matches!(last, Some(last) if last < len), "matrix view exceeds its slice");
+    }
+}
@@ -0,0 +1,502 @@
+//! The elementary functions underneath `Float`, from the C math library, or
+//! with the `libm` feature from the pure Rust `libm` crate, so nothing links
+//! against a C libm. With the `std` feature, unless `libm` is on as well,
+//! they are the inherent methods of `f32` and `f64` instead.
+//!
+//! The `unstable` feature takes rounding, square roots, `fma` and the
+//! functions the compiler has intrinsics for from the intrinsics instead,
+//! which LLVM folds and turns into instructions where the target has them,
+//! and lowers to the same libm calls otherwise.
+//!
+//! On `wasm32-unknown-unknown` there is no C math library, only the C names
+//! the compiler's builtins define. `cbrt`, `expm1`, `hypot`, `log1p` and the
+//! inverse hyperbolic functions are not among them, so there they are
+//! written here in terms of the others, within a few ulps of the C versions.
+//! Those fallbacks are built on every target, so the fuzz targets can
+//! compare them with the C ones.
+//!
+//! Every function is `unsafe` to call either way, like the C declarations.
+
+#[cfg(all(feature = "unstable", not(any(feature = "libm", feature = "std"))))]
+use core::intrinsics;
+
+#[cfg(all(not(all(target_arch = "wasm32", target_os = "unknown")), not(any(feature = "libm", feature = "std"))))]
+use libc::{c_float, c_double};
+#[cfg(all(all(target_arch = "wasm32", target_os = "unknown"), not(any(feature = "libm", feature = "std"))))]
+#[allow(non_camel_case_types)]
+type c_float = f32;
+#[cfg(all(all(target_arch = "wasm32", target_os = "unknown"), not(any(feature = "libm", feature = "std"))))]
+#[allow(non_camel_case_types)]
+type c_double = f64;
+#[cfg(feature = "libm")]
+use libm;
+
+
+macro_rules! forward {
+    ($($name:ident($($arg:ident: $A:ty),*) -> $R:ty => $target:path;)*) => ($(
+        #[inline(always)]
+        pub unsafe fn $name($($arg: $A),*) -> $R {
+            $target($($arg),*)
+        }
+    )*)
+}
+
+
+#[cfg(not(any(feature = "libm", feature = "std")))]
+extern "C" {
+    pub fn acosf(n: c_float) -> c_float;
+    pub fn asinf(n: c_float) -> c_float;
+    pub fn atan2f(y: c_float, x: c_float) -> c_float;
+    pub fn atanf(n: c_float) -> c_float;
+    pub fn coshf(n: c_float) -> c_float;
+    pub fn expf(n: c_float) -> c_float;
+    pub fn exp2f(n: c_float) -> c_float;
+    pub fn logf(n: c_float) -> c_float;
+    pub fn log2f(n: c_float) -> c_float;
+    pub fn log10f(n: c_float) -> c_float;
+    pub fn powf(x: c_float, y: c_float) -> c_float;
+    pub fn sinhf(n: c_float) -> c_float;
+    pub fn tanf(n: c_float) -> c_float;
+    pub fn tanhf(n: c_float) -> c_float;
+
+    pub fn acos(n: c_double) -> c_double;
+    pub fn asin(n: c_double) -> c_double;
+    pub fn atan2(y: c_double, x: c_double) -> c_double;
+    pub fn atan(n: c_double) -> c_double;
+    pub fn cosh(n: c_double) -> c_double;
+    pub fn exp(n: c_double) -> c_double;
+    pub fn exp2(n: c_double) -> c_double;
+    pub fn log(n: c_double) -> c_double;
+    pub fn log2(n: c_double) -> c_double;
+    pub fn log10(n: c_double) -> c_double;
+    pub fn pow(x: c_double, y: c_double) -> c_double;
+    pub fn sinh(n: c_double) -> c_double;
+    pub fn tan(n: c_double) -> c_double;
+    pub fn tanh(n: c_double) -> c_double;
+}
+
+#[cfg(all(not(all(target_arch = "wasm32", target_os = "unknown")), not(any(feature = "libm", feature = "std"))))]
+extern "C" {
+    pub fn acoshf(n: c_float) -> c_float;
+    pub fn asinhf(n: c_float) -> c_float;
+    pub fn atanhf(n: c_float) -> c_float;
+    pub fn cbrtf(n: c_float) -> c_float;
+    pub fn expm1f(n: c_float) -> c_float;
+    pub fn hypotf(x: c_float, y: c_float) -> c_float;
+    pub fn log1pf(n: c_float) -> c_float;
+
+    pub fn acosh(n: c_double) -> c_double;
+    pub fn asinh(n: c_double) -> c_double;
+    pub fn atanh(n: c_double) -> c_double;
+    pub fn cbrt(n: c_double) -> c_double;
+    pub fn expm1(n: c_double) -> c_double;
+    pub fn hypot(x: c_double, y: c_double) -> c_double;
+    pub fn log1p(n: c_double) -> c_double;
+}
+
+// the f32 versions round the f64 ones, which are exact enough for it
+#[cfg(all(all(target_arch = "wasm32", target_os = "unknown"), not(any(feature = "libm", feature = "std"))))]
+forward! {
+    acoshf(n: f32) -> f32 => soft::acoshf;
+    asinhf(n: f32) -> f32 => soft::asinhf;
+    atanhf(n: f32) -> f32 => soft::atanhf;
+    cbrtf(n: f32) -> f32 => soft::cbrtf;
+    expm1f(n: f32) -> f32 => soft::expm1f;
+    hypotf(x: f32, y: f32) -> f32 => soft::hypotf;
+    log1pf(n: f32) -> f32 => soft::log1pf;
+
+    acosh(n: f64) -> f64 => soft::acosh;
+    asinh(n: f64) -> f64 => soft::asinh;
+    atanh(n: f64) -> f64 => soft::atanh;
+    cbrt(n: f64) -> f64 => soft::cbrt;
+    expm1(n: f64) -> f64 => soft::expm1;
+    hypot(x: f64, y: f64) -> f64 => soft::hypot;
+    log1p(n: f64) -> f64 => soft::log1p;
+}
+
+#[cfg(all(feature = "unstable", not(any(feature = "libm", feature = "std"))))]
+forward! {
+    ceilf32(x: f32) -> f32 => intrinsics::ceilf32;
+    cosf32(x: f32) -> f32 => intrinsics::cosf32;
+    exp2f32(x: f32) -> f32 => intrinsics::exp2f32;
+    expf32(x: f32) -> f32 => intrinsics::expf32;
+    floorf32(x: f32) -> f32 => intrinsics::floorf32;
+    fmaf32(x: f32, y: f32, z: f32) -> f32 => intrinsics::fmaf32;
+    log10f32(x: f32) -> f32 => intrinsics::log10f32;
+    log2f32(x: f32) -> f32 => intrinsics::log2f32;
+    logf32(x: f32) -> f32 => intrinsics::logf32;
+    powf32(x: f32, y: f32) -> f32 => intrinsics::powf32;
+    roundf32(x: f32) -> f32 => intrinsics::roundf32;
+    sinf32(x: f32) -> f32 => intrinsics::sinf32;
+    sqrtf32(x: f32) -> f32 => intrinsics::sqrtf32;
+    truncf32(x: f32) -> f32 => intrinsics::truncf32;
+
+    ceilf64(x: f64) -> f64 => intrinsics::ceilf64;
+    cosf64(x: f64) -> f64 => intrinsics::cosf64;
+    exp2f64(x: f64) -> f64 => intrinsics::exp2f64;
+    expf64(x: f64) -> f64 => intrinsics::expf64;
+    floorf64(x: f64) -> f64 => intrinsics::floorf64;
+    fmaf64(x: f64, y: f64, z: f64) -> f64 => intrinsics::fmaf64;
+    log10f64(x: f64) -> f64 => intrinsics::log10f64;
+    log2f64(x: f64) -> f64 => intrinsics::log2f64;
+    logf64(x: f64) -> f64 => intrinsics::logf64;
+    powf64(x: f64, y: f64) -> f64 => intrinsics::powf64;
+    roundf64(x: f64) -> f64 => intrinsics::roundf64;
+    sinf64(x: f64) -> f64 => intrinsics::sinf64;
+    sqrtf64(x: f64) -> f64 => intrinsics::sqrtf64;
+    truncf64(x: f64) -> f64 => intrinsics::truncf64;
+}
+
+
+#[cfg(not(any(feature = "unstable", feature = "libm", feature = "std")))]
+extern "C" {
+    fn ceilf(n: c_float) -> c_float;
+    fn cosf(n: c_float) -> c_float;
+    fn floorf(n: c_float) -> c_float;
+    fn fmaf(x: c_float, y: c_float, z: c_float) -> c_float;
+    fn roundf(n: c_float) -> c_float;
+    fn sinf(n: c_float) -> c_float;
+    fn sqrtf(n: c_float) -> c_float;
+    fn truncf(n: c_float) -> c_float;
+
+    fn ceil(n: c_double) -> c_double;
+    fn cos(n: c_double) -> c_double;
+    fn floor(n: c_double) -> c_double;
+    fn fma(x: c_double, y: c_double, z: c_double) -> c_double;
+    fn round(n: c_double) -> c_double;
+    fn sin(n: c_double) -> c_double;
+    fn sqrt(n: c_double) -> c_double;
+    fn trunc(n: c_double) -> c_double;
+}
+
+#[cfg(not(any(feature = "unstable", feature = "libm", feature = "std")))]
+forward! {
+    ceilf32(x: f32) -> f32 => ceilf;
+    cosf32(x: f32) -> f32 => cosf;
+    exp2f32(x: f32) -> f32 => exp2f;
+    expf32(x: f32) -> f32 => expf;
+    floorf32(x: f32) -> f32 => floorf;
+    fmaf32(x: f32, y: f32, z: f32) -> f32 => fmaf;
+    log10f32(x: f32) -> f32 => log10f;
+    log2f32(x: f32) -> f32 => log2f;
+    logf32(x: f32) -> f32 => logf;
+    powf32(x: f32, y: f32) -> f32 => powf;
+    roundf32(x: f32) -> f32 => roundf;
+    sinf32(x: f32) -> f32 => sinf;
+    sqrtf32(x: f32) -> f32 => sqrtf;
+    truncf32(x: f32) -> f32 => truncf;
+
+    ceilf64(x: f64) -> f64 => ceil;
+    cosf64(x: f64) -> f64 => cos;
+    exp2f64(x: f64) -> f64 => exp2;
+    expf64(x: f64) -> f64 => exp;
+    floorf64(x: f64) -> f64 => floor;
+    fmaf64(x: f64, y: f64, z: f64) -> f64 => fma;
+    log10f64(x: f64) -> f64 => log10;
+    log2f64(x: f64) -> f64 => log2;
+    logf64(x: f64) -> f64 => log;
+    powf64(x: f64, y: f64) -> f64 => pow;
+    roundf64(x: f64) -> f64 => round;
+    sinf64(x: f64) -> f64 => sin;
+    sqrtf64(x: f64) -> f64 => sqrt;
+    truncf64(x: f64) -> f64 => trunc;
+}
+
+
+#[cfg(feature = "libm")]
+forward! {
+    acosf(n: f32) -> f32 => libm::acosf;
+    acoshf(n: f32) -> f32 => libm::acoshf;
+    asinf(n: f32) -> f32 => libm::asinf;
+    asinhf(n: f32) -> f32 => libm::asinhf;
+    atan2f(y: f32, x: f32) -> f32 => libm::atan2f;
+    atanf(n: f32) -> f32 => libm::atanf;
+    atanhf(n: f32) -> f32 => libm::atanhf;
+    cbrtf(n: f32) -> f32 => libm::cbrtf;
+    coshf(n: f32) -> f32 => libm::coshf;
+    expf(n: f32) -> f32 => libm::expf;
+    exp2f(n: f32) -> f32 => libm::exp2f;
+    expm1f(n: f32) -> f32 => libm::expm1f;
+    hypotf(x: f32, y: f32) -> f32 => libm::hypotf;
+    logf(n: f32) -> f32 => libm::logf;
+    log1pf(n: f32) -> f32 => libm::log1pf;
+    log2f(n: f32) -> f32 => libm::log2f;
+    log10f(n: f32) -> f32 => libm::log10f;
+    powf(x: f32, y: f32) -> f32 => libm::powf;
+    sinhf(n: f32) -> f32 => libm::sinhf;
+    tanf(n: f32) -> f32 => libm::tanf;
+    tanhf(n: f32) -> f32 => libm::tanhf;
+
+    acos(n: f64) -> f64 => libm::acos;
+    acosh(n: f64) -> f64 => libm::acosh;
+    asin(n: f64) -> f64 => libm::asin;
+    asinh(n: f64) -> f64 => libm::asinh;
+    atan2(y: f64, x: f64) -> f64 => libm::atan2;
+    atan(n: f64) -> f64 => libm::atan;
+    atanh(n: f64) -> f64 => libm::atanh;
+    cbrt(n: f64) -> f64 => libm::cbrt;
+    cosh(n: f64) -> f64 => libm::cosh;
+    exp(n: f64) -> f64 => libm::exp;
+    exp2(n: f64) -> f64 => libm::exp2;
+    expm1(n: f64) -> f64 => libm::expm1;
+    hypot(x: f64, y: f64) -> f64 => libm::hypot;
+    log(n: f64) -> f64 => libm::log;
+    log1p(n: f64) -> f64 => libm::log1p;
+    log2(n: f64) -> f64 => libm::log2;
+    log10(n: f64) -> f64 => libm::log10;
+    pow(x: f64, y: f64) -> f64 => libm::pow;
+    sinh(n: f64) -> f64 => libm::sinh;
+    tan(n: f64) -> f64 => libm::tan;
+    tanh(n: f64) -> f64 => libm::tanh;
+
+    ceilf32(x: f32) -> f32 => libm::ceilf;
+    cosf32(x: f32) -> f32 => libm::cosf;
+    exp2f32(x: f32) -> f32 => libm::exp2f;
+    expf32(x: f32) -> f32 => libm::expf;
+    floorf32(x: f32) -> f32 => libm::floorf;
+    fmaf32(x: f32, y: f32, z: f32) -> f32 => libm::fmaf;
+    log10f32(x: f32) -> f32 => libm::log10f;
+    log2f32(x: f32) -> f32 => libm::log2f;
+    logf32(x: f32) -> f32 => libm::logf;
+    powf32(x: f32, y: f32) -> f32 => libm::powf;
+    roundf32(x: f32) -> f32 => libm::roundf;
+    sinf32(x: f32) -> f32 => libm::sinf;
+    sqrtf32(x: f32) -> f32 => libm::sqrtf;
+    truncf32(x: f32) -> f32 => libm::truncf;
+
+    ceilf64(x: f64) -> f64 => libm::ceil;
+    cosf64(x: f64) -> f64 => libm::cos;
+    exp2f64(x: f64) -> f64 => libm::exp2;
+    expf64(x: f64) -> f64 => libm::exp;
+    floorf64(x: f64) -> f64 => libm::floor;
+    fmaf64(x: f64, y: f64, z: f64) -> f64 => libm::fma;
+    log10f64(x: f64) -> f64 => libm::log10;
+    log2f64(x: f64) -> f64 => libm::log2;
+    logf64(x: f64) -> f64 => libm::log;
+    powf64(x: f64, y: f64) -> f64 => libm::pow;
+    roundf64(x: f64) -> f64 => libm::round;
+    sinf64(x: f64) -> f64 => libm::sin;
+    sqrtf64(x: f64) -> f64 => libm::sqrt;
+    truncf64(x: f64) -> f64 => libm::trunc;
+}
+
+
+#[cfg(all(feature = "std", not(feature = "libm")))]
+forward! {
+    acosf(n: f32) -> f32 => f32::acos;
+    acoshf(n: f32) -> f32 => f32::acosh;
+    asinf(n: f32) -> f32 => f32::asin;
+    asinhf(n: f32) -> f32 => f32::asinh;
+    atan2f(y: f32, x: f32) -> f32 => f32::atan2;
+    atanf(n: f32) -> f32 => f32::atan;
+    atanhf(n: f32) -> f32 => f32::atanh;
+    cbrtf(n: f32) -> f32 => f32::cbrt;
+    coshf(n: f32) -> f32 => f32::cosh;
+    expf(n: f32) -> f32 => f32::exp;
+    exp2f(n: f32) -> f32 => f32::exp2;
+    expm1f(n: f32) -> f32 => f32::exp_m1;
+    hypotf(x: f32, y: f32) -> f32 => f32::hypot;
+    logf(n: f32) -> f32 => f32::ln;
+    log1pf(n: f32) -> f32 => f32::ln_1p;
+    log2f(n: f32) -> f32 => f32::log2;
+    log10f(n: f32) -> f32 => f32::log10;
+    powf(x: f32, y: f32) -> f32 => f32::powf;
+    sinhf(n: f32) -> f32 => f32::sinh;
+    tanf(n: f32) -> f32 => f32::tan;
+    tanhf(n: f32) -> f32 => f32::tanh;
+
+    acos(n: f64) -> f64 => f64::acos;
+    acosh(n: f64) -> f64 => f64::acosh;
+    asin(n: f64) -> f64 => f64::asin;
+    asinh(n: f64) -> f64 => f64::asinh;
+    atan2(y: f64, x: f64) -> f64 => f64::atan2;
+    atan(n: f64) -> f64 => f64::atan;
+    atanh(n: f64) -> f64 => f64::atanh;
+    cbrt(n: f64) -> f64 => f64::cbrt;
+    cosh(n: f64) -> f64 => f64::cosh;
+    exp(n: f64) -> f64 => f64::exp;
+    exp2(n: f64) -> f64 => f64::exp2;
+    expm1(n: f64) -> f64 => f64::exp_m1;
+    hypot(x: f64, y: f64) -> f64 => f64::hypot;
+    log(n: f64) -> f64 => f64::ln;
+    log1p(n: f64) -> f64 => f64::ln_1p;
+    log2(n: f64) -> f64 => f64::log2;
+    log10(n: f64) -> f64 => f64::log10;
+    pow(x: f64, y: f64) -> f64 => f64::powf;
+    sinh(n: f64) -> f64 => f64::sinh;
+    tan(n: f64) -> f64 => f64::tan;
+    tanh(n: f64) -> f64 => f64::tanh;
+
+    ceilf32(x: f32) -> f32 => f32::ceil;
+    cosf32(x: f32) -> f32 => f32::cos;
+    exp2f32(x: f32) -> f32 => f32::exp2;
+    expf32(x: f32) -> f32 => f32::exp;
+    floorf32(x: f32) -> f32 => f32::floor;
+    fmaf32(x: f32, y: f32, z: f32) -> f32 => f32::mul_add;
+    log10f32(x: f32) -> f32 => f32::log10;
+    log2f32(x: f32) -> f32 => f32::log2;
+    logf32(x: f32) -> f32 => f32::ln;
+    powf32(x: f32, y: f32) -> f32 => f32::powf;
+    roundf32(x: f32) -> f32 => f32::round;
+    sinf32(x: f32) -> f32 => f32::sin;
+    sqrtf32(x: f32) -> f32 => f32::sqrt;
+    truncf32(x: f32) -> f32 => f32::trunc;
+
+    ceilf64(x: f64) -> f64 => f64::ceil;
+    cosf64(x: f64) -> f64 => f64::cos;
+    exp2f64(x: f64) -> f64 => f64::exp2;
+    expf64(x: f64) -> f64 => f64::exp;
+    floorf64(x: f64) -> f64 => f64::floor;
+    fmaf64(x: f64, y: f64, z: f64) -> f64 => f64::mul_add;
+    log10f64(x: f64) -> f64 => f64::log10;
+    log2f64(x: f64) -> f64 => f64::log2;
+    logf64(x: f64) -> f64 => f64::ln;
+    powf64(x: f64, y: f64) -> f64 => f64::powf;
+    roundf64(x: f64) -> f64 => f64::round;
+    sinf64(x: f64) -> f64 => f64::sin;
+    sqrtf64(x: f64) -> f64 => f64::sqrt;
+    truncf64(x: f64) -> f64 => f64::trunc;
+}
+
+
+// unsafe only to stand in for the C declarations above
+#[allow(clippy::missing_safety_doc)]
+pub mod soft {
+    use core::{f32, f64};
+    use core::f64::consts::LN_2;
+
+
+    pub unsafe fn acoshf(n: f32) -> f32 {
+        acosh(n as f64) as f32
+    }
+
+    pub unsafe fn asinhf(n: f32) -> f32 {
+        asinh(n as f64) as f32
+    }
+
+    pub unsafe fn atanhf(n: f32) -> f32 {
+        atanh(n as f64) as f32
+    }
+
+    pub unsafe fn cbrtf(n: f32) -> f32 {
+        cbrt(n as f64) as f32
+    }
+
+    pub unsafe fn expm1f(n: f32) -> f32 {
+        expm1(n as f64) as f32
+    }
+
+    pub unsafe fn hypotf(x: f32, y: f32) -> f32 {
+        if x.is_infinite() || y.is_infinite() {
+            return f32::INFINITY;
+        }
+        let (x, y) = (x as f64, y as f64);
+        super::sqrtf64(x * x + y * y) as f32
+    }
+
+    pub unsafe fn log1pf(n: f32) -> f32 {
+        log1p(n as f64) as f32
+    }
+
+    /// `pow` of the magnitude and a Newton step, which takes the cube roots of
+    /// perfect cubes to the exact integer.
+    pub unsafe fn cbrt(n: f64) -> f64 {
+        if n == 0.0 || !n.is_finite() {
+            return n;
+        }
+        let a = if n < 0.0 { -n } else { n };
+        let mut y = super::pow(a, 1.0 / 3.0);
+        y = y + (a / (y * y) - y) / 3.0;
+        if n < 0.0 { -y } else { y }
+    }
+
+    /// Kahan's `(e^x - 1) x / ln(e^x)`, whose rounding errors in `e^x`
+    /// cancel.
+    pub unsafe fn expm1(n: f64) -> f64 {
+        let u = super::exp(n);
+        if u == 1.0 {
+            return n;
+        }
+        let um1 = u - 1.0;
+        if um1 == -1.0 || u == f64::INFINITY {
+            return um1;
+        }
+        um1 * (n / super::log(u))
+    }
+
+    /// `hypot` by the ratio of the smaller to the larger magnitude, an
+    /// infinity wins over a NaN as in C.
+    pub unsafe fn hypot(x: f64, y: f64) -> f64 {
+        if x.is_infinite() || y.is_infinite() {
+            return f64::INFINITY;
+        }
+        let (x, y) = (if x < 0.0 { -x } else { x }, if y < 0.0 { -y } else { y });
+        if x.is_nan() || y.is_nan() {
+            return x + y;
+        }
+        let (large, small) = if x < y { (y, x) } else { (x, y) };
+        if large == 0.0 {
+            return 0.0;
+        }
+        let ratio = small / large;
+        large * super::sqrtf64(1.0 + ratio * ratio)
+    }
+
+    /// fdlibm's `ln(x + sqrt(x^2 - 1))`, through `log1p` near one and without
+    /// the square for large `x`.
+    pub unsafe fn acosh(n: f64) -> f64 {
+        if n < 1.0 {
+            return f64::NAN;
+        }
+        if n > TWO_28 {
+            return super::log(n) + LN_2;
+        }
+        if n > 2.0 {
+            return super::log(2.0 * n - 1.0 / (n + super::sqrtf64(n * n - 1.0)));
+        }
+        let t = n - 1.0;
+        log1p(t + super::sqrtf64(2.0 * t + t * t))
+    }
+
+    /// fdlibm's `ln(x + sqrt(x^2 + 1))`, on the magnitude and through `log1p`
+    /// for small `x`.
+    pub unsafe fn asinh(n: f64) -> f64 {
+        let a = if n < 0.0 { -n } else { n };
+        let y = if a > TWO_28 {
+            super::log(a) + LN_2
+        } else if a > 2.0 {
+            super::log(2.0 * a + 1.0 / (super::sqrtf64(a * a + 1.0) + a))
+        } else {
+            log1p(a + a * a / (1.0 + super::sqrtf64(1.0 + a * a)))
+        };
+        if n.is_sign_negative() { -y } else { y }
+    }
+
+    /// fdlibm's `ln((1 + x) / (1 - x)) / 2` through `log1p`, on the magnitude.
+    pub unsafe fn atanh(n: f64) -> f64 {
+        let a = if n < 0.0 { -n } else { n };
+        let y = if a < 0.5 {
+            let t = a + a;
+            0.5 * log1p(t + t * a / (1.0 - a))
+        } else {
+            0.5 * log1p((a + a) / (1.0 - a))
+        };
+        if n.is_sign_negative() { -y } else { y }
+    }
+
+    /// Goldberg's `ln(1 + x) x / ((1 + x) - 1)`, which cancels the rounding
+    /// of `1 + x`.
+    pub unsafe fn log1p(n: f64) -> f64 {
+        let u = 1.0 + n;
+        if u == 1.0 {
+            return n;
+        }
+        if u == f64::INFINITY {
+            return u;
+        }
+        super::log(u) * (n / (u - 1.0))
+    }
+
+    // above it 1 is negligible against x^2
+    const TWO_28: f64 = 268435456.0;
+}
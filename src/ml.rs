@@ -0,0 +1,290 @@
+//! Slice routines and quantization parameters for machine learning code.
+
+use float::Float;
+
+
+/// Replaces `values` by `softmax(values / temperature)` in place.
+///
+/// The maximum is subtracted before exponentiating, so large logits neither
+/// overflow nor lose the distribution. Lower temperatures sharpen it, higher
+/// ones flatten it towards uniform. Infinite logits get the limit: the mass
+/// is shared equally by the elements at `+inf`, or by all of them when every
+/// one is `-inf`.
+///
+/// ```
+/// use float::ml;
+///
+/// let mut logits = [1000.0_f64, 1000.0, 999.0];
+/// ml::softmax_inplace(&mut logits, 1.0);
+/// assert_eq!(logits[0], logits[1]);
+/// assert!((logits[2] - 1.0 / (1.0 + 2.0 * 1.0_f64.exp())).abs() < 1e-16);
+///
+/// let mut sharp = [1.0_f32, 2.0];
+/// ml::softmax_inplace(&mut sharp, 0.001);
+/// assert_eq!(sharp, [0.0, 1.0]);
+///
+/// let mut infinite = [1.0_f32, f32::INFINITY, 2.0, f32::INFINITY];
+/// ml::softmax_inplace(&mut infinite, 1.0);
+/// assert_eq!(infinite, [0.0, 0.5, 0.0, 0.5]);
+/// ```
+pub fn softmax_inplace<T: Float>(values: &mut [T], temperature: T) {
+    let sum = shift_and_exp(values, temperature);
+    for x in values.iter_mut() {
+        *x = *x / sum;
+    }
+}
+
+/// Replaces `values` by `log_softmax(values / temperature)` in place, the
+/// logarithm of `softmax_inplace` computed without taking the logarithm of
+/// underflowed probabilities. Infinite logits get the same limit as there.
+///
+/// ```
+/// use float::ml;
+///
+/// let mut logits = [0.0_f64, -2000.0];
+/// ml::log_softmax_inplace(&mut logits, 2.0);
+/// assert_eq!(logits, [0.0, -1000.0]);
+///
+/// let mut infinite = [f64::NEG_INFINITY; 4];
+/// ml::log_softmax_inplace(&mut infinite, 1.0);
+/// assert_eq!(infinite, [-4.0_f64.ln(); 4]);
+/// ```
+pub fn log_softmax_inplace<T: Float>(values: &mut [T], temperature: T) {
+    let max = scale_and_max(values, temperature);
+
+    let mut sum = T::from_f64(0.0);
+    for &x in values.iter() {
+        sum = sum + exp_below(x, max);
+    }
+
+    if max.is_infinite() {
+        let log_sum = sum.ln();
+        for x in values.iter_mut() {
+            *x = exp_below(*x, max).ln() - log_sum;
+        }
+        return;
+    }
+
+    let offset = max + sum.ln();
+    for x in values.iter_mut() {
+        *x = *x - offset;
+    }
+}
+
+
+/// The parameters of a linear quantization, `x = scale * (q - zero_point)`
+/// for the integer codes `q` in `[min_code, max_code]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuantParams<T> {
+    pub scale: T,
+    pub zero_point: i32,
+    pub min_code: i32,
+    pub max_code: i32,
+}
+
+impl<T: Float> QuantParams<T> {
+    /// The code of `x`, rounded to nearest with ties to even and saturated to
+    /// the code range. NaN gives the zero point.
+    #[inline]
+    pub fn quantize(&self, x: T) -> i32 {
+        let code = (x / self.scale).round_ties_even() + T::from_f64(self.zero_point as f64);
+        if code.is_nan() {
+            self.zero_point
+        } else {
+            code.clamp(&T::from_f64(self.min_code as f64), &T::from_f64(self.max_code as f64)).to_f64() as i32
+        }
+    }
+    /// The value the code stands for.
+    #[inline]
+    pub fn dequantize(&self, code: i32) -> T {
+        self.scale * T::from_f64(code as f64 - self.zero_point as f64)
+    }
+}
+
+/// Affine quantization parameters for values in `[min, max]` with unsigned
+/// `bits`-bit codes, `0` to `2^bits - 1`, as TensorFlow Lite and gemmlowp
+/// choose them.
+///
+/// The range is widened to contain zero, so zero is exactly representable,
+/// which padding and ReLU rely on. The zero point is `-min / scale` rounded
+/// to nearest with ties to even, the choice of gemmlowp for codes from zero,
+/// which moves `max` rather than `min` by the rounding. A range of only zero
+/// gets a scale of one, and a range too narrow for a normal scale gets the
+/// smallest normal one, so `quantize` never divides by a subnormal. Ranges
+/// wider than the largest finite value still get a finite scale. Panics
+/// unless `min <= max` are finite and `bits` is in `1..=31`.
+///
+/// ```
+/// use float::ml;
+///
+/// let params = ml::compute_affine_quant_params(-1.0_f32, 1.0, 8);
+/// assert_eq!((params.scale, params.zero_point), (2.0 / 255.0, 128));
+/// assert_eq!((params.quantize(0.0), params.quantize(1.0), params.quantize(-2.0)), (128, 255, 0));
+/// assert_eq!(params.dequantize(128), 0.0);
+///
+/// // ReLU6 outputs, and a range widened to contain zero
+/// let relu6 = ml::compute_affine_quant_params(0.0_f64, 6.0, 8);
+/// assert_eq!((relu6.zero_point, relu6.quantize(6.0), relu6.quantize(7.0)), (0, 255, 255));
+/// assert_eq!(ml::compute_affine_quant_params(2.0_f64, 5.0, 8), ml::compute_affine_quant_params(0.0, 5.0, 8));
+///
+/// let wide = ml::compute_affine_quant_params(-f32::MAX, f32::MAX, 8);
+/// assert_eq!((wide.scale, wide.zero_point), (2.0 * (f32::MAX / 255.0), 128));
+/// assert_eq!((wide.quantize(-f32::MAX), wide.quantize(f32::MAX), wide.dequantize(128)), (0, 255, 0.0));
+///
+/// let degenerate = ml::compute_affine_quant_params(0.0_f32, 0.0, 4);
+/// assert_eq!((degenerate.scale, degenerate.zero_point, degenerate.max_code), (1.0, 0, 15));
+/// ```
+pub fn compute_affine_quant_params<T: Float>(min: T, max: T, bits: u32) -> QuantParams<T> {
+    assert!((1..=31).contains(&bits), "affine quantization needs 1 to 31 bits");
+    check_range(min, max);
+
+    let zero = T::from_f64(0.0);
+    let min = if min < zero { min } else { zero };
+    let max = if max > zero { max } else { zero };
+    let max_code = ((1_u64 << bits) - 1) as i32;
+    let codes = T::from_f64(max_code as f64);
+
+    if min == max {
+        return QuantParams { scale: T::from_f64(1.0), zero_point: 0, min_code: 0, max_code: max_code };
+    }
+    // divided first, since max - min can overflow
+    let scale = normal_scale(max / codes - min / codes);
+
+    // from the range rather than the rounded scale, whose error would
+    // otherwise decide the ties, halved exactly if its width overflows
+    let width = max - min;
+    let fraction = if width.is_finite() {
+        -min / width
+    } else {
+        let half = T::from_f64(0.5);
+        (-min * half) / (max * half - min * half)
+    };
+    let zero_point = (fraction * codes).clamp(&zero, &codes).round_ties_even().to_f64() as i32;
+
+    QuantParams { scale: scale, zero_point: zero_point, min_code: 0, max_code: max_code }
+}
+
+/// Symmetric quantization parameters for values in `[min, max]` with signed
+/// `bits`-bit codes: the zero point is zero and the codes run over the
+/// restricted range `-(2^(bits - 1) - 1)` to `2^(bits - 1) - 1`, so negation
+/// is exact, with the scale set by the larger magnitude. Degenerate and too
+/// narrow ranges are handled as by `compute_affine_quant_params`.
+/// Panics unless `min <= max` are finite and `bits` is in `2..=32`.
+///
+/// ```
+/// use float::ml;
+///
+/// let params = ml::compute_symmetric_quant_params(-0.5_f32, 2.0, 8);
+/// assert_eq!((params.scale, params.zero_point, params.min_code, params.max_code), (2.0 / 127.0, 0, -127, 127));
+/// assert_eq!((params.quantize(2.0), params.quantize(-2.0), params.quantize(-3.0)), (127, -127, -127));
+///
+/// assert_eq!(ml::compute_symmetric_quant_params(0.0_f64, 0.0, 8).scale, 1.0);
+/// assert_eq!(ml::compute_symmetric_quant_params(0.0_f32, 1e-40, 8).scale, 1.1754944e-38);
+/// ```
+pub fn compute_symmetric_quant_params<T: Float>(min: T, max: T, bits: u32) -> QuantParams<T> {
+    assert!((2..=32).contains(&bits), "symmetric quantization needs 2 to 32 bits");
+    check_range(min, max);
+
+    let max_code = ((1_u64 << (bits - 1)) - 1) as i32;
+    let magnitude = if min.abs() > max.abs() { min.abs() } else { max.abs() };
+
+    let scale = if magnitude == T::from_f64(0.0) {
+        T::from_f64(1.0)
+    } else {
+        normal_scale(magnitude / T::from_f64(max_code as f64))
+    };
+    QuantParams { scale: scale, zero_point: 0, min_code: -max_code, max_code: max_code }
+}
+
+/// `compute_affine_quant_params` for each channel, from the ranges
+/// `[mins[i], maxs[i]]` into `params[i]`. Panics unless the slices have the
+/// same length.
+///
+/// ```
+/// use float::ml::{self, QuantParams};
+///
+/// let mut params = [QuantParams { scale: 0.0, zero_point: 0, min_code: 0, max_code: 0 }; 2];
+/// ml::compute_affine_quant_params_per_channel(&[-1.0_f32, 0.0], &[1.0, 0.0], 8, &mut params);
+/// assert_eq!(params[0], ml::compute_affine_quant_params(-1.0, 1.0, 8));
+/// assert_eq!(params[1].scale, 1.0);
+/// ```
+pub fn compute_affine_quant_params_per_channel<T: Float>(mins: &[T], maxs: &[T], bits: u32, params: &mut [QuantParams<T>]) {
+    assert!(mins.len() == maxs.len() && maxs.len() == params.len(), "per channel ranges and parameters must have the same length");
+    for ((p, &min), &max) in params.iter_mut().zip(mins.iter()).zip(maxs.iter()) {
+        *p = compute_affine_quant_params(min, max, bits);
+    }
+}
+
+/// `compute_symmetric_quant_params` for each channel, from the ranges
+/// `[mins[i], maxs[i]]` into `params[i]`. Panics unless the slices have the
+/// same length.
+///
+/// ```
+/// use float::ml::{self, QuantParams};
+///
+/// let mut params = [QuantParams { scale: 0.0, zero_point: 0, min_code: 0, max_code: 0 }; 3];
+/// ml::compute_symmetric_quant_params_per_channel(&[-0.25_f64, -4.0, 0.0], &[0.5, 1.0, 0.0], 4, &mut params);
+/// assert_eq!([params[0].scale, params[1].scale, params[2].scale], [0.5 / 7.0, 4.0 / 7.0, 1.0]);
+/// ```
+pub fn compute_symmetric_quant_params_per_channel<T: Float>(mins: &[T], maxs: &[T], bits: u32, params: &mut [QuantParams<T>]) {
+    assert!(mins.len() == maxs.len() && maxs.len() == params.len(), "per channel ranges and parameters must have the same length");
+    for ((p, &min), &max) in params.iter_mut().zip(mins.iter()).zip(maxs.iter()) {
+        *p = compute_symmetric_quant_params(min, max, bits);
+    }
+}
+
+
+/// Divides by the temperature and returns the maximum.
+#[inline]
+fn scale_and_max<T: Float>(values: &mut [T], temperature: T) -> T {
+    assert!(temperature > T::from_f64(0.0), "softmax temperature must be positive");
+
+    let mut max = T::neg_infinity();
+    for x in values.iter_mut() {
+        *x = *x / temperature;
+        if *x > max {
+            max = *x;
+        }
+    }
+    max
+}
+
+/// Replaces `values` by `exp(values / temperature - max)` and returns their sum.
+#[inline]
+fn shift_and_exp<T: Float>(values: &mut [T], temperature: T) -> T {
+    let max = scale_and_max(values, temperature);
+
+    let mut sum = T::from_f64(0.0);
+    for x in values.iter_mut() {
+        *x = exp_below(*x, max);
+        sum = sum + *x;
+    }
+    sum
+}
+
+/// `exp(x - max)`, or its limit for an infinite `max`: one at the maximum
+/// and zero below it, where `x - max` would give NaN.
+#[inline]
+fn exp_below<T: Float>(x: T, max: T) -> T {
+    if !max.is_infinite() {
+        (x - max).exp()
+    } else if x == max {
+        T::from_f64(1.0)
+    } else if x.is_nan() {
+        x
+    } else {
+        T::from_f64(0.0)
+    }
+}
+
+#[inline]
+fn check_range<T: Float>(min: T, max: T) {
+    assert!(min.is_finite() && max.is_finite() && min <= max, "quantization range must be finite with min <= max");
+}
+
+/// `scale`, or the smallest normal number if it is below it.
+#[inline]
+fn normal_scale<T: Float>(scale: T) -> T {
+    let min_normal = T::from_f64(2.0).powi(T::min_exp() - 1);
+    if scale < min_normal { min_normal } else { scale }
+}
@@ -0,0 +1,52 @@
+#[cfg(feature = "nan-debug")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+
+#[cfg(feature = "nan-debug")]
+static TOLERANT_DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+
+/// Runs `f` with the `nan-debug` checks disabled, for code that produces or
+/// consumes NaNs on purpose. Without the feature this simply calls `f`.
+///
+/// The checks are disabled process wide while `f` runs, so NaN-tolerant code
+/// on one thread also silences them on the others.
+///
+/// ```
+/// use float::{Float, nan_tolerant};
+/// let x = nan_tolerant(|| (-1.0_f64).ln());
+/// assert!(x.is_nan());
+/// ```
+#[inline]
+pub fn nan_tolerant<F, R>(f: F) -> R
+    where F: FnOnce() -> R,
+{
+    let _guard = TolerantGuard::new();
+    f()
+}
+
+#[cfg(feature = "nan-debug")]
+#[inline(always)]
+pub(crate) fn is_tolerant() -> bool {
+    TOLERANT_DEPTH.load(Ordering::Relaxed) != 0
+}
+
+
+struct TolerantGuard;
+
+impl TolerantGuard {
+    #[inline(always)]
+    fn new() -> Self {
+        #[cfg(feature = "nan-debug")]
+        TOLERANT_DEPTH.fetch_add(1, Ordering::Relaxed);
+        TolerantGuard
+    }
+}
+
+impl Drop for TolerantGuard {
+    #[inline(always)]
+    fn drop(&mut self) {
+        #[cfg(feature = "nan-debug")]
+        TOLERANT_DEPTH.fetch_sub(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,170 @@
+use core::ops::{Add, Sub, Mul, Div, Rem, Neg};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::mem;
+
+use float::Float;
+
+
+static SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+static HOOK: AtomicUsize = AtomicUsize::new(0);
+
+
+/// Where a NaN was first produced: a process wide sequence number, increasing
+/// with every NaN a `NanTrace` observes, and the label of the operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NanOrigin {
+    pub sequence: usize,
+    pub label: &'static str,
+}
+
+
+/// Installs `hook` to be called every time a `NanTrace` records a new origin,
+/// e.g. to log it over a serial port or stash it in a crash dump.
+#[inline]
+pub fn set_nan_hook(hook: fn(NanOrigin)) {
+    HOOK.store(hook as usize, Ordering::SeqCst);
+}
+
+/// Removes the hook installed by `set_nan_hook`.
+#[inline]
+pub fn clear_nan_hook() {
+    HOOK.store(0, Ordering::SeqCst);
+}
+
+
+/// A value that remembers where the first NaN in its computation came from.
+///
+/// Arithmetic on `NanTrace` values carries the earliest origin forward, and a
+/// NaN produced from non-NaN operands records a fresh `NanOrigin`, so the
+/// culprit can be identified after the fact in release builds.
+///
+/// ```
+/// use float::NanTrace;
+///
+/// let x = NanTrace::new(4.0_f64);
+/// let y = x.map("ln", |v| (-v).ln());
+/// let z = (y + x) * NanTrace::new(2.0);
+///
+/// assert!(z.value().is_nan());
+/// assert_eq!(z.origin().unwrap().label, "ln");
+/// assert_eq!(z.origin(), y.origin());
+/// assert_eq!(x.origin(), None);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct NanTrace<T> {
+    value: T,
+    origin: Option<NanOrigin>,
+}
+
+impl<T: Float> NanTrace<T> {
+    /// Wraps `value`, recording an `"input"` origin if it already is a NaN.
+    #[inline]
+    pub fn new(value: T) -> Self {
+        NanTrace::traced(value, None, "input")
+    }
+
+    #[inline(always)]
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// Returns where the first NaN leading to this value was produced.
+    #[inline(always)]
+    pub fn origin(&self) -> Option<NanOrigin> {
+        self.origin
+    }
+
+    /// Applies a unary operation, e.g. `x.map("exp", |v| v.exp())`.
+    #[inline]
+    pub fn map<F>(self, label: &'static str, f: F) -> Self
+        where F: FnOnce(T) -> T,
+    {
+        NanTrace::traced(f(self.value), self.origin, label)
+    }
+
+    /// Applies a binary operation, e.g. `x.map2(y, "hypot", |a, b| a.hypot(&b))`.
+    #[inline]
+    pub fn map2<F>(self, other: Self, label: &'static str, f: F) -> Self
+        where F: FnOnce(T, T) -> T,
+    {
+        NanTrace::traced(f(self.value, other.value), earliest(self.origin, other.origin), label)
+    }
+
+    #[inline]
+    fn traced(value: T, origin: Option<NanOrigin>, label: &'static str) -> Self {
+        let origin = match origin {
+            Some(origin) => Some(origin),
+            None if value.is_nan() => Some(record(label)),
+            None => None,
+        };
+
+        NanTrace {
+            value: value,
+            origin: origin,
+        }
+    }
+}
+
+impl<T: Float> From<T> for NanTrace<T> {
+    #[inline(always)]
+    fn from(value: T) -> Self {
+        NanTrace::new(value)
+    }
+}
+
+
+macro_rules! impl_nan_trace_op {
+    ($Trait:ident, $method:ident, $label:expr) => (
+        impl<T: Float> $Trait for NanTrace<T> {
+            type Output = Self;
+
+            #[inline]
+            fn $method(self, other: Self) -> Self {
+                self.map2(other, $label, |a, b| $Trait::$method(a, b))
+            }
+        }
+    )
+}
+
+impl_nan_trace_op!(Add, add, "add");
+impl_nan_trace_op!(Sub, sub, "sub");
+impl_nan_trace_op!(Mul, mul, "mul");
+impl_nan_trace_op!(Div, div, "div");
+impl_nan_trace_op!(Rem, rem, "rem");
+
+impl<T: Float> Neg for NanTrace<T> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        NanTrace {
+            value: -self.value,
+            origin: self.origin,
+        }
+    }
+}
+
+
+#[inline]
+fn earliest(a: Option<NanOrigin>, b: Option<NanOrigin>) -> Option<NanOrigin> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if a.sequence <= b.sequence { a } else { b }),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+#[inline(never)]
+fn record(label: &'static str) -> NanOrigin {
+    let origin = NanOrigin {
+        sequence: SEQUENCE.fetch_add(1, Ordering::SeqCst),
+        label: label,
+    };
+    let hook = HOOK.load(Ordering::SeqCst);
+
+    if hook != 0 {
+        let hook: fn(NanOrigin) = unsafe { mem::transmute(hook) };
+        hook(origin);
+    }
+    origin
+}
@@ -0,0 +1,156 @@
+use expansion::{two_sum, two_product, TAU};
+use float::Float;
+
+
+/// A numerically controlled oscillator: a phase accumulator wrapping in
+/// `[0, 1)` turns.
+///
+/// Both the per-sample increment and the running phase carry their rounding
+/// error in a second term, so the phase follows `n * frequency / rate` to the
+/// precision of `T` however long the oscillator runs, where a plain `+=`
+/// drifts by a fraction of an ulp per sample. As an endless `Iterator` it
+/// yields the phase of each sample in turns.
+///
+/// ```
+/// use float::Nco;
+///
+/// let mut nco = Nco::new(440.0_f32, 48000.0);
+/// let mut naive = 0.0_f32;
+/// let increment = 440.0_f32 / 48000.0;
+/// for _ in 0..48000 * 60 {
+///     nco.next();
+///     naive += increment;
+///     naive -= naive.floor();
+/// }
+///
+/// // after a minute, 26400 whole cycles
+/// assert!(nco.phase() < 1e-6 || nco.phase() > 1.0 - 1e-6);
+/// assert!(naive > 1e-3 && naive < 1.0 - 1e-3);
+///
+/// let quarter: Vec<f64> = Nco::new(1.0, 4.0).take(5).collect();
+/// assert_eq!(quarter, [0.0, 0.25, 0.5, 0.75, 0.0]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Nco<T> {
+    phase: T,
+    phase_error: T,
+    increment: T,
+    increment_error: T,
+}
+
+impl<T: Float> Nco<T> {
+    /// Starts at phase zero, advancing `frequency / sample_rate` turns per
+    /// sample.
+    #[inline]
+    pub fn new(frequency: T, sample_rate: T) -> Self {
+        let mut nco = Nco {
+            phase: T::from_f64(0.0),
+            phase_error: T::from_f64(0.0),
+            increment: T::from_f64(0.0),
+            increment_error: T::from_f64(0.0),
+        };
+        nco.set_frequency(frequency, sample_rate);
+        nco
+    }
+
+    /// Changes the frequency without a phase discontinuity. Negative
+    /// frequencies run the phase backwards.
+    #[inline]
+    pub fn set_frequency(&mut self, frequency: T, sample_rate: T) {
+        let increment = frequency / sample_rate;
+
+        // frequency - increment * sample_rate is exact, divided it gives the
+        // part of the increment lost to rounding
+        let (product, product_error) = two_product(increment, sample_rate);
+        let residual = (frequency - product) - product_error;
+
+        self.increment = increment;
+        self.increment_error = residual / sample_rate;
+    }
+
+    /// Sets the increment in turns per sample directly.
+    #[inline]
+    pub fn set_increment(&mut self, increment: T) {
+        self.increment = increment;
+        self.increment_error = T::from_f64(0.0);
+    }
+
+    #[inline(always)]
+    pub fn increment(&self) -> T {
+        self.increment
+    }
+
+    /// Jumps to `phase` turns, which is wrapped into `[0, 1)`.
+    #[inline]
+    pub fn set_phase(&mut self, phase: T) {
+        self.phase = phase - phase.floor();
+        self.phase_error = T::from_f64(0.0);
+        self.wrap();
+    }
+
+    /// The current phase in turns, in `[0, 1)`.
+    #[inline(always)]
+    pub fn phase(&self) -> T {
+        self.phase
+    }
+
+    /// The current phase in radians, in `[0, 2 pi)` up to rounding.
+    ///
+    /// ```
+    /// use float::Nco;
+    /// let mut nco = Nco::new(1.0_f64, 4.0);
+    /// nco.next();
+    /// assert_eq!(nco.phase_radians(), std::f64::consts::FRAC_PI_2);
+    /// ```
+    #[inline]
+    pub fn phase_radians(&self) -> T {
+        let phase = self.phase + self.phase_error;
+        phase * T::from_f64(TAU.0) + phase * T::from_f64(TAU.1)
+    }
+
+    #[inline]
+    fn wrap(&mut self) {
+        let one = T::from_f64(1.0);
+        let zero = T::from_f64(0.0);
+
+        if self.phase >= one || self.phase < zero {
+            let turns = self.phase.floor();
+            let (phase, error) = two_sum(self.phase - turns, self.phase_error);
+            self.phase = phase;
+            self.phase_error = error;
+        }
+        // a phase just below zero can round up to one
+        if self.phase >= one {
+            self.phase = zero;
+            self.phase_error = zero;
+        } else if self.phase < zero {
+            let (phase, error) = two_sum(self.phase + one, self.phase_error);
+            if phase >= one {
+                self.phase = zero;
+                self.phase_error = zero;
+            } else {
+                self.phase = phase;
+                self.phase_error = error;
+            }
+        }
+    }
+}
+
+impl<T: Float> Iterator for Nco<T> {
+    type Item = T;
+
+    /// Returns the current phase in turns and advances by one sample. The
+    /// oscillator never runs out.
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        let phase = self.phase;
+
+        let (sum, error) = two_sum(self.phase, self.increment);
+        let (sum, error) = two_sum(sum, error + self.phase_error + self.increment_error);
+        self.phase = sum;
+        self.phase_error = error;
+        self.wrap();
+
+        Some(phase)
+    }
+}
@@ -0,0 +1,212 @@
+//! Gradient (Perlin) noise in one to three dimensions and fractal Brownian
+//! motion built from it.
+//!
+//! Only `floor` and the four basic operations are used, all of which are exact
+//! or correctly rounded under IEEE 754, so a given seed produces bit-identical
+//! noise on every platform regardless of the math backend.
+
+use float::Float;
+
+
+/// Seeded permutation table driving the noise functions.
+///
+/// ```
+/// use float::noise::Perlin;
+///
+/// let perlin = Perlin::new(42);
+/// let a = perlin.noise2(1.25_f64, 3.5);
+/// assert_eq!(a, Perlin::new(42).noise2(1.25, 3.5));
+/// assert!(a >= -1.0 && a <= 1.0);
+///
+/// // noise vanishes on the integer lattice
+/// assert_eq!(perlin.noise3(2.0_f32, -1.0, 5.0), 0.0);
+/// ```
+#[derive(Clone, Copy)]
+pub struct Perlin {
+    perm: [u8; 512],
+}
+
+impl Perlin {
+    /// Builds the permutation from `seed` with a Fisher-Yates shuffle driven
+    /// by xorshift32, so tables are reproducible without any allocation.
+    pub fn new(seed: u32) -> Self {
+        let mut perm = [0_u8; 512];
+        let mut state = if seed == 0 { 0x9e3779b9 } else { seed };
+
+        for (i, entry) in perm.iter_mut().take(256).enumerate() {
+            *entry = i as u8;
+        }
+        for i in (1..256).rev() {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+
+            let j = (state % (i as u32 + 1)) as usize;
+            perm.swap(i, j);
+        }
+        for i in 0..256 {
+            perm[256 + i] = perm[i];
+        }
+
+        Perlin {
+            perm: perm,
+        }
+    }
+
+    /// One dimensional gradient noise, roughly in `[-1, 1]`.
+    pub fn noise1<T: Float>(&self, x: T) -> T {
+        let (xi, xf) = lattice(x);
+        let u = fade(xf);
+        let one = T::from_f64(1.0);
+
+        let a = grad1(self.perm[xi], xf);
+        let b = grad1(self.perm[xi + 1], xf - one);
+
+        lerp(u, a, b)
+    }
+
+    /// Two dimensional gradient noise in `[-1, 1]`.
+    pub fn noise2<T: Float>(&self, x: T, y: T) -> T {
+        let (xi, xf) = lattice(x);
+        let (yi, yf) = lattice(y);
+        let (u, v) = (fade(xf), fade(yf));
+        let one = T::from_f64(1.0);
+        let p = &self.perm;
+
+        let a = p[xi] as usize + yi;
+        let b = p[xi + 1] as usize + yi;
+
+        let x0 = lerp(u, grad2(p[a], xf, yf), grad2(p[b], xf - one, yf));
+        let x1 = lerp(u, grad2(p[a + 1], xf, yf - one), grad2(p[b + 1], xf - one, yf - one));
+
+        lerp(v, x0, x1)
+    }
+
+    /// Three dimensional gradient noise (Perlin's improved noise), roughly in `[-1, 1]`.
+    pub fn noise3<T: Float>(&self, x: T, y: T, z: T) -> T {
+        let (xi, xf) = lattice(x);
+        let (yi, yf) = lattice(y);
+        let (zi, zf) = lattice(z);
+        let (u, v, w) = (fade(xf), fade(yf), fade(zf));
+        let one = T::from_f64(1.0);
+        let p = &self.perm;
+
+        let a = p[xi] as usize + yi;
+        let aa = p[a] as usize + zi;
+        let ab = p[a + 1] as usize + zi;
+        let b = p[xi + 1] as usize + yi;
+        let ba = p[b] as usize + zi;
+        let bb = p[b + 1] as usize + zi;
+
+        let y0 = lerp(v,
+            lerp(u, grad3(p[aa], xf, yf, zf), grad3(p[ba], xf - one, yf, zf)),
+            lerp(u, grad3(p[ab], xf, yf - one, zf), grad3(p[bb], xf - one, yf - one, zf)));
+        let y1 = lerp(v,
+            lerp(u, grad3(p[aa + 1], xf, yf, zf - one), grad3(p[ba + 1], xf - one, yf, zf - one)),
+            lerp(u, grad3(p[ab + 1], xf, yf - one, zf - one), grad3(p[bb + 1], xf - one, yf - one, zf - one)));
+
+        lerp(w, y0, y1)
+    }
+
+    /// Sums `octaves` layers of `noise1`, each `lacunarity` times the frequency
+    /// and `gain` times the amplitude of the previous one, normalized by the
+    /// total amplitude.
+    ///
+    /// ```
+    /// use float::noise::Perlin;
+    /// let value = Perlin::new(7).fbm1(0.3_f64, 5, 2.0, 0.5);
+    /// assert!(value >= -1.0 && value <= 1.0);
+    /// ```
+    pub fn fbm1<T: Float>(&self, x: T, octaves: u32, lacunarity: T, gain: T) -> T {
+        fbm(octaves, lacunarity, gain, |frequency| self.noise1(x * frequency))
+    }
+
+    /// Two dimensional version of `fbm1`.
+    pub fn fbm2<T: Float>(&self, x: T, y: T, octaves: u32, lacunarity: T, gain: T) -> T {
+        fbm(octaves, lacunarity, gain, |frequency| self.noise2(x * frequency, y * frequency))
+    }
+
+    /// Three dimensional version of `fbm1`.
+    pub fn fbm3<T: Float>(&self, x: T, y: T, z: T, octaves: u32, lacunarity: T, gain: T) -> T {
+        fbm(octaves, lacunarity, gain, |frequency| {
+            self.noise3(x * frequency, y * frequency, z * frequency)
+        })
+    }
+}
+
+impl Default for Perlin {
+    #[inline(always)]
+    fn default() -> Self {
+        Perlin::new(0)
+    }
+}
+
+
+#[inline]
+fn fbm<T: Float, F>(octaves: u32, lacunarity: T, gain: T, noise: F) -> T
+    where F: Fn(T) -> T,
+{
+    let zero = T::from_f64(0.0);
+    let mut frequency = T::from_f64(1.0);
+    let mut amplitude = T::from_f64(1.0);
+    let mut sum = zero;
+    let mut total = zero;
+
+    for _ in 0..octaves {
+        sum = sum + amplitude * noise(frequency);
+        total = total + amplitude;
+        frequency = frequency * lacunarity;
+        amplitude = amplitude * gain;
+    }
+
+    if total == zero { zero } else { sum / total }
+}
+
+/// Splits `x` into its lattice cell (wrapped to the table size) and the
+/// offset inside the cell.
+#[inline]
+fn lattice<T: Float>(x: T) -> (usize, T) {
+    let floor = x.floor();
+    ((floor.to_f64() as i64 & 255) as usize, x - floor)
+}
+
+#[inline(always)]
+fn fade<T: Float>(t: T) -> T {
+    t * t * t * (t * (t * T::from_f64(6.0) - T::from_f64(15.0)) + T::from_f64(10.0))
+}
+
+#[inline(always)]
+fn lerp<T: Float>(t: T, a: T, b: T) -> T {
+    a + t * (b - a)
+}
+
+#[inline]
+fn grad1<T: Float>(hash: u8, x: T) -> T {
+    // slopes between 1/4 and 2 keep the result inside [-1, 1]
+    let magnitude = T::from_f64((1 + (hash & 7)) as f64 * 0.25);
+    let g = if hash & 8 == 0 { magnitude } else { -magnitude };
+    g * x
+}
+
+#[inline]
+fn grad2<T: Float>(hash: u8, x: T, y: T) -> T {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+#[inline]
+fn grad3<T: Float>(hash: u8, x: T, y: T, z: T) -> T {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 { y } else if h == 12 || h == 14 { x } else { z };
+
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
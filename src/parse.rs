@@ -0,0 +1,119 @@
+//! Tolerant parsing of numeric text from data files.
+//!
+//! The accepted syntax is that of `str::parse` with surrounding whitespace
+//! allowed, plus the spellings enabled in a `ParseConfig`. The text is only
+//! rewritten into that syntax, so the result is correctly rounded.
+
+use core::fmt;
+use core::str::{self, FromStr};
+
+use collections::vec::Vec;
+
+use float::Float;
+
+
+/// The non-standard spellings `parse_float` accepts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ParseConfig {
+    /// Fortran exponents such as `1.0D+03`, with `D` or `d` for `E`.
+    pub fortran_exponent: bool,
+    /// A comma as the decimal separator, as in `3,25`. A point is then
+    /// rejected, since it is likely a thousands separator.
+    pub decimal_comma: bool,
+    /// Underscores between digits, as in `1_000_000`.
+    pub underscores: bool,
+}
+
+impl ParseConfig {
+    /// Everything except decimal commas, which would misread comma
+    /// separated fields.
+    #[inline]
+    pub fn tolerant() -> Self {
+        ParseConfig {
+            fortran_exponent: true,
+            decimal_comma: false,
+            underscores: true,
+        }
+    }
+}
+
+impl Default for ParseConfig {
+    #[inline]
+    fn default() -> Self {
+        ParseConfig {
+            fortran_exponent: false,
+            decimal_comma: false,
+            underscores: false,
+        }
+    }
+}
+
+
+/// Reasons `parse_float` rejects a text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ParseError {
+    /// Nothing but whitespace.
+    Empty,
+    /// Not a number, or a spelling the configuration does not allow.
+    Syntax,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::Empty => write!(f, "empty numeric field"),
+            ParseError::Syntax => write!(f, "malformed number"),
+        }
+    }
+}
+
+
+/// Parses `text` as `T`, accepting the spellings enabled in `config`.
+///
+/// ```
+/// use float::parse::{self, ParseConfig, ParseError};
+///
+/// let tolerant = ParseConfig::tolerant();
+/// assert_eq!(parse::parse_float::<f64>("1.0D+03", &tolerant), Ok(1000.0));
+/// assert_eq!(parse::parse_float::<f64>(" -2.5d-1 ", &tolerant), Ok(-0.25));
+/// assert_eq!(parse::parse_float::<f32>("1_000_000.5", &tolerant), Ok(1000000.5));
+/// assert_eq!(parse::parse_float::<f64>("1__0", &tolerant), Err(ParseError::Syntax));
+/// assert_eq!(parse::parse_float::<f64>("_1", &tolerant), Err(ParseError::Syntax));
+/// assert_eq!(parse::parse_float::<f64>("  ", &tolerant), Err(ParseError::Empty));
+///
+/// let european = ParseConfig { decimal_comma: true, ..ParseConfig::tolerant() };
+/// assert_eq!(parse::parse_float::<f64>("3,25", &european), Ok(3.25));
+/// assert_eq!(parse::parse_float::<f64>("3.25", &european), Err(ParseError::Syntax));
+///
+/// assert_eq!(parse::parse_float::<f64>("1.0D+03", &ParseConfig::default()), Err(ParseError::Syntax));
+/// ```
+pub fn parse_float<T>(text: &str, config: &ParseConfig) -> Result<T, ParseError>
+    where T: Float + FromStr,
+{
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let bytes = text.as_bytes();
+    let is_digit = |i: usize| i < bytes.len() && bytes[i] >= b'0' && bytes[i] <= b'9';
+
+    let mut normalized = Vec::with_capacity(bytes.len());
+    for (i, &c) in bytes.iter().enumerate() {
+        match c {
+            b'_' if config.underscores => {
+                if i == 0 || !is_digit(i - 1) || !is_digit(i + 1) {
+                    return Err(ParseError::Syntax);
+                }
+            },
+            b'd' | b'D' if config.fortran_exponent => normalized.push(b'e'),
+            b',' if config.decimal_comma => normalized.push(b'.'),
+            b'.' if config.decimal_comma => return Err(ParseError::Syntax),
+            _ => normalized.push(c),
+        }
+    }
+
+    // only ASCII bytes were replaced or removed, so this is still UTF-8
+    let normalized = str::from_utf8(&normalized).map_err(|_| ParseError::Syntax)?;
+    normalized.parse().map_err(|_| ParseError::Syntax)
+}
@@ -0,0 +1,401 @@
+//! Conversions between float samples in `[-1, 1)` and integer PCM.
+//!
+//! Samples are scaled by `2^(bits - 1)`, the convention of WAV and most
+//! converters, so decoding is an exact power of two division and `-1.0` maps
+//! to the most negative code. Encoding rounds to nearest with ties up after an
+//! optional dither, then applies a `ClipPolicy` to values outside the range of
+//! the format. 24-bit samples are carried in the low bits of an `i32`.
+//!
+//! The slice functions without dither are plain element-wise loops the
+//! compiler can vectorize.
+//!
+//! G.711 mu-law and A-law go through 16-bit linear PCM and follow the
+//! reference integer algorithm bit for bit.
+
+use float::Float;
+
+
+const MU_LAW_BIAS: i32 = 0x84;
+const MU_LAW_CLIP: i32 = 32635;
+const A_LAW_SEGMENT_ENDS: [i32; 8] = [0x1f, 0x3f, 0x7f, 0xff, 0x1ff, 0x3ff, 0x7ff, 0xfff];
+
+/// What to do with samples that do not fit the integer format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ClipPolicy {
+    /// Clamp to the largest or smallest code.
+    Saturate,
+    /// Keep the low bits, as two's complement hardware does. Infinities
+    /// still saturate.
+    Wrap,
+}
+
+impl Default for ClipPolicy {
+    #[inline(always)]
+    fn default() -> Self {
+        ClipPolicy::Saturate
+    }
+}
+
+
+/// Noise added before requantization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dither {
+    /// Plain rounding.
+    None,
+    /// Triangular noise of plus or minus one LSB, the difference of two uniform
+    /// variables, driven by the xorshift64* state it holds.
+    Tpdf(u64),
+}
+
+impl Dither {
+    /// Returns the next offset in LSBs and advances the state.
+    #[inline]
+    pub fn next_offset(&mut self) -> f64 {
+        match *self {
+            Dither::None => 0.0,
+            Dither::Tpdf(ref mut state) => {
+                if *state == 0 {
+                    *state = 0x9e3779b97f4a7c15;
+                }
+                *state ^= *state >> 12;
+                *state ^= *state << 25;
+                *state ^= *state >> 27;
+
+                let bits = state.wrapping_mul(0x2545f4914f6cdd1d);
+                let scale = 1.0 / (1_u64 << 32) as f64;
+                (bits >> 32) as f64 * scale - (bits & 0xffffffff) as f64 * scale
+            },
+        }
+    }
+}
+
+impl Default for Dither {
+    #[inline(always)]
+    fn default() -> Self {
+        Dither::None
+    }
+}
+
+
+/// Converts one sample to 16-bit PCM.
+///
+/// ```
+/// use float::pcm::{self, ClipPolicy};
+///
+/// assert_eq!(pcm::to_i16(0.5_f64, ClipPolicy::Saturate), 16384);
+/// assert_eq!(pcm::to_i16(-1.0_f32, ClipPolicy::Saturate), -32768);
+/// assert_eq!(pcm::to_i16(1.0_f64, ClipPolicy::Saturate), 32767);
+/// assert_eq!(pcm::to_i16(1.0_f64, ClipPolicy::Wrap), -32768);
+/// assert_eq!(pcm::to_i16(f64::NAN, ClipPolicy::Saturate), 0);
+/// ```
+#[inline]
+pub fn to_i16<T: Float>(x: T, clip: ClipPolicy) -> i16 {
+    quantize(x, 16, 0.0, clip).0 as i16
+}
+
+/// Converts one sample to 24-bit PCM held in an `i32`.
+///
+/// ```
+/// use float::pcm::{self, ClipPolicy};
+/// assert_eq!(pcm::to_i24(-0.25_f64, ClipPolicy::Saturate), -2097152);
+/// assert_eq!(pcm::to_i24(2.0_f32, ClipPolicy::Saturate), 8388607);
+/// ```
+#[inline]
+pub fn to_i24<T: Float>(x: T, clip: ClipPolicy) -> i32 {
+    quantize(x, 24, 0.0, clip).0 as i32
+}
+
+/// Converts one sample to 32-bit PCM.
+///
+/// ```
+/// use float::pcm::{self, ClipPolicy};
+/// assert_eq!(pcm::to_i32(1.0_f32, ClipPolicy::Saturate), i32::max_value());
+/// assert_eq!(pcm::to_i32(-0.5_f64, ClipPolicy::Saturate), -(1 << 30));
+/// ```
+#[inline]
+pub fn to_i32<T: Float>(x: T, clip: ClipPolicy) -> i32 {
+    quantize(x, 32, 0.0, clip).0 as i32
+}
+
+/// Converts one 16-bit PCM sample to a float in `[-1, 1)`.
+///
+/// ```
+/// use float::pcm;
+/// assert_eq!(pcm::from_i16::<f64>(-32768), -1.0);
+/// assert_eq!(pcm::from_i16::<f32>(16384), 0.5);
+/// ```
+#[inline]
+pub fn from_i16<T: Float>(sample: i16) -> T {
+    dequantize(sample as i32, 16)
+}
+
+/// Converts one 24-bit PCM sample held in an `i32` to a float in `[-1, 1)`.
+#[inline]
+pub fn from_i24<T: Float>(sample: i32) -> T {
+    dequantize(sample, 24)
+}
+
+/// Converts one 32-bit PCM sample to a float in `[-1, 1)`. `f32` rounds the
+/// low bits.
+#[inline]
+pub fn from_i32<T: Float>(sample: i32) -> T {
+    dequantize(sample, 32)
+}
+
+
+/// Encodes `input` into `output` and returns how many samples clipped.
+///
+/// ```
+/// use float::pcm::{self, ClipPolicy, Dither};
+///
+/// let input = [0.0_f64, 0.25, -1.0, 1.5];
+/// let mut output = [0_i16; 4];
+/// let clipped = pcm::encode_i16(&input, &mut output, &mut Dither::None, ClipPolicy::Saturate);
+/// assert_eq!(output, [0, 8192, -32768, 32767]);
+/// assert_eq!(clipped, 1);
+///
+/// // dither spreads a constant input over neighbouring codes
+/// let quiet = [0.3 / 32768.0_f64; 1000];
+/// let mut output = [0_i16; 1000];
+/// pcm::encode_i16(&quiet, &mut output, &mut Dither::Tpdf(1), ClipPolicy::Saturate);
+/// assert!(output.iter().all(|&s| s >= -1 && s <= 2));
+/// assert!(output.iter().any(|&s| s != 0));
+/// ```
+pub fn encode_i16<T: Float>(input: &[T], output: &mut [i16], dither: &mut Dither, clip: ClipPolicy) -> usize {
+    encode(input, output, 16, dither, clip, |s| s as i16)
+}
+
+/// Encodes `input` into 24-bit samples held in `output` and returns how
+/// many samples clipped.
+pub fn encode_i24<T: Float>(input: &[T], output: &mut [i32], dither: &mut Dither, clip: ClipPolicy) -> usize {
+    encode(input, output, 24, dither, clip, |s| s as i32)
+}
+
+/// Encodes `input` into `output` and returns how many samples clipped.
+pub fn encode_i32<T: Float>(input: &[T], output: &mut [i32], dither: &mut Dither, clip: ClipPolicy) -> usize {
+    encode(input, output, 32, dither, clip, |s| s as i32)
+}
+
+/// Decodes `input` into `output`.
+///
+/// ```
+/// use float::pcm;
+/// let mut output = [0.0_f32; 3];
+/// pcm::decode_i16(&[-16384, 0, 32767], &mut output);
+/// assert_eq!(output, [-0.5, 0.0, 32767.0 / 32768.0]);
+/// ```
+pub fn decode_i16<T: Float>(input: &[i16], output: &mut [T]) {
+    decode(input, output, 16, |&s| s as i32)
+}
+
+/// Decodes 24-bit samples held in `input` into `output`.
+pub fn decode_i24<T: Float>(input: &[i32], output: &mut [T]) {
+    decode(input, output, 24, |&s| s)
+}
+
+/// Decodes `input` into `output`.
+pub fn decode_i32<T: Float>(input: &[i32], output: &mut [T]) {
+    decode(input, output, 32, |&s| s)
+}
+
+
+
+/// Compands a sample to G.711 mu-law.
+///
+/// ```
+/// use float::pcm;
+/// assert_eq!(pcm::to_mu_law(0.0_f64), 0xff);
+/// assert_eq!(pcm::to_mu_law(1.0_f32), 0x80);
+/// assert_eq!(pcm::to_mu_law(-1.0_f64), 0x00);
+/// assert_eq!(pcm::from_mu_law::<f64>(0x80), 32124.0 / 32768.0);
+/// assert_eq!(pcm::from_mu_law::<f32>(pcm::to_mu_law(-0.25_f32)), -8316.0 / 32768.0);
+/// ```
+#[inline]
+pub fn to_mu_law<T: Float>(x: T) -> u8 {
+    linear_to_mu_law(quantize(x, 16, 0.0, ClipPolicy::Saturate).0 as i32)
+}
+
+/// Expands a G.711 mu-law code to a float in `(-1, 1)`.
+#[inline]
+pub fn from_mu_law<T: Float>(code: u8) -> T {
+    dequantize(mu_law_to_linear(code), 16)
+}
+
+/// Compands a sample to G.711 A-law.
+///
+/// ```
+/// use float::pcm;
+/// assert_eq!(pcm::to_a_law(0.0_f64), 0xd5);
+/// assert_eq!(pcm::to_a_law(1.0_f32), 0xaa);
+/// assert_eq!(pcm::to_a_law(-1.0_f64), 0x2a);
+/// assert_eq!(pcm::from_a_law::<f64>(0xd5), 8.0 / 32768.0);
+/// assert_eq!(pcm::from_a_law::<f64>(0xaa), 32256.0 / 32768.0);
+/// ```
+#[inline]
+pub fn to_a_law<T: Float>(x: T) -> u8 {
+    linear_to_a_law(quantize(x, 16, 0.0, ClipPolicy::Saturate).0 as i32)
+}
+
+/// Expands a G.711 A-law code to a float in `(-1, 1)`.
+#[inline]
+pub fn from_a_law<T: Float>(code: u8) -> T {
+    dequantize(a_law_to_linear(code), 16)
+}
+
+/// Encodes `input` into mu-law codes in `output`.
+///
+/// ```
+/// use float::pcm;
+///
+/// let input = [0.5_f64, -0.125, 0.001];
+/// let mut codes = [0_u8; 3];
+/// let mut output = [0.0_f64; 3];
+/// pcm::encode_mu_law(&input, &mut codes);
+/// pcm::decode_mu_law(&codes, &mut output);
+/// for (x, y) in input.iter().zip(output.iter()) {
+///     assert!((x - y).abs() <= x.abs() / 16.0 + 1.0 / 4096.0);
+/// }
+/// ```
+pub fn encode_mu_law<T: Float>(input: &[T], output: &mut [u8]) {
+    assert!(output.len() >= input.len(), "PCM output buffer smaller than the input");
+    for (out, &x) in output.iter_mut().zip(input.iter()) {
+        *out = to_mu_law(x);
+    }
+}
+
+/// Decodes mu-law codes in `input` into `output`.
+pub fn decode_mu_law<T: Float>(input: &[u8], output: &mut [T]) {
+    decode(input, output, 16, |&code| mu_law_to_linear(code))
+}
+
+/// Encodes `input` into A-law codes in `output`.
+pub fn encode_a_law<T: Float>(input: &[T], output: &mut [u8]) {
+    assert!(output.len() >= input.len(), "PCM output buffer smaller than the input");
+    for (out, &x) in output.iter_mut().zip(input.iter()) {
+        *out = to_a_law(x);
+    }
+}
+
+/// Decodes A-law codes in `input` into `output`.
+pub fn decode_a_law<T: Float>(input: &[u8], output: &mut [T]) {
+    decode(input, output, 16, |&code| a_law_to_linear(code))
+}
+
+
+#[inline]
+fn encode<T, S, F>(input: &[T], output: &mut [S], bits: u32, dither: &mut Dither, clip: ClipPolicy, cast: F) -> usize
+    where T: Float,
+          F: Fn(i64) -> S,
+{
+    assert!(output.len() >= input.len(), "PCM output buffer smaller than the input");
+
+    let mut clipped = 0;
+    for (out, &x) in output.iter_mut().zip(input.iter()) {
+        let (sample, clip_flag) = quantize(x, bits, dither.next_offset(), clip);
+        *out = cast(sample);
+        clipped += clip_flag as usize;
+    }
+    clipped
+}
+
+#[inline]
+fn decode<T, S, F>(input: &[S], output: &mut [T], bits: u32, cast: F)
+    where T: Float,
+          F: Fn(&S) -> i32,
+{
+    assert!(output.len() >= input.len(), "PCM output buffer smaller than the input");
+
+    for (out, sample) in output.iter_mut().zip(input.iter()) {
+        *out = dequantize(cast(sample), bits);
+    }
+}
+
+/// Scales, dithers and rounds `x`, returning the code and whether it clipped.
+#[inline]
+fn quantize<T: Float>(x: T, bits: u32, offset: f64, clip: ClipPolicy) -> (i64, bool) {
+    let max = (1_i64 << (bits - 1)) - 1;
+    let min = -(1_i64 << (bits - 1));
+
+    if x.is_nan() {
+        return (0, false);
+    }
+
+    let one = T::from_f64(1.0);
+    let half = T::from_f64(0.5);
+    let scaled = x * T::from_f64(2.0).powi(bits as i32 - 1) + T::from_f64(offset);
+
+    // the fraction of a float is exact, unlike adding one half before floor
+    let floor = scaled.floor();
+    let rounded = if scaled - floor >= half { floor + one } else { floor };
+
+    // compare in f64, where both limits are exact
+    let rounded = rounded.to_f64();
+
+    if !rounded.is_finite() || clip == ClipPolicy::Saturate {
+        if rounded > max as f64 {
+            return (max, true);
+        } else if rounded < min as f64 {
+            return (min, true);
+        }
+        (rounded as i64, false)
+    } else {
+        let value = rounded as i64;
+        let shift = 64 - bits;
+        let wrapped = value.wrapping_shl(shift).wrapping_shr(shift);
+        (wrapped, wrapped != value)
+    }
+}
+
+#[inline]
+fn dequantize<T: Float>(sample: i32, bits: u32) -> T {
+    T::from_f64(sample as f64 * 0.5_f64.powi(bits as i32 - 1))
+}
+
+fn linear_to_mu_law(sample: i32) -> u8 {
+    let (sign, magnitude) = if sample < 0 { (0x80, -sample) } else { (0, sample) };
+    let magnitude = if magnitude > MU_LAW_CLIP { MU_LAW_CLIP } else { magnitude } + MU_LAW_BIAS;
+
+    let exponent = 31 - magnitude.leading_zeros() as i32 - 7;
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0f;
+
+    !(sign | (exponent << 4) | mantissa) as u8
+}
+
+fn mu_law_to_linear(code: u8) -> i32 {
+    let code = !code as i32;
+    let exponent = (code >> 4) & 0x07;
+    let mantissa = code & 0x0f;
+    let magnitude = (((mantissa << 3) + MU_LAW_BIAS) << exponent) - MU_LAW_BIAS;
+
+    if code & 0x80 != 0 { -magnitude } else { magnitude }
+}
+
+fn linear_to_a_law(sample: i32) -> u8 {
+    let sample = sample >> 3;
+    let (mask, magnitude) = if sample >= 0 { (0xd5, sample) } else { (0x55, -sample - 1) };
+
+    let segment = A_LAW_SEGMENT_ENDS.iter().position(|&end| magnitude <= end);
+    let code = match segment {
+        Some(segment) => {
+            let shift = if segment < 2 { 1 } else { segment };
+            ((segment << 4) as i32) | ((magnitude >> shift) & 0x0f)
+        },
+        None => 0x7f,
+    };
+
+    (code ^ mask) as u8
+}
+
+fn a_law_to_linear(code: u8) -> i32 {
+    let code = (code ^ 0x55) as i32;
+    let segment = (code & 0x70) >> 4;
+    let mut magnitude = (code & 0x0f) << 4;
+
+    match segment {
+        0 => magnitude += 8,
+        1 => magnitude += 0x108,
+        _ => magnitude = (magnitude + 0x108) << (segment - 1),
+    }
+
+    if code & 0x80 != 0 { magnitude } else { -magnitude }
+}
@@ -0,0 +1,92 @@
+use float::Float;
+
+
+/// Number of coefficients up to which `eval_auto` uses Horner's scheme.
+const HORNER_MAX_LEN: usize = 4;
+/// With hardware FMA every Horner step is a single fused instruction, so the
+/// serial chain stays competitive for longer.
+const HORNER_MAX_LEN_FMA: usize = 8;
+
+
+/// Evaluates `coeffs[0] + coeffs[1] * x + ... + coeffs[n] * x^n` with Horner's scheme.
+///
+/// ```
+/// use float::polynomial;
+/// assert_eq!(polynomial::eval(2.0_f64, &[1.0, 2.0, 3.0]), 17.0);
+/// ```
+#[inline]
+pub fn eval<T: Float>(x: T, coeffs: &[T]) -> T {
+    let mut iter = coeffs.iter().rev();
+
+    match iter.next() {
+        Some(&last) => iter.fold(last, |acc, &c| acc * x + c),
+        None => T::from_f64(0.0),
+    }
+}
+
+/// Evaluates the same polynomial as `eval` with Estrin's scheme.
+///
+/// The coefficients are split into halves that are evaluated independently and
+/// joined by a power of `x`, which shortens the dependency chain from `n` to
+/// `log2(n)` steps at the cost of a few extra multiplications.
+///
+/// ```
+/// use float::polynomial;
+/// let coeffs = [1.0_f64, -2.0, 3.0, -4.0, 5.0, -6.0, 7.0];
+/// assert_eq!(polynomial::eval_estrin(0.5, &coeffs), polynomial::eval(0.5, &coeffs));
+/// ```
+#[inline]
+pub fn eval_estrin<T: Float>(x: T, coeffs: &[T]) -> T {
+    match coeffs.len() {
+        0 => T::from_f64(0.0),
+        1 => coeffs[0],
+        2 => coeffs[0] + coeffs[1] * x,
+        len => {
+            let mut split = 2;
+            let mut x_split = x * x;
+
+            while split * 2 < len {
+                split *= 2;
+                x_split = x_split * x_split;
+            }
+
+            eval_estrin(x, &coeffs[..split]) + x_split * eval_estrin(x, &coeffs[split..])
+        },
+    }
+}
+
+/// Picks Horner's scheme or `eval_estrin` based on the number of
+/// coefficients and on whether the target was compiled with FMA support, in
+/// which case each Horner step is a `mul_add`.
+///
+/// ```
+/// use float::polynomial;
+/// assert_eq!(polynomial::eval_auto(2.0_f32, &[1.0, 1.0, 1.0]), 7.0);
+/// ```
+#[inline]
+pub fn eval_auto<T: Float>(x: T, coeffs: &[T]) -> T {
+    let max_len = if cfg!(target_feature = "fma") {
+        HORNER_MAX_LEN_FMA
+    } else {
+        HORNER_MAX_LEN
+    };
+
+    if coeffs.len() > max_len {
+        eval_estrin(x, coeffs)
+    } else if cfg!(target_feature = "fma") {
+        eval_fused(x, coeffs)
+    } else {
+        eval(x, coeffs)
+    }
+}
+
+/// Horner's scheme with a fused multiply-add per step.
+#[inline]
+fn eval_fused<T: Float>(x: T, coeffs: &[T]) -> T {
+    let mut iter = coeffs.iter().rev();
+
+    match iter.next() {
+        Some(&last) => iter.fold(last, |acc, &c| acc.mul_add(&x, &c)),
+        None => T::from_f64(0.0),
+    }
+}
@@ -0,0 +1,51 @@
+use core::ops::Mul;
+
+
+/// Types with a multiplicative identity, the starting point of `pow_usize`.
+///
+/// The crate implements it for the primitive numbers and `DoubleDouble`;
+/// it has no complex or dual number types, but any `Copy` type with a `Mul`
+/// that implements it gets `pow_usize` as well.
+pub trait MulIdentity: Copy + Mul<Output = Self> {
+    fn mul_identity() -> Self;
+}
+
+
+macro_rules! impl_mul_identity {
+    ($($T:ident),*) => ($(
+        impl MulIdentity for $T {
+            #[inline(always)]
+            fn mul_identity() -> Self {
+                1 as $T
+            }
+        }
+    )*)
+}
+
+impl_mul_identity!(f32, f64, u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+
+/// Computes `base^n` by repeated squaring, using `floor(log2(n))` squarings and
+/// at most as many extra multiplications. `base^0` is the identity.
+///
+/// ```
+/// use float::pow_usize;
+/// assert_eq!(pow_usize(3_u64, 13), 1594323);
+/// assert_eq!(pow_usize(0.5_f64, 1074), 5e-324);
+/// assert_eq!(pow_usize(7_i32, 0), 1);
+/// ```
+#[inline]
+pub fn pow_usize<T: MulIdentity>(mut base: T, mut n: usize) -> T {
+    let mut acc = T::mul_identity();
+
+    while n != 0 {
+        if n & 1 == 1 {
+            acc = acc * base;
+        }
+        n >>= 1;
+        if n != 0 {
+            base = base * base;
+        }
+    }
+    acc
+}
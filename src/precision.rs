@@ -0,0 +1,224 @@
+//! Design-time rounding error estimates for choosing between half, single
+//! and double precision.
+//!
+//! Given the magnitudes a computation works with and how many rounded
+//! operations feed into a result, `advise` reports for each format the
+//! classic worst case bound, the probabilistic estimate that errors of random
+//! sign accumulate like a random walk, and what they mean in absolute terms
+//! and in units in the last place. The figures are for chains of additions,
+//! multiplications and divisions that each round once; cancellation in the
+//! algorithm itself comes on top.
+//!
+//! ```
+//! use float::precision::{self, Format};
+//!
+//! // sensor readings between 1e-3 and 50, 1000 operations each
+//! let reports = precision::advise(1e-3, 50.0, 1000);
+//! let single = reports[Format::Binary32 as usize];
+//! assert!(!single.overflows && !single.underflows);
+//! assert!(single.relative_bound < 1e-4);
+//!
+//! // half precision still covers the range but drifts by percent
+//! let half = reports[Format::Binary16 as usize];
+//! assert!(!half.overflows && half.relative_bound > 0.1);
+//!
+//! assert_eq!(precision::recommend(1e-3, 50.0, 1000, 1e-3), Some(Format::Binary32));
+//! assert_eq!(precision::recommend(1e-3, 1e6, 10, 1e-2), Some(Format::Binary32));
+//! assert_eq!(precision::recommend(1e-3, 1e6, 10, 1e-12), Some(Format::Binary64));
+//! assert_eq!(precision::recommend(1.0, 2.0, 10, 1e-17), None);
+//! ```
+
+use core::{fmt, f64};
+
+use float::Float;
+
+
+/// Every format, narrowest first, so `FORMATS[format as usize] == format`.
+pub const FORMATS: [Format; 3] = [Format::Binary16, Format::Binary32, Format::Binary64];
+
+
+/// The IEEE 754 binary interchange formats `advise` compares.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    Binary16,
+    Binary32,
+    Binary64,
+}
+
+impl Format {
+    /// Significand bits, the implicit bit included.
+    #[inline]
+    pub fn mantissa_digits(&self) -> u32 {
+        match *self {
+            Format::Binary16 => 11,
+            Format::Binary32 => 24,
+            Format::Binary64 => 53,
+        }
+    }
+
+    /// The exponent of the smallest normal number, `2^min_exponent`.
+    #[inline]
+    pub fn min_exponent(&self) -> i32 {
+        match *self {
+            Format::Binary16 => -14,
+            Format::Binary32 => -126,
+            Format::Binary64 => -1022,
+        }
+    }
+
+    /// The exponent of the largest finite numbers.
+    #[inline]
+    pub fn max_exponent(&self) -> i32 {
+        -self.min_exponent() + 1
+    }
+
+    /// Half the distance from one to the next float, the largest relative
+    /// error of a rounding to nearest.
+    #[inline]
+    pub fn unit_roundoff(&self) -> f64 {
+        power_of_two(-(self.mantissa_digits() as i32))
+    }
+
+    /// The largest finite value.
+    #[inline]
+    pub fn max_value(&self) -> f64 {
+        let digits = self.mantissa_digits() as i32;
+        (2.0 - power_of_two(1 - digits)) * power_of_two(self.max_exponent())
+    }
+
+    /// The smallest normal value.
+    #[inline]
+    pub fn min_positive(&self) -> f64 {
+        power_of_two(self.min_exponent())
+    }
+
+    /// The spacing of the floats around `x` of either sign, the subnormal
+    /// spacing below `min_positive`.
+    #[inline]
+    pub fn ulp(&self, x: f64) -> f64 {
+        let exponent = x.exponent().max(self.min_exponent()).min(self.max_exponent());
+        power_of_two(exponent - (self.mantissa_digits() as i32 - 1))
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Format::Binary16 => "binary16",
+            Format::Binary32 => "binary32",
+            Format::Binary64 => "binary64",
+        })
+    }
+}
+
+
+/// Error estimates for one format, as returned by `advise`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PrecisionReport {
+    pub format: Format,
+    /// The largest magnitude is above the largest finite value.
+    pub overflows: bool,
+    /// The smallest magnitude is below the smallest normal value, where
+    /// precision drops with every bit of gradual underflow.
+    pub underflows: bool,
+    /// Worst case relative error `n u / (1 - n u)` after `n` roundings with
+    /// unit roundoff `u`, infinite once `n u` reaches one.
+    pub relative_bound: f64,
+    /// The estimate `sqrt(n) u` that holds with high probability when the
+    /// rounding errors are independent.
+    pub relative_expected: f64,
+    /// The worst case bound at the largest magnitude, plus half the
+    /// subnormal spacing per operation that underflow can lose.
+    pub absolute_bound: f64,
+    /// The worst case bound at the largest magnitude in units in the last
+    /// place there.
+    pub ulps_bound: f64,
+}
+
+impl PrecisionReport {
+    /// Whether the range fits and the worst case relative error is at most
+    /// `tolerance`.
+    #[inline]
+    pub fn meets(&self, tolerance: f64) -> bool {
+        !self.overflows && !self.underflows && self.relative_bound <= tolerance
+    }
+}
+
+impl fmt::Display for PrecisionReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: relative error up to {:e} (typically {:e}), absolute {:e}, {:.1} ulps",
+               self.format, self.relative_bound, self.relative_expected, self.absolute_bound,
+               self.ulps_bound)?;
+        if self.overflows {
+            f.write_str(", overflows")?;
+        }
+        if self.underflows {
+            f.write_str(", underflows")?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Error estimates for every format, in the order of `FORMATS`, for
+/// values whose nonzero magnitudes lie in `[smallest, largest]` and results
+/// that go through `operations` roundings.
+///
+/// ```
+/// use float::precision::{self, Format};
+///
+/// let reports = precision::advise(1.0, 1.0, 1);
+/// for &format in precision::FORMATS.iter() {
+///     let report = reports[format as usize];
+///     assert_eq!(report.format, format);
+///     // one rounding of values near one is half an ulp
+///     assert!((report.ulps_bound - 0.5).abs() < 1e-3);
+///     assert_eq!(report.relative_expected, format.unit_roundoff());
+/// }
+///
+/// let reports = precision::advise(1e-6, 1e5, 100);
+/// assert!(reports[Format::Binary16 as usize].overflows);
+/// assert!(reports[Format::Binary16 as usize].underflows);
+/// assert!(reports[Format::Binary32 as usize].meets(1e-5));
+///
+/// let text = reports[Format::Binary64 as usize].to_string();
+/// assert!(text.starts_with("binary64: relative error up to 1.11"), "{}", text);
+/// assert!(text.ends_with(", 76.3 ulps"), "{}", text);
+/// assert!(reports[Format::Binary16 as usize].to_string().ends_with("ulps, overflows, underflows"));
+/// ```
+pub fn advise(smallest: f64, largest: f64, operations: u64) -> [PrecisionReport; 3] {
+    assert!(smallest >= 0.0 && smallest <= largest, "advise needs 0 <= smallest <= largest");
+    let report = |format: Format| {
+        let n = operations as f64;
+        let u = format.unit_roundoff();
+        let relative_bound = if n * u < 1.0 { n * u / (1.0 - n * u) } else { f64::INFINITY };
+        let absolute_bound = relative_bound * largest + n * format.ulp(0.0) * 0.5;
+
+        PrecisionReport {
+            format: format,
+            overflows: largest > format.max_value(),
+            underflows: smallest > 0.0 && smallest < format.min_positive(),
+            relative_bound: relative_bound,
+            relative_expected: Float::sqrt(&n) * u,
+            absolute_bound: absolute_bound,
+            ulps_bound: if largest > 0.0 { relative_bound * largest / format.ulp(largest) } else { 0.0 },
+        }
+    };
+    [report(Format::Binary16), report(Format::Binary32), report(Format::Binary64)]
+}
+
+/// The narrowest format that holds the magnitudes without overflow or
+/// underflow and keeps the worst case relative error within `tolerance`,
+/// `None` if even `Binary64` does not.
+#[inline]
+pub fn recommend(smallest: f64, largest: f64, operations: u64, tolerance: f64) -> Option<Format> {
+    advise(smallest, largest, operations).iter()
+        .find(|report| report.meets(tolerance))
+        .map(|report| report.format)
+}
+
+
+#[inline]
+fn power_of_two(exponent: i32) -> f64 {
+    f64::integer_encode(1, exponent as i16, 1)
+}
@@ -0,0 +1,114 @@
+use core::fmt;
+
+use expansion::two_sum;
+use float::Float;
+
+
+/// Reasons a weight vector can not be turned into a probability vector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProbabilityError {
+    /// The slice has no elements.
+    Empty,
+    /// The weight at the given index is NaN or infinite.
+    NotFinite(usize),
+    /// The weight at the given index is negative.
+    Negative(usize),
+    /// All weights are zero.
+    ZeroSum,
+}
+
+impl fmt::Display for ProbabilityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProbabilityError::Empty => write!(f, "no weights given"),
+            ProbabilityError::NotFinite(index) => write!(f, "weight {} is not finite", index),
+            ProbabilityError::Negative(index) => write!(f, "weight {} is negative", index),
+            ProbabilityError::ZeroSum => write!(f, "weights sum to zero"),
+        }
+    }
+}
+
+
+/// Scales non-negative `weights` in place into probabilities whose running
+/// sum, accumulated left to right, ends at exactly `1.0`.
+///
+/// The weights are divided by their compensated sum, then the rounding error
+/// is folded into the last non-zero probability as `1 - sum(previous)`. Since
+/// that correction is a few ULPs of `1.0` at most, the cumulative sums stay
+/// non-decreasing and zero weights remain zero, so a CDF built from the
+/// result never selects an impossible outcome nor leaves a gap below `1.0`.
+/// In the pathological case where the last non-zero probability is smaller
+/// than the correction, it is set to zero and the previous one is corrected.
+///
+/// ```
+/// use float::probability::{self, ProbabilityError};
+///
+/// let mut weights = [1.0_f64, 1.0, 1.0, 0.0, 7.0];
+/// probability::normalize_probabilities_inplace(&mut weights).unwrap();
+/// assert_eq!(weights.iter().fold(0.0, |sum, &p| sum + p), 1.0);
+/// assert_eq!(weights[3], 0.0);
+///
+/// let mut thirds = [1.0_f32; 3];
+/// probability::normalize_probabilities_inplace(&mut thirds).unwrap();
+/// assert_eq!(thirds[0] + thirds[1] + thirds[2], 1.0);
+///
+/// assert_eq!(probability::normalize_probabilities_inplace(&mut [1.0, -1.0]),
+///            Err(ProbabilityError::Negative(1)));
+/// assert_eq!(probability::normalize_probabilities_inplace(&mut [0.0_f64, 0.0]),
+///            Err(ProbabilityError::ZeroSum));
+/// ```
+pub fn normalize_probabilities_inplace<T: Float>(weights: &mut [T]) -> Result<(), ProbabilityError> {
+    let zero = T::from_f64(0.0);
+    let one = T::from_f64(1.0);
+
+    if weights.is_empty() {
+        return Err(ProbabilityError::Empty);
+    }
+
+    let mut max = zero;
+    for (index, &w) in weights.iter().enumerate() {
+        if !w.is_finite() {
+            return Err(ProbabilityError::NotFinite(index));
+        } else if w < zero {
+            return Err(ProbabilityError::Negative(index));
+        } else if w > max {
+            max = w;
+        }
+    }
+    if max == zero {
+        return Err(ProbabilityError::ZeroSum);
+    }
+
+    // dividing by the largest weight first keeps the sum from overflowing
+    for w in weights.iter_mut() {
+        *w = *w / max;
+    }
+
+    let mut sum = zero;
+    let mut err = zero;
+    for &w in weights.iter() {
+        let (s, e) = two_sum(sum, w);
+        err = err + e;
+        sum = s;
+    }
+    let total = sum + err;
+
+    for w in weights.iter_mut() {
+        *w = *w / total;
+    }
+
+    let mut end = weights.len();
+    while let Some(last) = weights[..end].iter().rposition(|&w| w > zero) {
+        let prefix = weights[..last].iter().fold(zero, |sum, &w| sum + w);
+        let correction = one - prefix;
+
+        if correction >= zero {
+            weights[last] = correction;
+            return Ok(());
+        }
+        weights[last] = zero;
+        end = last;
+    }
+
+    Err(ProbabilityError::ZeroSum)
+}
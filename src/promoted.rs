@@ -0,0 +1,351 @@
+use core::num::FpCategory;
+use core::ops::{Add, Sub, Mul, Div, Rem, Neg};
+
+use approx_eq::ApproxEq;
+use signed::Signed;
+
+use float::Float;
+use float_parts::{FloatParts, FloatFields};
+
+
+/// A float that keeps its value in `T::Wider` and only rounds to `T` when
+/// read back with `get`.
+///
+/// The four basic operations, negation and comparisons run in the wider
+/// type, so a `Promoted<f32>` accumulator behaves like `f64` and a
+/// `Promoted<f64>` like a double-double. Everything else, the math functions
+/// as well as the classification and decoding methods, is evaluated in `T` on
+/// the rounded value. Since it implements `Float`, generic code can switch
+/// per variable between speed and accuracy.
+///
+/// ```
+/// use float::{Float, Promoted};
+///
+/// fn sum<T: Float>(values: &[f32]) -> f32 {
+///     let mut total = T::from_f64(0.0);
+///     for &x in values {
+///         total = total + T::from_f64(x as f64);
+///     }
+///     total.to_f64() as f32
+/// }
+///
+/// let values = [1.0_f32, 1e-8, 1e-8, 1e-8, 1e-8, 1e-8, 1e-8, -1.0];
+/// assert_eq!(sum::<f32>(&values), 0.0);
+/// assert!((sum::<Promoted<f32>>(&values) - 6e-8).abs() < 1e-14);
+///
+/// let tiny = Promoted::new(2.0_f64.powi(-60));
+/// let one = Promoted::new(1.0_f64);
+/// assert_eq!(((one + tiny) - one).get(), 2.0_f64.powi(-60));
+/// ```
+pub struct Promoted<T: Float>(T::Wider);
+
+impl<T: Float> Promoted<T> {
+    #[inline(always)]
+    pub fn new(value: T) -> Self {
+        Promoted(value.widen())
+    }
+
+    #[inline(always)]
+    pub fn from_wider(value: T::Wider) -> Self {
+        Promoted(value)
+    }
+
+    /// Rounds the value to `T`.
+    #[inline(always)]
+    pub fn get(&self) -> T {
+        T::narrow(self.0)
+    }
+
+    #[inline(always)]
+    pub fn wider(&self) -> T::Wider {
+        self.0
+    }
+}
+
+impl<T: Float> Clone for Promoted<T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Float> Copy for Promoted<T> {}
+
+impl<T: Float> PartialEq for Promoted<T> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Float> PartialOrd for Promoted<T> {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl<T: Float> From<T> for Promoted<T> {
+    #[inline(always)]
+    fn from(value: T) -> Self {
+        Promoted::new(value)
+    }
+}
+
+
+macro_rules! impl_wider_op {
+    ($Trait:ident, $method:ident, $op:tt) => (
+        impl<T: Float> $Trait for Promoted<T> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $method(self, other: Self) -> Self {
+                Promoted(self.0 $op other.0)
+            }
+        }
+    )
+}
+
+impl_wider_op!(Add, add, +);
+impl_wider_op!(Sub, sub, -);
+impl_wider_op!(Mul, mul, *);
+impl_wider_op!(Div, div, /);
+
+impl<T: Float> Rem for Promoted<T> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn rem(self, other: Self) -> Self {
+        Promoted::new(self.get() % other.get())
+    }
+}
+
+impl<T: Float> Neg for Promoted<T> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn neg(self) -> Self {
+        Promoted(-self.0)
+    }
+}
+
+impl<T: Float> ApproxEq for Promoted<T> {
+    #[inline(always)]
+    fn approx_eq(&self, other: &Self) -> bool {
+        self.get().approx_eq(&other.get())
+    }
+}
+
+impl<T: Float> Signed for Promoted<T> {
+    #[inline]
+    fn abs(&self) -> Self {
+        if self.get().is_sign_negative() { -*self } else { *self }
+    }
+    #[inline(always)]
+    fn signum(&self) -> Self {
+        Promoted::new(self.get().signum())
+    }
+    #[inline(always)]
+    fn is_positive(&self) -> bool {
+        self.get().is_positive()
+    }
+    #[inline(always)]
+    fn is_negative(&self) -> bool {
+        self.get().is_negative()
+    }
+}
+
+
+macro_rules! narrowed {
+    ($($method:ident),*) => ($(
+        #[inline(always)]
+        fn $method(&self) -> Self {
+            Promoted::new(self.get().$method())
+        }
+    )*)
+}
+
+macro_rules! narrowed_binary {
+    ($($method:ident),*) => ($(
+        #[inline(always)]
+        fn $method(&self, other: &Self) -> Self {
+            Promoted::new(self.get().$method(&other.get()))
+        }
+    )*)
+}
+
+macro_rules! narrowed_constant {
+    ($($method:ident),*) => ($(
+        #[inline(always)]
+        fn $method() -> Self {
+            Promoted::new(T::$method())
+        }
+    )*)
+}
+
+macro_rules! narrowed_query {
+    ($($method:ident -> $R:ty),*) => ($(
+        #[inline(always)]
+        fn $method(&self) -> $R {
+            self.get().$method()
+        }
+    )*)
+}
+
+impl<T: Float> Float for Promoted<T> {
+    narrowed!(trunc, floor, ceil, round, round_ties_even, fract, recip, exp, exp2, ln, log2, log10,
+              sqrt, cbrt, exp_m1, ln_1p, ulp, to_degrees, to_radians, sin, cos, tan, asin, acos,
+              atan, sinh, cosh, tanh, asinh, acosh, atanh, next_up, next_down, tanpi, atanpi,
+              asinpi, acospi, significand, logb);
+    narrowed_binary!(powf, log, hypot, ln_add_exp, ln_sub_exp, atan2, next_after);
+    narrowed_constant!(nan, infinity, neg_infinity, neg_zero, epsilon, pi, tau, e, sqrt_2,
+                       frac_1_sqrt_2, ln_2, ln_10, log2_e, log10_e, frac_1_pi, frac_2_pi, frac_pi_2,
+                       frac_pi_4);
+    narrowed_query!(is_nan -> bool, is_infinite -> bool, is_finite -> bool, is_normal -> bool,
+                    is_subnormal -> bool, classify -> FpCategory, is_sign_positive -> bool, is_sign_negative -> bool,
+                    to_f64 -> f64, exponent -> i32, sign_bit -> bool, integer_decode -> (u64, i16, i8),
+                    into_parts -> FloatParts, into_raw_parts -> FloatParts,
+                    parts -> FloatFields);
+
+    #[inline(always)]
+    fn from_f64(n: f64) -> Self {
+        Promoted::new(T::from_f64(n))
+    }
+    #[inline(always)]
+    fn mantissa_digits() -> u32 {
+        T::mantissa_digits()
+    }
+    #[inline(always)]
+    fn min_exp() -> i32 {
+        T::min_exp()
+    }
+    #[inline(always)]
+    fn max_exp() -> i32 {
+        T::max_exp()
+    }
+    #[inline(always)]
+    fn copysign(&self, sign: &Self) -> Self {
+        if self.is_sign_negative() == sign.is_sign_negative() { *self } else { -*self }
+    }
+    #[inline(always)]
+    fn flip_sign(&self, sign: &Self) -> Self {
+        if sign.is_sign_negative() { -*self } else { *self }
+    }
+    #[inline(always)]
+    fn min(&self, other: &Self) -> Self {
+        if self.is_nan() || other.0 < self.0 { *other } else { *self }
+    }
+    #[inline(always)]
+    fn max(&self, other: &Self) -> Self {
+        if self.is_nan() || other.0 > self.0 { *other } else { *self }
+    }
+    #[inline(always)]
+    fn clamp(&self, min: &Self, max: &Self) -> Self {
+        debug_assert!(min.0 <= max.0, "clamp needs min <= max");
+        if self.0 < min.0 { *min } else if self.0 > max.0 { *max } else { *self }
+    }
+    #[inline(always)]
+    fn powi(&self, n: i32) -> Self {
+        Promoted::new(self.get().powi(n))
+    }
+    #[inline(always)]
+    fn powi_checked(&self, n: i32) -> Option<Self> {
+        self.get().powi_checked(n).map(Promoted::new)
+    }
+    #[inline(always)]
+    fn compound(&self, n: i32) -> Self {
+        Promoted::new(self.get().compound(n))
+    }
+    #[inline(always)]
+    fn rootn(&self, n: i32) -> Self {
+        Promoted::new(self.get().rootn(n))
+    }
+    #[inline(always)]
+    fn mul_add(&self, a: &Self, b: &Self) -> Self {
+        Promoted::new(self.get().mul_add(&a.get(), &b.get()))
+    }
+    #[inline(always)]
+    fn sin_cos(&self) -> (Self, Self) {
+        let (sin, cos) = self.get().sin_cos();
+        (Promoted::new(sin), Promoted::new(cos))
+    }
+    #[inline(always)]
+    fn integer_encode(mantissa: u64, exponent: i16, sign: i8) -> Self {
+        Promoted::new(T::integer_encode(mantissa, exponent, sign))
+    }
+    #[inline(always)]
+    fn from_parts(parts: FloatParts) -> Self {
+        Promoted::new(T::from_parts(parts))
+    }
+    #[inline(always)]
+    fn from_raw_parts(parts: FloatParts) -> Self {
+        Promoted::new(T::from_raw_parts(parts))
+    }
+    #[inline(always)]
+    fn ulps_between(&self, other: &Self) -> u64 {
+        self.get().ulps_between(&other.get())
+    }
+    #[inline(always)]
+    fn total_cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+        self.get().total_cmp(&other.get())
+    }
+    #[inline(always)]
+    fn cmp_int(&self, i: i64) -> Option<::core::cmp::Ordering> {
+        self.get().cmp_int(i)
+    }
+    #[inline(always)]
+    fn eq_int(&self, i: i64) -> bool {
+        self.get().eq_int(i)
+    }
+    #[inline(always)]
+    fn cmp_decimal_str(&self, decimal: &str) -> Option<::core::cmp::Ordering> {
+        self.get().cmp_decimal_str(decimal)
+    }
+
+    type Bits = T::Bits;
+
+    #[inline(always)]
+    fn to_bits(&self) -> T::Bits {
+        self.get().to_bits()
+    }
+    #[inline(always)]
+    fn from_bits(bits: T::Bits) -> Self {
+        Promoted::new(T::from_bits(bits))
+    }
+    #[inline(always)]
+    fn signaling_nan() -> Self {
+        // widening to f64 may quiet it, as the conversion does on most targets
+        Promoted::new(T::signaling_nan())
+    }
+    #[inline(always)]
+    fn is_signaling_nan(&self) -> bool {
+        self.get().is_signaling_nan()
+    }
+    #[inline(always)]
+    fn nan_with_payload(payload: T::Bits) -> Self {
+        Promoted::new(T::nan_with_payload(payload))
+    }
+    #[inline(always)]
+    fn nan_payload(&self) -> Option<T::Bits> {
+        self.get().nan_payload()
+    }
+
+    type Wider = T::Wider;
+
+    #[inline(always)]
+    fn widen(&self) -> T::Wider {
+        self.0
+    }
+    #[inline(always)]
+    fn narrow(wide: T::Wider) -> Self {
+        Promoted(wide)
+    }
+    #[inline(always)]
+    fn widening_add(&self, other: &Self) -> T::Wider {
+        self.0 + other.0
+    }
+    #[inline(always)]
+    fn widening_mul(&self, other: &Self) -> T::Wider {
+        self.0 * other.0
+    }
+}
@@ -0,0 +1,250 @@
+//! The functions of Python's `math` module that have no direct counterpart in
+//! `Float`, with Python's semantics, for porting numeric code from Python and
+//! getting the same numbers out.
+//!
+//! The rest of the module maps onto `Float` methods of the same meaning:
+//!
+//! | Python | here |
+//! |---|---|
+//! | `fabs`, `copysign` | `abs`, `copysign` |
+//! | `isfinite`, `isinf`, `isnan` | `is_finite`, `is_infinite`, `is_nan` |
+//! | `floor`, `ceil`, `trunc` | `floor`, `ceil`, `trunc` |
+//! | `sqrt`, `cbrt`, `pow` | `sqrt`, `cbrt`, `powf` |
+//! | `exp`, `exp2`, `expm1` | `exp`, `exp2`, `exp_m1` |
+//! | `log(x)`, `log(x, base)`, `log2`, `log10`, `log1p` | `ln`, `log`, `log2`, `log10`, `ln_1p` |
+//! | `sin`, `cos`, `tan`, `asin`, `acos`, `atan`, `atan2` | the same names |
+//! | `sinh`, `cosh`, `tanh`, `asinh`, `acosh`, `atanh` | the same names |
+//! | `degrees`, `radians` | `to_degrees`, `to_radians` |
+//! | `nextafter`, `ulp`, `fma` | `next_after`, `ulp`, `mul_add` |
+//! | `hypot(x, y)` | `hypot`, or `pymath::hypot` for any number of arguments |
+//!
+//! Where Python raises `ValueError` for a bad argument these functions panic,
+//! and `fsum` reports its two errors in `FsumError`.
+
+use core::fmt;
+use core::mem;
+
+use collections::vec::Vec;
+
+use float::Float;
+use geometry;
+
+
+/// Why `fsum` has no result, Python's `OverflowError` and `ValueError`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FsumError {
+    /// A partial sum of finite values overflowed, even if the total would
+    /// not have.
+    Overflow,
+    /// The values hold both infinities.
+    InfMinusInf,
+}
+
+impl fmt::Display for FsumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FsumError::Overflow => write!(f, "intermediate overflow in fsum"),
+            FsumError::InfMinusInf => write!(f, "-inf + inf in fsum"),
+        }
+    }
+}
+
+
+/// `math.isclose(a, b)` with Python's defaults, a relative tolerance of
+/// `1e-9` and no absolute tolerance.
+///
+/// ```
+/// use float::pymath;
+///
+/// assert!(pymath::isclose(1.0_f64, 1.0 + 1e-10));
+/// assert!(!pymath::isclose(1.0_f64, 1.0 + 1e-8));
+/// assert!(!pymath::isclose(0.0_f64, 1e-300));
+/// assert!(pymath::isclose(f64::INFINITY, f64::INFINITY));
+/// assert!(!pymath::isclose(f64::NAN, f64::NAN));
+/// ```
+#[inline]
+pub fn isclose<T: Float>(a: T, b: T) -> bool {
+    isclose_with(a, b, T::from_f64(1e-9), T::from_f64(0.0))
+}
+
+/// `math.isclose(a, b, rel_tol=rel_tol, abs_tol=abs_tol)`: equal values,
+/// infinities included, are close, other infinities and NaNs are not, and
+/// finite values are when `|a - b|` is within `rel_tol` times the larger
+/// magnitude or within `abs_tol`. Panics on a negative tolerance.
+///
+/// ```
+/// use float::pymath;
+///
+/// assert!(pymath::isclose_with(0.0_f64, 1e-300, 1e-9, 1e-12));
+/// assert!(pymath::isclose_with(100.0_f32, 101.0, 0.01, 0.0));
+/// assert!(!pymath::isclose_with(f64::INFINITY, 1e308, 1.0, f64::INFINITY));
+/// ```
+pub fn isclose_with<T: Float>(a: T, b: T, rel_tol: T, abs_tol: T) -> bool {
+    let zero = T::from_f64(0.0);
+    assert!(rel_tol >= zero && abs_tol >= zero, "isclose tolerances must be non-negative");
+
+    if a == b {
+        return true;
+    }
+    if a.is_infinite() || b.is_infinite() {
+        return false;
+    }
+    let diff = (b - a).abs();
+    diff <= (rel_tol * b).abs() || diff <= (rel_tol * a).abs() || diff <= abs_tol
+}
+
+/// `math.fsum`: the correctly rounded sum, from Shewchuk's exact partial
+/// sums and CPython's final rounding, so it agrees with Python bit for bit.
+///
+/// An infinity or NaN among the values gives their plain sum, NaN for both
+/// infinities together, which Python reports as `InfMinusInf`.
+///
+/// ```
+/// use float::pymath::{self, FsumError};
+///
+/// let values = [0.1_f64; 10];
+/// assert_eq!(values.iter().sum::<f64>(), 0.9999999999999999);
+/// assert_eq!(pymath::fsum(values.iter().cloned()), Ok(1.0));
+///
+/// assert_eq!(pymath::fsum(vec![1e100_f64, 1.0, -1e100, 1e-100, 1e50, -1.0, -1e50]), Ok(1e-100));
+/// // the halfway case CPython resolves with the partials below it
+/// assert_eq!(pymath::fsum(vec![1.0_f64, 1e-16, 1e-16]), Ok(1.0000000000000002));
+/// assert_eq!(pymath::fsum(Vec::<f32>::new()), Ok(0.0));
+///
+/// assert_eq!(pymath::fsum(vec![f64::INFINITY, 1.0]), Ok(f64::INFINITY));
+/// assert_eq!(pymath::fsum(vec![f64::INFINITY, f64::NEG_INFINITY]), Err(FsumError::InfMinusInf));
+/// assert!(pymath::fsum(vec![f64::NAN, 1.0]).unwrap().is_nan());
+/// assert_eq!(pymath::fsum(vec![f64::MAX, f64::MAX, -f64::MAX]), Err(FsumError::Overflow));
+/// ```
+pub fn fsum<T, I>(values: I) -> Result<T, FsumError>
+    where T: Float,
+          I: IntoIterator<Item = T>,
+{
+    let zero = T::from_f64(0.0);
+    let mut partials: Vec<T> = Vec::new();
+    let mut special = zero;
+    let mut infinities = zero;
+
+    for value in values {
+        let mut x = value;
+        let mut kept = 0;
+        for i in 0..partials.len() {
+            let mut y = partials[i];
+            if x.abs() < y.abs() {
+                mem::swap(&mut x, &mut y);
+            }
+            let hi = x + y;
+            let lo = y - (hi - x);
+            if lo != zero {
+                partials[kept] = lo;
+                kept += 1;
+            }
+            x = hi;
+        }
+        partials.truncate(kept);
+
+        if x != zero {
+            if !x.is_finite() {
+                // a non-finite sum of finite values is an overflow
+                if value.is_finite() {
+                    return Err(FsumError::Overflow);
+                }
+                if value.is_infinite() {
+                    infinities = infinities + value;
+                }
+                special = special + value;
+                partials.clear();
+            } else {
+                partials.push(x);
+            }
+        }
+    }
+
+    if special != zero {
+        return if infinities.is_nan() { Err(FsumError::InfMinusInf) } else { Ok(special) };
+    }
+    if special.is_nan() {
+        return Ok(special);
+    }
+
+    let mut n = partials.len();
+    if n == 0 {
+        return Ok(zero);
+    }
+    n -= 1;
+    let mut hi = partials[n];
+    let mut lo = zero;
+    while n > 0 {
+        let x = hi;
+        n -= 1;
+        let y = partials[n];
+        hi = x + y;
+        lo = y - (hi - x);
+        if lo != zero {
+            break;
+        }
+    }
+
+    // a halfway rounding of hi + lo that the next partial breaks the tie of
+    if n > 0 && ((lo < zero && partials[n - 1] < zero) || (lo > zero && partials[n - 1] > zero)) {
+        let y = lo + lo;
+        let x = hi + y;
+        if y == x - hi {
+            hi = x;
+        }
+    }
+    Ok(hi)
+}
+
+/// `math.prod`: the product from left to right, starting from one, so an
+/// empty iterator gives one.
+///
+/// ```
+/// use float::pymath;
+///
+/// assert_eq!(pymath::prod(vec![1.5_f64, 2.0, -4.0]), -12.0);
+/// assert_eq!(pymath::prod(Vec::<f32>::new()), 1.0);
+/// assert!(pymath::prod(vec![f64::INFINITY, 0.0]).is_nan());
+/// ```
+#[inline]
+pub fn prod<T, I>(values: I) -> T
+    where T: Float,
+          I: IntoIterator<Item = T>,
+{
+    values.into_iter().fold(T::from_f64(1.0), |product, x| product * x)
+}
+
+/// `math.hypot(*coordinates)`: the Euclidean norm of any number of
+/// coordinates, zero for none, which is `geometry::hypot_n`.
+///
+/// ```
+/// use float::pymath;
+///
+/// assert_eq!(pymath::hypot(&[3.0_f64, 4.0, 12.0]), 13.0);
+/// assert_eq!(pymath::hypot(&[3e-320_f64, 4e-320]), 5e-320);
+/// assert_eq!(pymath::hypot(&[f64::NAN, f64::NEG_INFINITY]), f64::INFINITY);
+/// ```
+#[inline]
+pub fn hypot<T: Float>(coordinates: &[T]) -> T {
+    geometry::hypot_n(coordinates)
+}
+
+/// `math.dist(p, q)`: the Euclidean distance between two points given as
+/// coordinate slices of the same length, which Python requires and this
+/// asserts.
+///
+/// ```
+/// use float::pymath;
+///
+/// assert_eq!(pymath::dist(&[1.0_f64, 2.0], &[4.0, 6.0]), 5.0);
+/// assert_eq!(pymath::dist(&[1e308_f64], &[-1e308]), f64::INFINITY);
+/// ```
+pub fn dist<T: Float>(p: &[T], q: &[T]) -> T {
+    assert!(p.len() == q.len(), "dist needs both points to have the same dimension");
+    let mut differences: Vec<T> = Vec::with_capacity(p.len());
+    for (&a, &b) in p.iter().zip(q.iter()) {
+        differences.push(a - b);
+    }
+    hypot(&differences)
+}
+
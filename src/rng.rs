@@ -0,0 +1,226 @@
+//! Small `no_std` pseudo random number generators with an exact mapping from
+//! random bits to floats in `[0, 1)`.
+//!
+//! `unit` takes the top `mantissa_digits` bits of a 64-bit word and scales
+//! them by `2^-mantissa_digits`. The result is one of `2^mantissa_digits`
+//! equally likely, evenly spaced values, every mantissa bit down to the last
+//! one is a fair coin, and `1.0` can never be produced. The common
+//! alternatives, dividing by `u64::MAX` or converting all 64 bits before
+//! scaling, round and may return `1.0` or leave the low bits biased.
+
+use float::Float;
+
+
+const PCG32_MULTIPLIER: u64 = 6364136223846793005;
+
+
+/// Maps the high bits of `bits` to a float in `[0, 1)` without rounding.
+///
+/// ```
+/// use float::rng;
+/// assert_eq!(rng::unit::<f64>(0), 0.0);
+/// assert_eq!(rng::unit::<f64>(1 << 63), 0.5);
+/// assert_eq!(rng::unit::<f64>(u64::max_value()), 1.0 - f64::EPSILON / 2.0);
+/// assert_eq!(rng::unit::<f32>(u64::max_value()), 1.0 - f32::EPSILON / 2.0);
+/// ```
+#[inline]
+pub fn unit<T: Float>(bits: u64) -> T {
+    let digits = T::mantissa_digits();
+    T::from_f64((bits >> (64 - digits)) as f64) * T::from_f64(0.5).powi(digits as i32)
+}
+
+
+/// A source of uniformly distributed random bits.
+pub trait FloatRng {
+    fn next_u64(&mut self) -> u64;
+
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Returns a float uniformly distributed in `[0, 1)`, see `unit`.
+    #[inline]
+    fn next_float<T: Float>(&mut self) -> T where Self: Sized {
+        unit(self.next_u64())
+    }
+
+    /// Returns an integer uniformly distributed in `[0, n)`, by Lemire's
+    /// widening multiply: the high word of `next_u32() * n`, redrawing the
+    /// few words whose low word falls in the `2^32 mod n` values that would
+    /// make some results more likely than others. Panics if `n` is zero.
+    ///
+    /// ```
+    /// use float::rng::{FloatRng, Pcg32};
+    ///
+    /// let mut rng = Pcg32::new(7, 0);
+    /// let mut counts = [0; 3];
+    /// for _ in 0..30000 {
+    ///     counts[rng.next_below(3) as usize] += 1;
+    /// }
+    /// assert!(counts.iter().all(|&count| (count - 10000_i32).abs() < 400));
+    /// assert_eq!(rng.next_below(1), 0);
+    /// ```
+    #[inline]
+    fn next_below(&mut self, n: u32) -> u32 {
+        assert!(n > 0, "next_below needs a non-empty range");
+
+        let mut product = self.next_u32() as u64 * n as u64;
+        if (product as u32) < n {
+            let threshold = n.wrapping_neg() % n;
+            while (product as u32) < threshold {
+                product = self.next_u32() as u64 * n as u64;
+            }
+        }
+        (product >> 32) as u32
+    }
+
+    /// Returns a float uniformly distributed in `[low, high)`, `low` plus
+    /// the width times `next_float`. Draws that round up to `high` are
+    /// redrawn rather than moved, so no value gets their weight; after 64
+    /// such draws in a row, which no real range needs, the float below
+    /// `high` is returned. A width that overflows is taken in halves. Panics
+    /// unless `low < high` are finite.
+    ///
+    /// ```
+    /// use float::rng::{FloatRng, XorShift64Star};
+    ///
+    /// let mut rng = XorShift64Star::new(3);
+    /// for _ in 0..10000 {
+    ///     let x = rng.next_range(1.0_f32, 1.0 + 3.0 * f32::EPSILON);
+    ///     assert!(x >= 1.0 && x < 1.0 + 3.0 * f32::EPSILON);
+    /// }
+    ///
+    /// let mut negative = 0;
+    /// for _ in 0..10000 {
+    ///     let x = rng.next_range(-f64::MAX, f64::MAX);
+    ///     assert!(x.is_finite());
+    ///     negative += (x < 0.0) as i32;
+    /// }
+    /// assert!((negative - 5000).abs() < 200);
+    /// ```
+    #[inline]
+    fn next_range<T: Float>(&mut self, low: T, high: T) -> T where Self: Sized {
+        assert!(low < high && low.is_finite() && high.is_finite(), "next_range needs finite low < high");
+
+        let width = high - low;
+        let half = T::from_f64(0.5);
+        for _ in 0..64 {
+            let u = self.next_float::<T>();
+            let value = if width.is_finite() {
+                low + width * u
+            } else {
+                let step = (high * half - low * half) * u;
+                (low + step) + step
+            };
+            // rounding can land on `high` when the range is not a power of two
+            if value < high {
+                return value;
+            }
+        }
+        Float::next_down(&high)
+    }
+}
+
+
+/// Marsaglia's xorshift64* generator: 64 bits of state, period `2^64 - 1`.
+///
+/// ```
+/// use float::rng::{FloatRng, XorShift64Star};
+///
+/// let mut rng = XorShift64Star::new(1);
+/// let mut low_bits = 0;
+/// for _ in 0..10000 {
+///     let x: f64 = rng.next_float();
+///     assert!(x >= 0.0 && x < 1.0);
+///     low_bits += ((x * 2.0_f64.powi(53)) as u64 & 1) as i32;
+/// }
+/// // the last mantissa bit is as fair as the first
+/// assert!((low_bits - 5000).abs() < 200);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XorShift64Star {
+    state: u64,
+}
+
+impl XorShift64Star {
+    /// Seeds the generator, replacing a zero seed by a fixed non-zero one.
+    #[inline]
+    pub fn new(seed: u64) -> Self {
+        XorShift64Star {
+            state: if seed == 0 { 0x9e3779b97f4a7c15 } else { seed },
+        }
+    }
+}
+
+impl FloatRng for XorShift64Star {
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+}
+
+
+/// O'Neill's PCG32 (XSH RR variant): 64 bits of state, 32-bit output.
+///
+/// ```
+/// use float::rng::{FloatRng, Pcg32};
+///
+/// let mut rng = Pcg32::new(42, 54);
+/// assert_eq!(rng.next_u32(), 0xa15c02b7);
+/// assert_eq!(rng.next_u32(), 0x7b47f409);
+///
+/// let mut low_bits = 0;
+/// for _ in 0..10000 {
+///     let x: f32 = rng.next_float();
+///     assert!(x >= 0.0 && x < 1.0);
+///     low_bits += ((x * 2.0_f32.powi(24)) as u32 & 1) as i32;
+/// }
+/// assert!((low_bits - 5000).abs() < 200);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    /// Seeds the generator with an initial state and a stream selector.
+    #[inline]
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut rng = Pcg32 {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    #[inline(always)]
+    fn step(&mut self) {
+        self.state = self.state.wrapping_mul(PCG32_MULTIPLIER).wrapping_add(self.increment);
+    }
+}
+
+impl FloatRng for Pcg32 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rotation = (old >> 59) as u32;
+        xorshifted.rotate_right(rotation)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let high = self.next_u32() as u64;
+        (high << 32) | self.next_u32() as u64
+    }
+}
@@ -0,0 +1,46 @@
+/// IEEE 754 rounding-direction attributes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Round to nearest, ties to even.
+    NearestEven,
+    /// Round toward zero (truncate).
+    TowardZero,
+    /// Round toward positive infinity.
+    TowardPositive,
+    /// Round toward negative infinity.
+    TowardNegative,
+}
+
+impl RoundingMode {
+    /// Returns true if a value with the given sign whose discarded bits are
+    /// `round` (the first discarded bit) and `sticky` (any later bit) should be
+    /// rounded away from zero, given that the kept bits end in `odd`.
+    #[inline]
+    pub fn rounds_away(&self, negative: bool, odd: bool, round: bool, sticky: bool) -> bool {
+        match *self {
+            RoundingMode::NearestEven => round && (sticky || odd),
+            RoundingMode::TowardZero => false,
+            RoundingMode::TowardPositive => !negative && (round || sticky),
+            RoundingMode::TowardNegative => negative && (round || sticky),
+        }
+    }
+
+    /// Returns true if a value too large for the format overflows to infinity
+    /// instead of the largest finite number.
+    #[inline]
+    pub fn overflows_to_infinity(&self, negative: bool) -> bool {
+        match *self {
+            RoundingMode::NearestEven => true,
+            RoundingMode::TowardZero => false,
+            RoundingMode::TowardPositive => !negative,
+            RoundingMode::TowardNegative => negative,
+        }
+    }
+}
+
+impl Default for RoundingMode {
+    #[inline(always)]
+    fn default() -> Self {
+        RoundingMode::NearestEven
+    }
+}
@@ -0,0 +1,117 @@
+use conformance::{self, ConformanceReport, opaque};
+use expansion::two_sum;
+use float::Float;
+use linalg;
+use mat_view::{MatView, MatViewMut};
+use nan_debug::nan_tolerant;
+use wrap::{wrap_range, fold_range};
+
+
+/// Runs the correctness checks of the crate on the running target, for
+/// bring-up on hardware where the doctests can not run: the IEEE 754
+/// battery of `conformance::run` followed by the properties the doctests pin
+/// down for the crate's own functions, for `f32` and `f64`.
+///
+/// Enabled by the `self-test` feature. The checks need no allocation beyond
+/// the report and no output, so the report can be inspected from a debugger
+/// or sent over whatever link the board has.
+///
+/// ```
+/// let report = float::self_test();
+/// assert!(report.is_conformant(), "{}", report);
+/// assert!(report.total() > float::conformance::run().total());
+/// ```
+pub fn self_test() -> ConformanceReport {
+    let mut report = conformance::run();
+
+    nan_tolerant(|| {
+        check::<f32>(&mut report, "f32");
+        check::<f64>(&mut report, "f64");
+    });
+
+    report
+}
+
+
+fn check<T: Float>(report: &mut ConformanceReport, float: &'static str) {
+    let zero = opaque(T::from_f64(0.0));
+    let one = opaque(T::from_f64(1.0));
+    let two = opaque(T::from_f64(2.0));
+    let half = opaque(T::from_f64(0.5));
+    let eps = opaque(T::epsilon());
+    let inf = opaque(T::infinity());
+    let min_normal = opaque(two.powi(T::min_exp() - 1));
+    let tiny = min_normal * eps;
+
+    // neighbours and ordering
+    report.record(float, "next_up(1) is 1 + eps", one.next_up() == one + eps);
+    report.record(float, "next_down(0) is the negative smallest subnormal", zero.next_down() == -tiny);
+    report.record(float, "next_after(1, 2) is next_up(1)", one.next_after(&two) == one.next_up());
+    report.record(float, "ulps_between(1, next_up(1)) is 1", one.ulps_between(&one.next_up()) == 1);
+    report.record(float, "total_cmp orders -0 before +0", (-zero).total_cmp(&zero) == ::core::cmp::Ordering::Less);
+    report.record(float, "total_cmp orders NaN after inf", T::nan().total_cmp(&inf) == ::core::cmp::Ordering::Greater);
+
+    // classification and encoding
+    report.record(float, "is_subnormal at the normal boundary",
+                  !min_normal.is_subnormal() && min_normal.next_down().is_subnormal() && !zero.is_subnormal());
+    report.record(float, "integer_encode inverts integer_decode", {
+        let values = [one, -half, tiny, min_normal.next_down(), T::from_f64(0.1)];
+        values.iter().all(|x| {
+            let (mantissa, exponent, sign) = x.integer_decode();
+            T::integer_encode(mantissa, exponent, sign) == *x
+        })
+    });
+    report.record(float, "from_parts inverts into_parts", {
+        let x = opaque(T::from_f64(-3.75));
+        T::from_parts(x.into_parts()) == x && T::from_raw_parts(x.into_raw_parts()) == x
+    });
+    report.record(float, "signaling NaN keeps its quiet bit clear", {
+        let snan = T::signaling_nan();
+        snan.is_nan() && snan.is_signaling_nan() && !T::nan().is_signaling_nan()
+    });
+    report.record(float, "NaN payloads round trip", match T::signaling_nan().nan_payload() {
+        Some(payload) => {
+            let x = T::nan_with_payload(payload);
+            !x.is_signaling_nan() && x.nan_payload() == Some(payload) && one.nan_payload().is_none()
+        }
+        None => false,
+    });
+    report.record(float, "exponent and significand of 6", {
+        let x = opaque(T::from_f64(6.0));
+        x.exponent() == 2 && x.significand() == T::from_f64(1.5)
+    });
+
+    // rounding and exact comparisons
+    report.record(float, "round(2.5) is 3", opaque(T::from_f64(2.5)).round() == T::from_f64(3.0));
+    report.record(float, "round_ties_even(2.5) is 2", opaque(T::from_f64(2.5)).round_ties_even() == two);
+    report.record(float, "0.1 is not exactly 1/10",
+                  T::from_f64(0.1).cmp_decimal_str("0.1") != Some(::core::cmp::Ordering::Equal));
+    report.record(float, "eq_int(2) holds only for 2", two.eq_int(2) && !two.next_up().eq_int(2));
+
+    // functions with exact results
+    report.record(float, "sqrt(-0) is -0", {
+        let root = (-zero).sqrt();
+        root == zero && root.is_sign_negative()
+    });
+    report.record(float, "rootn(-8, 3) is -2", opaque(T::from_f64(-8.0)).rootn(3) == -two);
+    report.record(float, "compound(x, 0) is 1", opaque(T::from_f64(0.3)).compound(0) == one);
+    report.record(float, "tanpi(1/4) is 1", opaque(T::from_f64(0.25)).tanpi() == one);
+    report.record(float, "two_sum keeps the rounding error", {
+        let (sum, error) = two_sum(one, eps * half * half);
+        sum == one && error == eps * half * half
+    });
+    report.record(float, "wrap_range(hi) is lo", wrap_range(one, zero, one) == zero);
+    report.record(float, "fold_range reflects at hi", fold_range(T::from_f64(1.25), zero, one) == T::from_f64(0.75));
+
+    // a well conditioned solve comes out exact
+    report.record(float, "lu_solve of a 2x2 system", {
+        let mut a = [T::from_f64(4.0), T::from_f64(3.0), T::from_f64(6.0), T::from_f64(3.0)];
+        let mut pivots = [0; 2];
+        let mut b = [T::from_f64(10.0), T::from_f64(12.0)];
+        let ok = linalg::lu_decompose(&mut MatViewMut::new(&mut a, 2, 2), &mut pivots).is_ok();
+        ok && {
+            linalg::lu_solve(&MatView::new(&a, 2, 2), &pivots, &mut b);
+            b[0] == one && b[1] == two
+        }
+    });
+}
@@ -0,0 +1,287 @@
+//! Special functions in forms that stay finite and accurate where the naive
+//! expressions overflow or cancel.
+//!
+//! The functions evaluate in `f64` and round once to `T`, with a relative
+//! error of a few units in the last place of `f64` over the whole range.
+
+use core::f64;
+
+use expansion::two_product;
+use float::Float;
+
+
+const FRAC_1_SQRT_PI: f64 = 0.5641895835477562869480794515607725858;
+const EULER_GAMMA: f64 = 0.5772156649015328606065120900824024310;
+/// The positive root of `Ei`, as a sum of two `f64`.
+const EI_ROOT: (f64, f64) = (0.3725074107813666, 1.3140183414386028e-17);
+
+
+/// The scaled complementary error function `e^(x^2) erfc(x)`.
+///
+/// It decays like `1 / (x sqrt(pi))` instead of underflowing, and overflows
+/// only where `e^(x^2)` does, below about `-26.6`.
+///
+/// ```
+/// use float::special;
+/// assert_eq!(special::erfcx(0.0_f64), 1.0);
+/// assert!((special::erfcx(1.0_f64) - 0.42758357615580700442).abs() < 1e-16);
+/// assert!((special::erfcx(30.0_f64) / 0.018795888861416751 - 1.0).abs() < 1e-15);
+/// assert!((special::erfcx(-2.0_f32) - 108.940_85).abs() < 1e-4);
+/// assert!((special::erfcx(1e200_f64) * 1e200 - 0.5641895835477563).abs() < 1e-16);
+/// assert_eq!(special::erfcx(-30.0_f64), f64::INFINITY);
+/// ```
+pub fn erfcx<T: Float>(x: T) -> T {
+    T::from_f64(erfcx_f64(x.to_f64()))
+}
+
+/// The exponential integral `E1(x)`, the integral of `e^-t / t` from `x` to
+/// infinity, for `x >= 0`. Negative arguments give NaN.
+///
+/// ```
+/// use float::special;
+/// assert_eq!(special::expint_e1(0.0_f64), f64::INFINITY);
+/// assert!((special::expint_e1(1.0_f64) - 0.21938393439552027368).abs() < 1e-15);
+/// assert!((special::expint_e1(1e-10_f64) - 22.448635265138923).abs() < 1e-14);
+/// assert!(special::expint_e1(-1.0_f64).is_nan());
+/// ```
+pub fn expint_e1<T: Float>(x: T) -> T {
+    let x = x.to_f64();
+    T::from_f64(if x > 1.0 { e1_scaled(x) * (-x).exp() } else { e1_f64(x) })
+}
+
+/// The scaled exponential integral `e^x E1(x)`, which behaves like `1 / x`
+/// for large `x` instead of underflowing.
+///
+/// ```
+/// use float::special;
+/// assert!((special::expint_e1_scaled(1000.0_f64) - 0.00099900199402388071).abs() < 1e-18);
+/// assert!((special::expint_e1_scaled(1.0_f32) - 0.596_347_4).abs() < 1e-7);
+/// ```
+pub fn expint_e1_scaled<T: Float>(x: T) -> T {
+    let x = x.to_f64();
+    T::from_f64(if x > 1.0 { e1_scaled(x) } else { e1_f64(x) * x.exp() })
+}
+
+/// The exponential integral `Ei(x)`, the principal value of the integral of
+/// `e^t / t` from minus infinity to `x`, which is `-E1(-x)` for negative `x`.
+///
+/// Around its positive root the result keeps its relative accuracy.
+///
+/// ```
+/// use float::special;
+/// assert_eq!(special::expint_ei(0.0_f64), f64::NEG_INFINITY);
+/// assert!((special::expint_ei(1.0_f64) - 1.8951178163559367555).abs() < 1e-15);
+/// assert!((special::expint_ei(-1.0_f64) + 0.21938393439552027368).abs() < 1e-15);
+/// assert!((special::expint_ei(50.0_f64) / 1.0585636897131691e20 - 1.0).abs() < 1e-15);
+/// assert!((special::expint_ei(0.3725_f64) / -2.8874183188745965e-5 - 1.0).abs() < 1e-14);
+/// ```
+pub fn expint_ei<T: Float>(x: T) -> T {
+    T::from_f64(ei_f64(x.to_f64()))
+}
+
+
+/// The Wright omega function, the solution `w` of `w + ln(w) = x`, which is
+/// `W0(e^x)` without forming `e^x`.
+///
+/// ```
+/// use float::special;
+/// assert_eq!(special::wright_omega(1.0_f64), 1.0);
+/// assert!((special::wright_omega(0.0_f64) - 0.56714329040978387300).abs() < 1e-16);
+/// assert!((special::wright_omega(1000.0_f64) - 993.09916947238910439).abs() < 1e-12);
+/// assert!((special::wright_omega(-40.0_f32) / 4.248354e-18 - 1.0).abs() < 1e-6);
+/// assert_eq!(special::wright_omega(f64::NEG_INFINITY), 0.0);
+/// ```
+pub fn wright_omega<T: Float>(x: T) -> T {
+    T::from_f64(wright_omega_f64(x.to_f64()))
+}
+
+
+fn erfcx_f64(x: f64) -> f64 {
+    if x.is_nan() {
+        x
+    } else if x < -1.5 {
+        // erfcx(x) = 2 e^(x^2) - erfcx(-x), with x^2 split exactly so the
+        // exponential does not amplify its rounding error
+        let (square, error) = two_product(x, x);
+        let scale = square.exp();
+        if scale == f64::INFINITY {
+            return scale;
+        }
+        2.0 * (scale + scale * error) - erfcx_f64(-x)
+    } else if x < 1.5 {
+        // Taylor series from the differential equation y' = 2xy - 2/sqrt(pi)
+        let mut previous = 1.0;
+        let mut current = -2.0 * FRAC_1_SQRT_PI;
+        let mut power = x;
+        let mut sum = previous + current * x;
+        let mut n = 1.0;
+
+        loop {
+            let next = 2.0 * previous / (n + 1.0);
+            previous = current;
+            current = next;
+            power *= x;
+            n += 1.0;
+
+            let term = current * power;
+            sum += term;
+            if term.abs() <= 1e-17 * sum.abs() {
+                return sum;
+            }
+        }
+    } else {
+        // Laplace's continued fraction, evaluated from the tail
+        let terms = if x < 3.0 { 120 } else if x < 8.0 { 40 } else { 12 };
+        let mut t = x;
+        for k in (1..terms + 1).rev() {
+            t = x + (k as f64 * 0.5) / t;
+        }
+        FRAC_1_SQRT_PI / t
+    }
+}
+
+/// `E1` for `0 <= x <= 1`, from its power series.
+fn e1_f64(x: f64) -> f64 {
+    if !(x >= 0.0) {
+        return f64::NAN;
+    } else if x == 0.0 {
+        return f64::INFINITY;
+    } else if x > 1.0 {
+        return e1_scaled(x) * (-x).exp();
+    }
+
+    let mut sum = 0.0;
+    let mut power = 1.0;
+    let mut k = 1.0;
+    loop {
+        power *= -x / k;
+        let term = power / k;
+        sum += term;
+        if term.abs() <= 1e-17 * sum.abs() {
+            break;
+        }
+        k += 1.0;
+    }
+
+    -EULER_GAMMA - x.ln() - sum
+}
+
+/// `e^x E1(x)` for `x > 1`, from the continued fraction evaluated with
+/// Lentz's method.
+fn e1_scaled(x: f64) -> f64 {
+    if x == f64::INFINITY {
+        return 0.0;
+    }
+
+    let mut b = x + 1.0;
+    let mut c = 1e300;
+    let mut d = 1.0 / b;
+    let mut h = d;
+
+    for i in 1..1000 {
+        let a = -((i * i) as f64);
+        b += 2.0;
+        d = 1.0 / (a * d + b);
+        c = b + a / c;
+        let delta = c * d;
+        h *= delta;
+        if (delta - 1.0).abs() < 1e-16 {
+            break;
+        }
+    }
+
+    h
+}
+
+fn ei_f64(x: f64) -> f64 {
+    if x.is_nan() {
+        x
+    } else if x < 0.0 {
+        -e1_f64(-x)
+    } else if x == 0.0 {
+        f64::NEG_INFINITY
+    } else if x > 0.2 && x < 0.6 {
+        // Ei(x) = ln(x / x0) + S(x) - S(x0) where S is the power series sum,
+        // with x^k - x0^k accumulated directly to avoid cancellation
+        let dx = (x - EI_ROOT.0) - EI_ROOT.1;
+        let mut sum = 0.0;
+        let mut difference = 0.0;
+        let mut root_power = 1.0;
+        let mut factorial = 1.0;
+        let mut k = 1.0;
+
+        loop {
+            difference = x * difference + root_power * dx;
+            factorial *= k;
+            let term = difference / (k * factorial);
+            sum += term;
+            if term.abs() <= 1e-18 * sum.abs() || k > 60.0 {
+                break;
+            }
+            root_power *= EI_ROOT.0;
+            k += 1.0;
+        }
+
+        (dx / EI_ROOT.0).ln_1p() + sum
+    } else if x <= 40.0 {
+        let mut sum = 0.0;
+        let mut power = 1.0;
+        let mut k = 1.0;
+        loop {
+            power *= x / k;
+            let term = power / k;
+            sum += term;
+            if term <= 1e-17 * sum {
+                break;
+            }
+            k += 1.0;
+        }
+        EULER_GAMMA + x.ln() + sum
+    } else {
+        // asymptotic series, stopped at its smallest term
+        let mut sum = 1.0;
+        let mut term = 1.0;
+        let mut k = 1.0;
+        loop {
+            let next = term * k / x;
+            if next < 1e-17 || next > term {
+                break;
+            }
+            term = next;
+            sum += term;
+            k += 1.0;
+        }
+        // split e^x so that Ei stays finite as long as the result does
+        let half = (0.5 * x).exp();
+        half * (half * sum / x)
+    }
+}
+
+fn wright_omega_f64(x: f64) -> f64 {
+    if x.is_nan() || x == f64::INFINITY {
+        return x;
+    }
+
+    let mut w = if x < -2.0 {
+        x.exp()
+    } else if x < 1.0 {
+        let d = x - 1.0;
+        1.0 + d * (0.5 + d * (1.0 / 16.0 - d / 192.0))
+    } else {
+        let ln = x.ln();
+        x - ln + ln / x
+    };
+
+    // Fritsch, Shafer and Crowley's iteration, cubically convergent
+    for _ in 0..3 {
+        if w == 0.0 {
+            break;
+        }
+        let r = x - w - w.ln();
+        let wp = 1.0 + w;
+        let t = wp * (wp + 2.0 * r / 3.0);
+        w *= 1.0 + r / wp * (t - 0.5 * r) / (t - r);
+    }
+
+    w
+}
@@ -0,0 +1,1332 @@
+//! Descriptive statistics over float slices and streams.
+
+use core::fmt;
+
+use collections::vec::Vec;
+
+use expansion::two_sum;
+use float::Float;
+
+
+/// Compact description of a buffer, see `summary`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FloatSummary<T> {
+    /// Number of elements, including NaNs.
+    pub len: usize,
+    /// Number of NaN elements.
+    pub nan_count: usize,
+    /// Smallest non-NaN element, NaN if there is none.
+    pub min: T,
+    /// Largest non-NaN element, NaN if there is none.
+    pub max: T,
+    /// Mean of the non-NaN elements, NaN if there is none.
+    pub mean: T,
+}
+
+impl<T: fmt::Display> fmt::Display for FloatSummary<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "n={} nan={} min={} max={} mean={}",
+               self.len, self.nan_count, self.min, self.max, self.mean)
+    }
+}
+
+
+/// Summarizes `values` in one pass for one-line diagnostics.
+///
+/// NaNs are counted and otherwise skipped. The mean uses a compensated sum of
+/// the finite elements, and infinities behave as in IEEE arithmetic: a single
+/// signed infinity wins and both together give NaN.
+///
+/// ```
+/// use float::stats;
+///
+/// let summary = stats::summary(&[2.0_f64, f64::NAN, -1.0, 3.0]);
+/// assert_eq!(summary.nan_count, 1);
+/// assert_eq!((summary.min, summary.max), (-1.0, 3.0));
+/// assert_eq!(summary.mean, 4.0 / 3.0);
+/// assert_eq!(format!("{}", stats::summary(&[0.5_f32, 1.5])), "n=2 nan=0 min=0.5 max=1.5 mean=1");
+///
+/// assert!(stats::summary::<f64>(&[]).mean.is_nan());
+/// assert_eq!(stats::summary(&[1.0, f64::INFINITY]).mean, f64::INFINITY);
+/// ```
+pub fn summary<T: Float>(values: &[T]) -> FloatSummary<T> {
+    let zero = T::from_f64(0.0);
+    let mut nan_count = 0;
+    let mut min = T::nan();
+    let mut max = T::nan();
+    let mut sum = zero;
+    let mut err = zero;
+    let mut positive_infinity = false;
+    let mut negative_infinity = false;
+
+    for &x in values {
+        if x.is_nan() {
+            nan_count += 1;
+            continue;
+        }
+        if !(x >= min) {
+            min = x;
+        }
+        if !(x <= max) {
+            max = x;
+        }
+
+        if x.is_infinite() {
+            if x > zero {
+                positive_infinity = true;
+            } else {
+                negative_infinity = true;
+            }
+        } else {
+            let (s, e) = two_sum(sum, x);
+            err = err + e;
+            sum = s;
+        }
+    }
+
+    let count = values.len() - nan_count;
+    let mean = if count == 0 || (positive_infinity && negative_infinity) {
+        T::nan()
+    } else if positive_infinity {
+        T::infinity()
+    } else if negative_infinity {
+        T::neg_infinity()
+    } else if sum.is_finite() {
+        (sum + err) / T::from_f64(count as f64)
+    } else {
+        sum
+    };
+
+    FloatSummary {
+        len: values.len(),
+        nan_count: nan_count,
+        min: min,
+        max: max,
+        mean: mean,
+    }
+}
+
+
+/// Overlapping Allan variance of fractional frequency (or rate) samples for
+/// the averaging time of `m` samples.
+///
+/// This is half the mean square difference between the averages of every
+/// pair of adjacent windows of `m` samples. The window sums slide along the
+/// data with compensated updates instead of going through the integrated
+/// phase, so a large constant offset, such as a gyro bias, does not swamp
+/// the fluctuations. NaN when `m` is zero or there are fewer than `2 m`
+/// samples.
+///
+/// ```
+/// use float::stats;
+///
+/// let rates: Vec<f64> = (0..100).map(|i| 1e12 + if i % 2 == 0 { 1.0 } else { -1.0 }).collect();
+/// assert_eq!(stats::allan_variance(&rates, 1), 2.0);
+/// assert_eq!(stats::allan_variance(&rates, 2), 0.0);
+/// assert!((stats::allan_deviation(&rates, 1) - 2.0_f64.powf(0.5)).abs() < 1e-15);
+/// assert!(stats::allan_variance(&rates[..3], 2).is_nan());
+/// ```
+pub fn allan_variance<T: Float>(rates: &[T], m: usize) -> T {
+    let n = rates.len();
+    if m == 0 || n < 2 * m {
+        return T::nan();
+    }
+
+    let two = T::from_f64(2.0);
+    let mut difference = Compensated::new();
+    for j in 0..m {
+        difference.add(rates[j + m]);
+        difference.add(-rates[j]);
+    }
+
+    let mut squares = Compensated::new();
+    for i in 0..n - 2 * m + 1 {
+        let d = difference.value();
+        squares.add(d * d);
+
+        if i + 2 * m < n {
+            difference.add(rates[i + 2 * m]);
+            difference.add(-two * rates[i + m]);
+            difference.add(rates[i]);
+        }
+    }
+
+    let count = (n - 2 * m + 1) as f64;
+    squares.value() / T::from_f64(2.0 * (m as f64) * (m as f64) * count)
+}
+
+/// The square root of `allan_variance`.
+#[inline]
+pub fn allan_deviation<T: Float>(rates: &[T], m: usize) -> T {
+    allan_variance(rates, m).sqrt()
+}
+
+/// Overlapping Allan variance of phase (or angle) samples taken every
+/// `tau0`, for the averaging time `m tau0`, from the second differences
+/// `x[i + 2m] - 2 x[i + m] + x[i]`. NaN when `m` is zero or there are fewer
+/// than `2 m + 1` samples.
+///
+/// ```
+/// use float::stats;
+///
+/// // the integral of alternating unit rates
+/// let phases: Vec<f64> = (0..101).map(|i| (i % 2) as f64).collect();
+/// assert_eq!(stats::allan_variance_phase(&phases, 1, 1.0), 2.0);
+/// assert_eq!(stats::allan_variance_phase(&phases, 2, 0.5), 0.0);
+/// ```
+pub fn allan_variance_phase<T: Float>(phases: &[T], m: usize, tau0: T) -> T {
+    let n = phases.len();
+    if m == 0 || n < 2 * m + 1 {
+        return T::nan();
+    }
+
+    let mut squares = Compensated::new();
+    for i in 0..n - 2 * m {
+        let d = (phases[i + 2 * m] - phases[i + m]) - (phases[i + m] - phases[i]);
+        squares.add(d * d);
+    }
+
+    let tau = T::from_f64(m as f64) * tau0;
+    let count = T::from_f64((n - 2 * m) as f64);
+    squares.value() / (T::from_f64(2.0) * tau * tau * count)
+}
+
+
+/// Streaming non-overlapping Allan variance of rate samples for a fixed
+/// averaging window of `m` samples, in constant memory.
+///
+/// ```
+/// use float::stats::AllanVariance;
+///
+/// let mut allan = AllanVariance::new(2);
+/// for &x in [3.0_f32, 1.0, 0.0, 2.0, 4.0, 2.0].iter() {
+///     allan.push(x);
+/// }
+/// // window averages 2, 1, 3
+/// assert_eq!(allan.pairs(), 2);
+/// assert_eq!(allan.variance(), (1.0 + 4.0) / 4.0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AllanVariance<T> {
+    m: usize,
+    window: Compensated<T>,
+    filled: usize,
+    previous: Option<T>,
+    squares: Compensated<T>,
+    pairs: usize,
+}
+
+impl<T: Float> AllanVariance<T> {
+    pub fn new(m: usize) -> Self {
+        assert!(m > 0, "Allan variance window must be non-zero");
+
+        AllanVariance {
+            m: m,
+            window: Compensated::new(),
+            filled: 0,
+            previous: None,
+            squares: Compensated::new(),
+            pairs: 0,
+        }
+    }
+
+    #[inline]
+    pub fn push(&mut self, rate: T) {
+        self.window.add(rate);
+        self.filled += 1;
+
+        if self.filled == self.m {
+            let average = self.window.value() / T::from_f64(self.m as f64);
+            if let Some(previous) = self.previous {
+                let d = average - previous;
+                self.squares.add(d * d);
+                self.pairs += 1;
+            }
+            self.previous = Some(average);
+            self.window = Compensated::new();
+            self.filled = 0;
+        }
+    }
+
+    /// The number of adjacent window pairs seen so far.
+    #[inline(always)]
+    pub fn pairs(&self) -> usize {
+        self.pairs
+    }
+
+    /// The variance so far, NaN before the second complete window.
+    #[inline]
+    pub fn variance(&self) -> T {
+        if self.pairs == 0 {
+            T::nan()
+        } else {
+            self.squares.value() / T::from_f64(2.0 * self.pairs as f64)
+        }
+    }
+
+    #[inline]
+    pub fn deviation(&self) -> T {
+        self.variance().sqrt()
+    }
+}
+
+
+/// The algorithm `variance_auto` settled on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VarianceMethod {
+    /// One pass of sums of `x - x[0]` and its square.
+    Shifted,
+    /// A compensated mean, then squared deviations from it with the
+    /// rounding error of the mean corrected for.
+    TwoPass,
+}
+
+/// A sample variance together with how it was computed, see `variance_auto`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VarianceEstimate<T> {
+    pub variance: T,
+    pub method: VarianceMethod,
+    /// Worst case bound on the relative error of `variance` from the
+    /// arithmetic, to first order.
+    pub relative_error: T,
+}
+
+
+/// Sample variance, with the `n - 1` denominator, computed by the cheapest
+/// algorithm whose error bound is good enough for the data.
+///
+/// The single-pass formula over data shifted by its first element is tried
+/// first. Its error grows like `n u k^2`, with `u` the unit roundoff and `k`
+/// the condition number `sqrt(1 + n (mean - x[0])^2 / S)` of the data about
+/// the shift, which is measured from the same sums. When that bound exceeds
+/// `sqrt(epsilon)` a second pass about the mean is made, whose error bound is
+/// `n u + (n u k)^2`. The bound of the method used is reported alongside.
+/// NaN with fewer than two values.
+///
+/// ```
+/// use float::stats::{self, VarianceMethod};
+///
+/// // a large offset alone is absorbed by the shift
+/// let clustered: Vec<f64> = (0..10).map(|i| 1e9 + i as f64).collect();
+/// let estimate = stats::variance_auto(&clustered);
+/// assert_eq!(estimate.method, VarianceMethod::Shifted);
+/// assert_eq!(estimate.variance, 82.5 / 9.0);
+/// assert!(estimate.relative_error < 1e-14);
+///
+/// // an outlier as the shift makes the one-pass bound too weak for f32
+/// let mut skewed = vec![1e4_f32];
+/// skewed.extend((1..1000).map(|i| (i % 2) as f32));
+/// let estimate = stats::variance_auto(&skewed);
+/// assert_eq!(estimate.method, VarianceMethod::TwoPass);
+///
+/// let values: Vec<f64> = skewed.iter().map(|&x| x as f64).collect();
+/// let mean = values.iter().sum::<f64>() / 1000.0;
+/// let exact = values.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / 999.0;
+/// assert!(((estimate.variance as f64 - exact) / exact).abs() < estimate.relative_error as f64);
+/// ```
+pub fn variance_auto<T: Float>(values: &[T]) -> VarianceEstimate<T> {
+    let zero = T::from_f64(0.0);
+    let one = T::from_f64(1.0);
+    let n = values.len();
+    if n < 2 {
+        return VarianceEstimate {
+            variance: T::nan(),
+            method: VarianceMethod::Shifted,
+            relative_error: T::nan(),
+        };
+    }
+
+    let count = T::from_f64(n as f64);
+    let unit_roundoff = T::epsilon() * T::from_f64(0.5);
+    let n_u = count * unit_roundoff;
+
+    let shift = values[0];
+    let mut sum = Compensated::new();
+    let mut squares = Compensated::new();
+    for &x in values {
+        let d = x - shift;
+        sum.add(d);
+        squares.add(d * d);
+    }
+    let offset = sum.value() / count;
+    let deviations = squares.value() - sum.value() * offset;
+
+    let condition = if deviations > zero {
+        one + count * offset * offset / deviations
+    } else if offset == zero {
+        one
+    } else {
+        T::infinity()
+    };
+    let bound = n_u * condition;
+    if bound <= T::epsilon().sqrt() {
+        return VarianceEstimate {
+            variance: if deviations > zero { deviations / (count - one) } else { zero },
+            method: VarianceMethod::Shifted,
+            relative_error: bound,
+        };
+    }
+
+    let mean = shift + offset;
+    let mut residual = Compensated::new();
+    let mut squares = Compensated::new();
+    for &x in values {
+        let d = x - mean;
+        residual.add(d);
+        squares.add(d * d);
+    }
+    let deviations = squares.value() - residual.value() * residual.value() / count;
+
+    // about the mean the condition number is one up to the rounding of the
+    // mean itself
+    let condition = one + count * (residual.value() / count) * (residual.value() / count) /
+        if deviations > zero { deviations } else { one };
+    VarianceEstimate {
+        variance: if deviations > zero { deviations / (count - one) } else { zero },
+        method: VarianceMethod::TwoPass,
+        relative_error: n_u + n_u * n_u * condition,
+    }
+}
+
+
+/// A straight line `y = slope x + intercept`, see `linear_regression`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LinearFit<T> {
+    pub slope: T,
+    pub intercept: T,
+}
+
+impl<T: Float> LinearFit<T> {
+    /// The value of the line at `x`.
+    #[inline(always)]
+    pub fn at(&self, x: T) -> T {
+        self.slope * x + self.intercept
+    }
+}
+
+
+/// Ordinary least squares line through the points `(xs[i], ys[i])`.
+///
+/// The means are taken first with compensated sums and the moments are
+/// accumulated about them, so abscissas far from zero, such as timestamps,
+/// do not cancel away the slope the way the textbook `n sum(xy) - sum(x)
+/// sum(y)` formula does. Both fields are NaN with fewer than two distinct
+/// `x`.
+///
+/// ```
+/// use float::stats;
+///
+/// let xs: Vec<f64> = (0..10).map(|i| 1e8 + i as f64).collect();
+/// let ys: Vec<f64> = xs.iter().map(|&x| 3.0 * x + 1.0).collect();
+/// let fit = stats::linear_regression(&xs, &ys);
+/// assert_eq!((fit.slope, fit.intercept), (3.0, 1.0));
+/// assert_eq!(fit.at(2.0), 7.0);
+///
+/// assert!(stats::linear_regression(&[1.0_f32, 1.0], &[0.0, 2.0]).slope.is_nan());
+/// ```
+pub fn linear_regression<T: Float>(xs: &[T], ys: &[T]) -> LinearFit<T> {
+    assert!(xs.len() == ys.len(), "regression needs as many x as y values");
+    regression(xs.iter().zip(ys.iter()).map(|(&x, &y)| (x, y, T::from_f64(1.0))))
+}
+
+/// Weighted least squares line through the points `(xs[i], ys[i])`, each
+/// counting `weights[i]` times, computed as `linear_regression`.
+///
+/// ```
+/// use float::stats;
+///
+/// // a weight of zero removes the outlier
+/// let fit = stats::weighted_linear_regression(&[0.0_f64, 1.0, 2.0, 3.0], &[1.0, 3.0, 100.0, 7.0], &[1.0, 1.0, 0.0, 1.0]);
+/// assert!((fit.slope - 2.0).abs() < 1e-15 && (fit.intercept - 1.0).abs() < 1e-15);
+/// ```
+pub fn weighted_linear_regression<T: Float>(xs: &[T], ys: &[T], weights: &[T]) -> LinearFit<T> {
+    assert!(xs.len() == ys.len() && xs.len() == weights.len(),
+            "regression needs as many x, y and weight values");
+    regression(xs.iter().zip(ys.iter()).zip(weights.iter()).map(|((&x, &y), &w)| (x, y, w)))
+}
+
+fn regression<T, I>(points: I) -> LinearFit<T>
+    where T: Float,
+          I: Iterator<Item = (T, T, T)> + Clone,
+{
+    let mut total = Compensated::new();
+    let mut sum_x = Compensated::new();
+    let mut sum_y = Compensated::new();
+    for (x, y, w) in points.clone() {
+        total.add(w);
+        sum_x.add(w * x);
+        sum_y.add(w * y);
+    }
+
+    let total = total.value();
+    let mean_x = sum_x.value() / total;
+    let mean_y = sum_y.value() / total;
+
+    let mut sxx = Compensated::new();
+    let mut sxy = Compensated::new();
+    for (x, y, w) in points {
+        let dx = x - mean_x;
+        sxx.add(w * dx * dx);
+        sxy.add(w * dx * (y - mean_y));
+    }
+
+    line(sxx.value(), sxy.value(), mean_x, mean_y)
+}
+
+#[inline]
+fn line<T: Float>(sxx: T, sxy: T, mean_x: T, mean_y: T) -> LinearFit<T> {
+    if sxx > T::from_f64(0.0) {
+        let slope = sxy / sxx;
+        LinearFit {
+            slope: slope,
+            intercept: mean_y - slope * mean_x,
+        }
+    } else {
+        LinearFit {
+            slope: T::nan(),
+            intercept: T::nan(),
+        }
+    }
+}
+
+
+/// Streaming weighted least squares line, updated one point at a time with
+/// West's running means and centred moments, which stay accurate where
+/// running sums of `x^2` and `x y` cancel. The points are also shifted by the
+/// first one, so the running means stay small next to large offsets.
+///
+/// ```
+/// use float::stats::OnlineRegression;
+///
+/// let mut trend = OnlineRegression::new();
+/// for i in 0..1000 {
+///     let t = 1e6_f64 + i as f64 * 0.001;
+///     trend.push(t, 0.5 * (t - 1e6) - 2.0);
+/// }
+/// let fit = trend.fit();
+/// assert!((fit.slope - 0.5).abs() < 1e-12);
+/// assert!((fit.at(1e6) + 2.0).abs() < 1e-9);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OnlineRegression<T> {
+    origin_x: T,
+    origin_y: T,
+    weight: T,
+    mean_x: T,
+    mean_y: T,
+    sxx: T,
+    sxy: T,
+}
+
+impl<T: Float> OnlineRegression<T> {
+    #[inline]
+    pub fn new() -> Self {
+        let zero = T::from_f64(0.0);
+        OnlineRegression {
+            origin_x: zero,
+            origin_y: zero,
+            weight: zero,
+            mean_x: zero,
+            mean_y: zero,
+            sxx: zero,
+            sxy: zero,
+        }
+    }
+
+    #[inline(always)]
+    pub fn push(&mut self, x: T, y: T) {
+        self.push_weighted(x, y, T::from_f64(1.0));
+    }
+
+    /// Adds a point counting `weight` times. Non-positive weights are
+    /// ignored.
+    #[inline]
+    pub fn push_weighted(&mut self, x: T, y: T, weight: T) {
+        let zero = T::from_f64(0.0);
+        if !(weight > zero) {
+            return;
+        }
+        if self.weight == zero {
+            self.origin_x = x;
+            self.origin_y = y;
+        }
+        let x = x - self.origin_x;
+        let y = y - self.origin_y;
+        self.weight = self.weight + weight;
+
+        let fraction = weight / self.weight;
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x = self.mean_x + dx * fraction;
+        self.mean_y = self.mean_y + dy * fraction;
+
+        self.sxx = self.sxx + weight * dx * (x - self.mean_x);
+        self.sxy = self.sxy + weight * dx * (y - self.mean_y);
+    }
+
+    /// The total weight of the points seen.
+    #[inline(always)]
+    pub fn weight(&self) -> T {
+        self.weight
+    }
+
+    /// The line through the points seen so far, NaN before two distinct `x`.
+    #[inline]
+    pub fn fit(&self) -> LinearFit<T> {
+        let shifted = line(self.sxx, self.sxy, self.mean_x, self.mean_y);
+        LinearFit {
+            slope: shifted.slope,
+            intercept: self.origin_y + (shifted.intercept - shifted.slope * self.origin_x),
+        }
+    }
+}
+
+impl<T: Float> Default for OnlineRegression<T> {
+    #[inline(always)]
+    fn default() -> Self {
+        OnlineRegression::new()
+    }
+}
+
+
+/// Theil's estimator of a robust line through `(xs[i], ys[i])`: the median
+/// of the slopes between the points `i` and `i + n / 2`, and the median of
+/// the intercepts `y - slope x`.
+///
+/// The slopes are gathered into `scratch`, so memory stays bounded: when it
+/// holds fewer than `n / 2` values an evenly strided subset of the pairs is
+/// used, trading accuracy for space. Pairs with equal `x` and NaN slopes are
+/// skipped. An outlier spoils at most the one pair it is in, so the slope
+/// survives up to a quarter of the points being arbitrary outliers, short of
+/// the 29 percent of the estimator over all pairs. Both fields are NaN when
+/// no pair is usable.
+///
+/// ```
+/// use float::stats;
+///
+/// let xs: Vec<f64> = (0..20).map(|i| i as f64).collect();
+/// let mut ys: Vec<f64> = xs.iter().map(|&x| 2.0 * x + 1.0).collect();
+/// for i in (0..20).filter(|i| i % 5 == 0) {
+///     ys[i] = 1000.0;
+/// }
+///
+/// let mut scratch = [0.0; 10];
+/// let fit = stats::theil_sen(&xs, &ys, &mut scratch);
+/// assert_eq!((fit.slope, fit.intercept), (2.0, 1.0));
+/// assert!(stats::linear_regression(&xs, &ys).intercept > 100.0);
+/// ```
+pub fn theil_sen<T: Float>(xs: &[T], ys: &[T], scratch: &mut [T]) -> LinearFit<T> {
+    assert!(xs.len() == ys.len(), "regression needs as many x as y values");
+    assert!(!scratch.is_empty(), "scratch buffer must not be empty");
+
+    let n = xs.len();
+    let half = n / 2;
+    let pairs = strided(half, scratch.len());
+
+    let mut count = 0;
+    for j in 0..pairs {
+        let i = sample_index(j, pairs, half);
+        let dx = xs[i + half] - xs[i];
+        let slope = (ys[i + half] - ys[i]) / dx;
+        if dx != T::from_f64(0.0) && !slope.is_nan() {
+            scratch[count] = slope;
+            count += 1;
+        }
+    }
+    if count == 0 {
+        return LinearFit {
+            slope: T::nan(),
+            intercept: T::nan(),
+        };
+    }
+    let slope = median_inplace(&mut scratch[..count]);
+
+    let points = strided(n, scratch.len());
+    for (j, residual) in scratch[..points].iter_mut().enumerate() {
+        let i = sample_index(j, points, n);
+        *residual = ys[i] - slope * xs[i];
+    }
+
+    LinearFit {
+        slope: slope,
+        intercept: median_inplace(&mut scratch[..points]),
+    }
+}
+
+/// Huber regression: the line minimizing the Huber loss of the residuals,
+/// quadratic within `delta` robust standard deviations and linear beyond,
+/// by iteratively reweighted least squares started from `theil_sen`.
+///
+/// The residual scale is re-estimated every iteration as `1.4826` times the
+/// median absolute residual over at most `scratch.len()` strided points.
+/// It stops after `iterations` rounds or once the fit no longer changes.
+/// `delta = 1.345` is the usual choice, with 95% efficiency on clean
+/// Gaussian data.
+///
+/// ```
+/// use float::stats;
+///
+/// let xs: Vec<f64> = (0..50).map(|i| i as f64 * 0.1).collect();
+/// let mut ys: Vec<f64> = xs.iter().enumerate()
+///     .map(|(i, &x)| 0.5 - 3.0 * x + if i % 2 == 0 { 0.01 } else { -0.01 })
+///     .collect();
+/// ys[7] = 40.0;
+/// ys[31] = -60.0;
+///
+/// let mut scratch = [0.0; 50];
+/// let fit = stats::huber_regression(&xs, &ys, 1.345, 20, &mut scratch);
+/// assert!((fit.slope + 3.0).abs() < 0.01);
+/// assert!((fit.intercept - 0.5).abs() < 0.01);
+/// ```
+pub fn huber_regression<T: Float>(xs: &[T], ys: &[T], delta: T, iterations: usize, scratch: &mut [T]) -> LinearFit<T> {
+    let mut fit = theil_sen(xs, ys, scratch);
+    if fit.slope.is_nan() {
+        return fit;
+    }
+
+    let n = xs.len();
+    let one = T::from_f64(1.0);
+    let points = strided(n, scratch.len());
+
+    for _ in 0..iterations {
+        for (j, residual) in scratch[..points].iter_mut().enumerate() {
+            let i = sample_index(j, points, n);
+            *residual = (ys[i] - fit.at(xs[i])).abs();
+        }
+        let threshold = delta * T::from_f64(1.4826) * median_inplace(&mut scratch[..points]);
+        if !(threshold > T::from_f64(0.0)) {
+            break;
+        }
+
+        let current = fit;
+        let next = regression(xs.iter().zip(ys.iter()).map(move |(&x, &y)| {
+            let residual = (y - current.at(x)).abs();
+            let weight = if residual <= threshold { one } else { threshold / residual };
+            (x, y, weight)
+        }));
+        if next.slope.is_nan() || next == fit {
+            break;
+        }
+        fit = next;
+    }
+    fit
+}
+
+/// The number of evenly strided samples out of `len` that fit in `capacity`.
+#[inline(always)]
+fn strided(len: usize, capacity: usize) -> usize {
+    if len < capacity { len } else { capacity }
+}
+
+#[inline(always)]
+fn sample_index(j: usize, samples: usize, len: usize) -> usize {
+    ((j as u64 * len as u64) / samples as u64) as usize
+}
+
+/// The median of `values`, which are reordered, averaging the two middle
+/// values for an even length. There must be no NaN.
+fn median_inplace<T: Float>(values: &mut [T]) -> T {
+    let n = values.len();
+    let middle = n / 2;
+    select_inplace(values, middle);
+    let upper = values[middle];
+
+    if n % 2 == 1 {
+        upper
+    } else {
+        let lower = values[..middle].iter().fold(values[0], |max, &x| if x > max { x } else { max });
+        lower + (upper - lower) * T::from_f64(0.5)
+    }
+}
+
+/// Quickselect: moves the `k`-th smallest value to `values[k]`, smaller ones
+/// before it and larger ones after.
+fn select_inplace<T: Float>(values: &mut [T], k: usize) {
+    let mut low = 0;
+    let mut high = values.len() - 1;
+
+    while low < high {
+        // median of three pivot, moved to the end
+        let middle = low + (high - low) / 2;
+        if values[middle] < values[low] {
+            values.swap(middle, low);
+        }
+        if values[high] < values[low] {
+            values.swap(high, low);
+        }
+        if values[middle] < values[high] {
+            values.swap(middle, high);
+        }
+        let pivot = values[high];
+
+        let mut store = low;
+        for i in low..high {
+            if values[i] < pivot {
+                values.swap(i, store);
+                store += 1;
+            }
+        }
+        values.swap(store, high);
+
+        if store == k {
+            return;
+        } else if store < k {
+            low = store + 1;
+        } else {
+            high = store - 1;
+        }
+    }
+}
+
+
+/// Counts of values in equal width bins over `[min, max]`, with the values
+/// outside the range and the NaNs counted separately.
+///
+/// ```
+/// use float::stats::Histogram;
+///
+/// let mut histogram = Histogram::new(0.0_f64, 4.0, 4);
+/// for &x in [0.5, 1.5, 1.7, 4.0, 9.0, f64::NAN].iter() {
+///     histogram.push(x);
+/// }
+/// assert_eq!(histogram.counts(), &[1, 2, 0, 1]);
+/// assert_eq!((histogram.below(), histogram.above(), histogram.nan_count()), (0, 1, 1));
+/// assert_eq!(histogram.cdf(2.0), 0.6);
+/// assert_eq!(histogram.cdf(1.5), 0.4);
+///
+/// // a range wider than the largest float
+/// let mut histogram = Histogram::new(-f64::MAX, f64::MAX, 4);
+/// for &x in [-1e308, -5e307, 1.0, f64::MAX].iter() {
+///     histogram.push(x);
+/// }
+/// assert_eq!(histogram.counts(), &[1, 1, 1, 1]);
+/// assert_eq!(histogram.cdf(0.0), 0.5);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram<T> {
+    min: T,
+    max: T,
+    counts: Vec<u64>,
+    below: u64,
+    above: u64,
+    nan_count: u64,
+}
+
+impl<T: Float> Histogram<T> {
+    /// An empty histogram of `bins` bins over `[min, max]`, the last bin
+    /// including `max`.
+    pub fn new(min: T, max: T, bins: usize) -> Self {
+        assert!(min.is_finite() && max.is_finite() && min < max, "histogram range must be finite and non-empty");
+        assert!(bins > 0, "histogram needs at least one bin");
+
+        Histogram {
+            min: min,
+            max: max,
+            counts: (0..bins).map(|_| 0).collect(),
+            below: 0,
+            above: 0,
+            nan_count: 0,
+        }
+    }
+
+    /// The histogram of `values` over the range of their finite elements,
+    /// `None` unless there are two distinct ones.
+    pub fn from_values(values: &[T], bins: usize) -> Option<Self> {
+        let mut min = T::infinity();
+        let mut max = T::neg_infinity();
+        for &x in values.iter().filter(|x| x.is_finite()) {
+            if x < min {
+                min = x;
+            }
+            if x > max {
+                max = x;
+            }
+        }
+        if !(min < max) {
+            return None;
+        }
+
+        let mut histogram = Histogram::new(min, max, bins);
+        for &x in values {
+            histogram.push(x);
+        }
+        Some(histogram)
+    }
+
+    #[inline]
+    pub fn push(&mut self, x: T) {
+        if x.is_nan() {
+            self.nan_count += 1;
+        } else if x < self.min {
+            self.below += 1;
+        } else if x > self.max {
+            self.above += 1;
+        } else {
+            let (bin, _) = self.position(x);
+            self.counts[bin] += 1;
+        }
+    }
+
+    #[inline(always)]
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    #[inline(always)]
+    pub fn below(&self) -> u64 {
+        self.below
+    }
+
+    #[inline(always)]
+    pub fn above(&self) -> u64 {
+        self.above
+    }
+
+    #[inline(always)]
+    pub fn nan_count(&self) -> u64 {
+        self.nan_count
+    }
+
+    /// The number of values counted, NaNs excluded.
+    #[inline]
+    pub fn total(&self) -> u64 {
+        self.counts.iter().fold(self.below + self.above, |sum, &c| sum + c)
+    }
+
+    /// The bin `x` falls in, `None` outside the range.
+    #[inline]
+    pub fn bin(&self, x: T) -> Option<usize> {
+        if x >= self.min && x <= self.max { Some(self.position(x).0) } else { None }
+    }
+
+    /// The empirical distribution function at `x`: the fraction of the
+    /// counted values below it, taking the values of a bin as spread evenly
+    /// across it. NaN for an empty histogram.
+    pub fn cdf(&self, x: T) -> T {
+        let total = self.total();
+        if total == 0 || x.is_nan() {
+            return T::nan();
+        }
+
+        let below = if x < self.min {
+            0.0
+        } else if x >= self.max {
+            (total - self.above) as f64 + if x > self.max { self.above as f64 } else { 0.0 }
+        } else {
+            let (bin, fraction) = self.position(x);
+            let full = self.counts[..bin].iter().fold(self.below, |sum, &c| sum + c);
+            full as f64 + self.counts[bin] as f64 * fraction.to_f64()
+        };
+        T::from_f64(below / total as f64)
+    }
+
+    /// The bin of an `x` in the range and the position inside it, in
+    /// `[0, 1]`.
+    #[inline]
+    fn position(&self, x: T) -> (usize, T) {
+        let bins = self.counts.len();
+        let mut width = self.max - self.min;
+        let mut offset = x - self.min;
+        if !width.is_finite() {
+            // a range wider than the largest float fits in halves
+            let half = T::from_f64(0.5);
+            width = self.max * half - self.min * half;
+            offset = x * half - self.min * half;
+        }
+        let scaled = offset / width * T::from_f64(bins as f64);
+        let bin = scaled.floor().to_f64() as usize;
+        if bin >= bins {
+            (bins - 1, T::from_f64(1.0))
+        } else {
+            (bin, scaled - T::from_f64(bin as f64))
+        }
+    }
+}
+
+
+/// Histogram equalization: replaces every value by the empirical
+/// distribution function of `values` at it, from a histogram of `bins`
+/// bins, spreading them approximately uniformly over `[0, 1]` while keeping
+/// their order. NaNs are left in place; if there are fewer than two distinct
+/// finite values nothing changes.
+///
+/// ```
+/// use float::stats;
+///
+/// let mut values: Vec<f64> = (0..1000).map(|i| (i as f64).powi(2)).collect();
+/// stats::equalize_inplace(&mut values, 10000);
+/// for (i, &x) in values.iter().enumerate() {
+///     assert!((x - i as f64 / 1000.0).abs() < 0.01);
+/// }
+/// ```
+pub fn equalize_inplace<T: Float>(values: &mut [T], bins: usize) {
+    if let Some(histogram) = Histogram::from_values(values, bins) {
+        for x in values.iter_mut() {
+            if !x.is_nan() {
+                *x = histogram.cdf(*x);
+            }
+        }
+    }
+}
+
+
+/// How the paired statistics treat a pair with a NaN on either side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NanHandling {
+    /// Any NaN makes the result NaN.
+    Propagate,
+    /// Pairs containing a NaN are left out.
+    Omit,
+    /// Panic on the first NaN.
+    Panic,
+}
+
+impl Default for NanHandling {
+    #[inline(always)]
+    fn default() -> Self {
+        NanHandling::Propagate
+    }
+}
+
+
+/// Sample covariance of the pairs `(xs[i], ys[i])`, with the `n - 1`
+/// denominator and moments taken about the means. NaN with fewer than two
+/// pairs.
+///
+/// ```
+/// use float::stats::{self, NanHandling};
+///
+/// let xs = [1.0_f64, 2.0, 3.0, f64::NAN];
+/// let ys = [2.0_f64, 4.0, 6.0, 8.0];
+/// assert!(stats::covariance(&xs, &ys, NanHandling::Propagate).is_nan());
+/// assert_eq!(stats::covariance(&xs, &ys, NanHandling::Omit), 2.0);
+/// ```
+pub fn covariance<T: Float>(xs: &[T], ys: &[T], nan: NanHandling) -> T {
+    match paired_moments(xs, ys, nan) {
+        Some(moments) if moments.count >= 2.0 => moments.sxy / T::from_f64(moments.count - 1.0),
+        _ => T::nan(),
+    }
+}
+
+/// Pearson's correlation coefficient of the pairs `(xs[i], ys[i])`, in
+/// `[-1, 1]`. NaN with fewer than two pairs or when either side is constant.
+///
+/// ```
+/// use float::stats::{self, NanHandling};
+///
+/// let xs = [1e9_f64 + 1.0, 1e9 + 2.0, 1e9 + 3.0, 1e9 + 4.0];
+/// let ys = [4.0_f64, 3.0, 2.0, 1.0];
+/// assert_eq!(stats::pearson(&xs, &ys, NanHandling::Propagate), -1.0);
+/// assert!(stats::pearson(&[1.0_f32, 1.0], &[1.0, 2.0], NanHandling::Omit).is_nan());
+/// ```
+pub fn pearson<T: Float>(xs: &[T], ys: &[T], nan: NanHandling) -> T {
+    match paired_moments(xs, ys, nan) {
+        Some(moments) if moments.count >= 2.0 => correlation(moments.sxx, moments.syy, moments.sxy),
+        _ => T::nan(),
+    }
+}
+
+/// Spearman's rank correlation of the pairs `(xs[i], ys[i])`: Pearson's
+/// correlation of their ranks, with tied values sharing the average of
+/// their ranks.
+///
+/// ```
+/// use float::stats::{self, NanHandling};
+///
+/// // any increasing relation has a rank correlation of one
+/// let xs = [0.5_f64, 2.0, 1.0, 8.0, f64::NAN];
+/// let ys: Vec<f64> = xs.iter().map(|&x| x.exp()).collect();
+/// assert_eq!(stats::spearman(&xs, &ys, NanHandling::Omit), 1.0);
+///
+/// let ties = stats::spearman(&[1.0_f64, 2.0, 2.0, 3.0], &[1.0, 3.0, 2.0, 4.0], NanHandling::Panic);
+/// assert!((ties - 0.9486832980505138).abs() < 1e-15);
+/// ```
+pub fn spearman<T: Float>(xs: &[T], ys: &[T], nan: NanHandling) -> T {
+    assert!(xs.len() == ys.len(), "correlation needs as many x as y values");
+
+    let mut valid = Vec::with_capacity(xs.len());
+    for (i, (&x, &y)) in xs.iter().zip(ys.iter()).enumerate() {
+        if x.is_nan() || y.is_nan() {
+            match nan {
+                NanHandling::Propagate => return T::nan(),
+                NanHandling::Omit => continue,
+                NanHandling::Panic => panic!("NaN in correlation input at index {}", i),
+            }
+        }
+        valid.push(i);
+    }
+
+    let x_ranks = ranks(xs, &valid);
+    let y_ranks = ranks(ys, &valid);
+    pearson(&x_ranks, &y_ranks, NanHandling::Propagate)
+}
+
+
+/// Pearson's correlation over a sliding window of the last `window_len`
+/// pairs, leaving out pairs that contain a NaN.
+///
+/// The window sums are kept about the window means as of the last
+/// refresh, and refreshed from the buffered pairs every `window_len`
+/// samples, so neither offsets nor a long run of updates cancel them away.
+///
+/// ```
+/// use float::stats::RollingCorrelation;
+///
+/// let mut rolling = RollingCorrelation::new(4);
+/// for i in 0..8 {
+///     let x = 1e6_f64 + i as f64;
+///     rolling.push(x, if i < 4 { x } else { -x });
+/// }
+/// assert_eq!(rolling.correlation(), -1.0);
+///
+/// rolling.push(f64::NAN, 0.0);
+/// assert_eq!(rolling.len(), 3);
+/// assert!(!rolling.is_empty());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RollingCorrelation<T> {
+    pairs: Vec<(T, T)>,
+    index: usize,
+    filled: usize,
+    count: usize,
+    shift: (T, T),
+    sums: [T; 5],
+}
+
+impl<T: Float> RollingCorrelation<T> {
+    pub fn new(window_len: usize) -> Self {
+        assert!(window_len > 0, "correlation window length must be non-zero");
+
+        let zero = T::from_f64(0.0);
+        RollingCorrelation {
+            pairs: (0..window_len).map(|_| (T::nan(), T::nan())).collect(),
+            index: 0,
+            filled: 0,
+            count: 0,
+            shift: (zero, zero),
+            sums: [zero; 5],
+        }
+    }
+
+    #[inline(always)]
+    pub fn window_len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// The number of pairs without NaN in the window.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Whether the window holds no pair without NaN.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Adds a pair, dropping the oldest once the window is full.
+    pub fn push(&mut self, x: T, y: T) {
+        let (old_x, old_y) = self.pairs[self.index];
+        if !(old_x.is_nan() || old_y.is_nan()) {
+            self.accumulate(old_x, old_y, -T::from_f64(1.0));
+            self.count -= 1;
+        }
+        if !(x.is_nan() || y.is_nan()) {
+            self.accumulate(x, y, T::from_f64(1.0));
+            self.count += 1;
+        }
+        self.pairs[self.index] = (x, y);
+
+        self.index += 1;
+        if self.filled < self.pairs.len() {
+            self.filled += 1;
+        }
+        if self.index == self.pairs.len() {
+            self.index = 0;
+            self.refresh();
+        }
+    }
+
+    /// The correlation of the pairs in the window, NaN with fewer than two
+    /// pairs or when either side is constant.
+    pub fn correlation(&self) -> T {
+        if self.count < 2 {
+            return T::nan();
+        }
+        let n = T::from_f64(self.count as f64);
+        let sums = &self.sums;
+        correlation(sums[2] - sums[0] * sums[0] / n,
+                    sums[3] - sums[1] * sums[1] / n,
+                    sums[4] - sums[0] * sums[1] / n)
+    }
+
+    #[inline]
+    fn accumulate(&mut self, x: T, y: T, sign: T) {
+        let dx = x - self.shift.0;
+        let dy = y - self.shift.1;
+        self.sums[0] = self.sums[0] + sign * dx;
+        self.sums[1] = self.sums[1] + sign * dy;
+        self.sums[2] = self.sums[2] + sign * dx * dx;
+        self.sums[3] = self.sums[3] + sign * dy * dy;
+        self.sums[4] = self.sums[4] + sign * dx * dy;
+    }
+
+    /// Recomputes the sums about the current window means.
+    fn refresh(&mut self) {
+        let zero = T::from_f64(0.0);
+        self.sums = [zero; 5];
+        self.shift = (zero, zero);
+        if self.count == 0 {
+            return;
+        }
+
+        let (mut mean_x, mut mean_y) = (Compensated::new(), Compensated::new());
+        for &(x, y) in self.pairs[..self.filled].iter() {
+            if !(x.is_nan() || y.is_nan()) {
+                mean_x.add(x);
+                mean_y.add(y);
+            }
+        }
+        let n = T::from_f64(self.count as f64);
+        self.shift = (mean_x.value() / n, mean_y.value() / n);
+
+        for i in 0..self.filled {
+            let (x, y) = self.pairs[i];
+            if !(x.is_nan() || y.is_nan()) {
+                self.accumulate(x, y, T::from_f64(1.0));
+            }
+        }
+    }
+}
+
+
+struct Moments<T> {
+    count: f64,
+    sxx: T,
+    syy: T,
+    sxy: T,
+}
+
+/// Centred second moments of the pairs, or `None` when a NaN is propagated.
+fn paired_moments<T: Float>(xs: &[T], ys: &[T], nan: NanHandling) -> Option<Moments<T>> {
+    assert!(xs.len() == ys.len(), "correlation needs as many x as y values");
+
+    let mut count = 0_usize;
+    let mut sum_x = Compensated::new();
+    let mut sum_y = Compensated::new();
+    for (i, (&x, &y)) in xs.iter().zip(ys.iter()).enumerate() {
+        if x.is_nan() || y.is_nan() {
+            match nan {
+                NanHandling::Propagate => return None,
+                NanHandling::Omit => continue,
+                NanHandling::Panic => panic!("NaN in correlation input at index {}", i),
+            }
+        }
+        count += 1;
+        sum_x.add(x);
+        sum_y.add(y);
+    }
+    if count == 0 {
+        return None;
+    }
+
+    let n = T::from_f64(count as f64);
+    let mean_x = sum_x.value() / n;
+    let mean_y = sum_y.value() / n;
+
+    let mut sxx = Compensated::new();
+    let mut syy = Compensated::new();
+    let mut sxy = Compensated::new();
+    for (&x, &y) in xs.iter().zip(ys.iter()) {
+        if x.is_nan() || y.is_nan() {
+            continue;
+        }
+        let dx = x - mean_x;
+        let dy = y - mean_y;
+        sxx.add(dx * dx);
+        syy.add(dy * dy);
+        sxy.add(dx * dy);
+    }
+
+    Some(Moments {
+        count: count as f64,
+        sxx: sxx.value(),
+        syy: syy.value(),
+        sxy: sxy.value(),
+    })
+}
+
+#[inline]
+fn correlation<T: Float>(sxx: T, syy: T, sxy: T) -> T {
+    let zero = T::from_f64(0.0);
+    let one = T::from_f64(1.0);
+    if !(sxx > zero && syy > zero) {
+        return T::nan();
+    }
+
+    // a single square root keeps perfectly correlated data at exactly one,
+    // the product only leaves the normal range at extreme scales
+    let product = sxx * syy;
+    let scale = if product.is_normal() {
+        product.sqrt()
+    } else {
+        sxx.sqrt() * syy.sqrt()
+    };
+    let r = sxy / scale;
+    if r > one { one } else if r < -one { -one } else { r }
+}
+
+/// The ranks, from one, of `values[i]` for the indices in `valid`, ties
+/// sharing the average of their ranks.
+fn ranks<T: Float>(values: &[T], valid: &[usize]) -> Vec<T> {
+    let mut order: Vec<usize> = (0..valid.len()).collect();
+    order.sort_by(|&a, &b| values[valid[a]].partial_cmp(&values[valid[b]]).unwrap());
+
+    let mut ranks: Vec<T> = (0..valid.len()).map(|_| T::from_f64(0.0)).collect();
+    let mut start = 0;
+    while start < order.len() {
+        let value = values[valid[order[start]]];
+        let mut end = start + 1;
+        while end < order.len() && values[valid[order[end]]] == value {
+            end += 1;
+        }
+
+        let rank = T::from_f64((start + end + 1) as f64 * 0.5);
+        for &position in order[start..end].iter() {
+            ranks[position] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+
+/// A sum with the rounding error of every addition kept in a second term.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Compensated<T> {
+    sum: T,
+    error: T,
+}
+
+impl<T: Float> Compensated<T> {
+    #[inline(always)]
+    fn new() -> Self {
+        Compensated {
+            sum: T::from_f64(0.0),
+            error: T::from_f64(0.0),
+        }
+    }
+
+    #[inline(always)]
+    fn add(&mut self, x: T) {
+        let (sum, error) = two_sum(self.sum, x);
+        self.sum = sum;
+        self.error = self.error + error;
+    }
+
+    #[inline(always)]
+    fn value(&self) -> T {
+        self.sum + self.error
+    }
+}
@@ -0,0 +1,40 @@
+//! Workarounds for the math libraries of particular targets, which `Float`
+//! calls in place of the missing or broken functions. They build on every
+//! target, so they are tested everywhere, but only their own target uses
+//! them.
+//!
+//! ```
+//! use float::{self, Float};
+//!
+//! assert_eq!(Float::log2(&8.0_f32), 3.0);
+//! assert_eq!(Float::log2(&0.1_f32), -3.321928);
+//! assert_eq!(Float::log2(&1.0e-45_f32), -149.0);
+//! assert_eq!(Float::log2(&0.0_f32), ::std::f32::NEG_INFINITY);
+//! assert!(float::nan_tolerant(|| Float::log2(&-1.0_f32)).is_nan());
+//! ```
+
+
+/// Android's libm has `log2f` only from API level 18.
+pub mod android {
+    use core::f64::consts::LN_2;
+
+    use math;
+
+
+    /// `log2` through the natural logarithm in `f64`, whose error the
+    /// rounding to `f32` hides.
+    ///
+    /// ```
+    /// use float::sys::android::log2f32;
+    ///
+    /// assert_eq!(log2f32(8.0), 3.0);
+    /// assert_eq!(log2f32(0.1), -3.321928);
+    /// assert_eq!(log2f32(1.0e-45), -149.0);
+    /// assert_eq!(log2f32(f32::INFINITY), f32::INFINITY);
+    /// assert!(log2f32(-1.0).is_nan());
+    /// ```
+    #[inline]
+    pub fn log2f32(x: f32) -> f32 {
+        (unsafe { math::log(x as f64) } / LN_2) as f32
+    }
+}
@@ -0,0 +1,331 @@
+//! Validation of the active backend against Berkeley TestFloat and IBM FPgen
+//! test vectors.
+//!
+//! Only results are compared, the exception flags of the vectors are ignored
+//! since the crate can not observe them. Any NaN matches any NaN, everything
+//! else must match bit for bit, so the sign of zero counts. Vectors for widths,
+//! operations or rounding modes the crate does not provide are skipped.
+
+use core::fmt;
+
+use collections::vec::Vec;
+
+use float::Float;
+
+
+/// Operations a vector file can exercise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    /// Quiet equality, giving `1` or `0`.
+    Eq,
+    /// Signaling less than or equal, giving `1` or `0`.
+    Le,
+    /// Signaling less than, giving `1` or `0`.
+    Lt,
+}
+
+impl Operation {
+    fn is_comparison(&self) -> bool {
+        matches!(*self, Operation::Eq | Operation::Le | Operation::Lt)
+    }
+
+    fn apply<T: Float>(&self, a: T, b: T) -> u64 {
+        match *self {
+            Operation::Add => encode(a + b),
+            Operation::Sub => encode(a - b),
+            Operation::Mul => encode(a * b),
+            Operation::Div => encode(a / b),
+            Operation::Eq => (a == b) as u64,
+            Operation::Le => (a <= b) as u64,
+            Operation::Lt => (a < b) as u64,
+        }
+    }
+}
+
+
+/// A vector whose result differed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Mismatch {
+    /// One based line number in the vector text.
+    pub line: usize,
+    /// Expected result, as bits or as `1`/`0` for comparisons.
+    pub expected: u64,
+    /// Result of the backend.
+    pub actual: u64,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: expected {:#x}, got {:#x}", self.line, self.expected, self.actual)
+    }
+}
+
+
+/// Outcome of running a vector file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VectorReport {
+    pub passed: usize,
+    pub skipped: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl VectorReport {
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    fn record(&mut self, line: usize, is_nan_result: bool, expected: u64, actual: u64, actual_is_nan: bool) {
+        if expected == actual || (is_nan_result && actual_is_nan) {
+            self.passed += 1;
+        } else {
+            self.mismatches.push(Mismatch {
+                line: line,
+                expected: expected,
+                actual: actual,
+            });
+        }
+    }
+}
+
+impl fmt::Display for VectorReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} passed, {} failed, {} skipped",
+               self.passed, self.mismatches.len(), self.skipped)?;
+        for mismatch in self.mismatches.iter() {
+            write!(f, "\n{}", mismatch)?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Reasons a vector file can not be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VectorError {
+    /// The line with the given one based number is malformed.
+    Syntax(usize),
+}
+
+impl fmt::Display for VectorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VectorError::Syntax(line) => write!(f, "malformed test vector on line {}", line),
+        }
+    }
+}
+
+
+/// Runs TestFloat vectors, as written by `testfloat_gen`, for `operation`
+/// on `T`.
+///
+/// Each line holds the operands and the expected result in hexadecimal,
+/// optionally followed by the flags. Blank lines and lines starting with `#`
+/// are ignored.
+///
+/// ```
+/// use float::test_vectors::{self, Operation};
+///
+/// let vectors = "\
+/// 3FF0000000000000 3FF0000000000000 4000000000000000 00
+/// 7FEFFFFFFFFFFFFF 7FEFFFFFFFFFFFFF 7FF0000000000000 05
+/// 8000000000000000 0000000000000000 0000000000000000 00
+/// 7FF0000000000000 FFF0000000000000 7FF8000000000000 10
+/// ";
+/// let report = test_vectors::run_testfloat::<f64>(Operation::Add, vectors).unwrap();
+/// assert!(report.is_success(), "{}", report);
+/// assert_eq!(report.passed, 4);
+///
+/// let report = test_vectors::run_testfloat::<f32>(Operation::Lt, "3F800000 40000000 1 00").unwrap();
+/// assert_eq!(report.passed, 1);
+/// ```
+pub fn run_testfloat<T: Float>(operation: Operation, vectors: &str) -> Result<VectorReport, VectorError> {
+    let mut report = VectorReport::default();
+
+    for (index, line) in vectors.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace().map(|field| u64::from_str_radix(field, 16));
+        let a = fields.next();
+        let b = fields.next();
+        let expected = fields.next();
+
+        match (a, b, expected) {
+            (Some(Ok(a)), Some(Ok(b)), Some(Ok(expected))) => {
+                let actual = operation.apply(decode::<T>(a), decode::<T>(b));
+                let nan_result = !operation.is_comparison() && decode::<T>(expected).is_nan();
+                let actual_is_nan = !operation.is_comparison() && decode::<T>(actual).is_nan();
+                report.record(number, nan_result, expected, actual, actual_is_nan);
+            },
+            _ => return Err(VectorError::Syntax(number)),
+        }
+    }
+
+    Ok(report)
+}
+
+
+/// Runs IBM FPgen vectors for `f32` (`b32`) and `f64` (`b64`).
+///
+/// Lines look like `b64+ =0 +1.0000000000000P0 -1.8000000000000P-1 -> +1.0000000000000P-1`,
+/// with operands written as `1.fraction` or `0.fraction` in hexadecimal, a
+/// binary exponent after `P`, or as `Inf`, `Zero`, `Q` and `S`. Only the
+/// basic arithmetic operations in round to nearest even (`=0`) are run.
+///
+/// ```
+/// use float::test_vectors;
+///
+/// let vectors = "\
+/// b32+ =0 +1.000000P0 +1.000000P0 -> +1.000000P1
+/// b32* =0 +1.7FFFFFP127 +1.000000P1 -> +Inf xo
+/// b64/ =0 -Zero +Inf -> -Zero
+/// b64- =0 +Inf +Inf -> Q i
+/// b64/ =0 +0.0000000000001P-1022 +1.0000000000000P1 -> +Zero xu
+/// b64V =0 +1.0000000000000P2 -> +1.0000000000000P1
+/// b32+ >0 +1.000000P0 +1.000000P-30 -> +1.000001P0 x
+/// ";
+/// let report = test_vectors::run_fpgen(vectors).unwrap();
+/// assert!(report.is_success(), "{}", report);
+/// assert_eq!((report.passed, report.skipped), (5, 2));
+///
+/// // the fields of an operand out of order, or a signed fraction
+/// assert_eq!(test_vectors::run_fpgen("b64+ =0 +P1.0 +1.0P0 -> +1.0P0"), Err(test_vectors::VectorError::Syntax(1)));
+/// assert!(test_vectors::run_fpgen("b32+ =0 +1.+00000P0 +1.000000P0 -> +1.000000P1").is_err());
+/// ```
+pub fn run_fpgen(vectors: &str) -> Result<VectorReport, VectorError> {
+    let mut report = VectorReport::default();
+
+    for (index, line) in vectors.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let (width, operation) = match fields.next() {
+            Some(field) if field.starts_with("b32") => (32, &field[3..]),
+            Some(field) if field.starts_with("b64") => (64, &field[3..]),
+            Some(_) => {
+                report.skipped += 1;
+                continue;
+            },
+            None => return Err(VectorError::Syntax(number)),
+        };
+        let operation = match operation {
+            "+" => Operation::Add,
+            "-" => Operation::Sub,
+            "*" => Operation::Mul,
+            "/" => Operation::Div,
+            _ => {
+                report.skipped += 1;
+                continue;
+            },
+        };
+        match fields.next() {
+            Some("=0") => (),
+            Some(_) => {
+                report.skipped += 1;
+                continue;
+            },
+            None => return Err(VectorError::Syntax(number)),
+        }
+
+        let a = fields.next().and_then(|field| fpgen_bits(field, width));
+        let b = fields.next().and_then(|field| fpgen_bits(field, width));
+        let arrow = fields.next();
+        let expected = fields.next().and_then(|field| fpgen_bits(field, width));
+
+        match (a, b, arrow, expected) {
+            (Some(a), Some(b), Some("->"), Some(expected)) => if width == 32 {
+                let actual = operation.apply(decode::<f32>(a), decode::<f32>(b));
+                let nan = |bits| decode::<f32>(bits).is_nan();
+                report.record(number, nan(expected), expected, actual, nan(actual));
+            } else {
+                let actual = operation.apply(decode::<f64>(a), decode::<f64>(b));
+                let nan = |bits| decode::<f64>(bits).is_nan();
+                report.record(number, nan(expected), expected, actual, nan(actual));
+            },
+            _ => return Err(VectorError::Syntax(number)),
+        }
+    }
+
+    Ok(report)
+}
+
+
+/// Parses an FPgen operand into the bits of a `width` bit float.
+fn fpgen_bits(field: &str, width: u32) -> Option<u64> {
+    let (fraction_bits, exponent_bits) = if width == 32 { (23, 8) } else { (52, 11) };
+    let bias = (1_i64 << (exponent_bits - 1)) - 1;
+    let all_ones = (1_u64 << exponent_bits) - 1;
+
+    let (sign, rest) = match field.as_bytes().first() {
+        Some(&b'+') => (0, &field[1..]),
+        Some(&b'-') => (1_u64 << (width - 1), &field[1..]),
+        _ => (0, field),
+    };
+
+    match rest {
+        "Inf" => return Some(sign | all_ones << fraction_bits),
+        "Zero" | "0" => return Some(sign),
+        "Q" => return Some(sign | all_ones << fraction_bits | 1 << (fraction_bits - 1)),
+        "S" => return Some(sign | all_ones << fraction_bits | 1),
+        _ => (),
+    }
+
+    let point = rest.find('.')?;
+    let p = rest.find('P')?;
+    if point > p {
+        return None;
+    }
+    let lead = &rest[..point];
+    let digits = &rest[point + 1..p];
+    if digits.is_empty() || !digits.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+        return None;
+    }
+    let fraction = u64::from_str_radix(digits, 16).ok()?;
+    let exponent: i64 = rest[p + 1..].parse().ok()?;
+
+    if fraction >> fraction_bits != 0 {
+        return None;
+    }
+
+    match lead {
+        "1" if exponent + bias >= 1 && exponent + bias < all_ones as i64 => {
+            Some(sign | ((exponent + bias) as u64) << fraction_bits | fraction)
+        },
+        "0" if exponent == 1 - bias => Some(sign | fraction),
+        _ => None,
+    }
+}
+
+/// Reinterprets the low bits as a float of the width of `T`.
+#[inline]
+fn decode<T: Float>(bits: u64) -> T {
+    if T::mantissa_digits() == 24 {
+        let x: f32 = Float::from_bits(bits as u32);
+        T::from_f64(x as f64)
+    } else {
+        T::from_f64(Float::from_bits(bits))
+    }
+}
+
+/// Returns the bits of `x` in the width of `T`.
+#[inline]
+fn encode<T: Float>(x: T) -> u64 {
+    if T::mantissa_digits() == 24 {
+        let bits = Float::to_bits(&(x.to_f64() as f32));
+        bits as u64
+    } else {
+        Float::to_bits(&x.to_f64())
+    }
+}
@@ -0,0 +1,130 @@
+use collections::vec::Vec;
+
+use float::Float;
+use probability::{self, ProbabilityError};
+
+
+/// Walker/Vose alias table for O(1) sampling from a discrete distribution
+/// given by float weights.
+///
+/// The weights are normalized with `normalize_probabilities_inplace`, so the
+/// same validation applies and zero weights are never sampled. Randomness is
+/// supplied by the caller as raw bits, which keeps the table independent of
+/// any particular generator.
+///
+/// ```
+/// use float::WeightedAlias;
+///
+/// let table = WeightedAlias::new(&[1.0_f64, 0.0, 3.0]).unwrap();
+/// let mut counts = [0; 3];
+/// let mut state = 0x853c49e6748fea9b_u64;
+///
+/// for _ in 0..4000 {
+///     state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///     counts[table.sample(state)] += 1;
+/// }
+/// assert_eq!(counts[1], 0);
+/// assert!(counts[2] > 2 * counts[0]);
+/// ```
+pub struct WeightedAlias<T> {
+    prob: Vec<T>,
+    alias: Vec<usize>,
+}
+
+impl<T: Float> WeightedAlias<T> {
+    /// Builds the table, failing for the same inputs as
+    /// `normalize_probabilities_inplace`. Panics for more than `2^32`
+    /// weights.
+    pub fn new(weights: &[T]) -> Result<Self, ProbabilityError> {
+        let n = weights.len();
+        let zero = T::from_f64(0.0);
+        let one = T::from_f64(1.0);
+
+        assert!(n as u64 <= 1 << 32, "too many weights for an alias table");
+
+        let mut prob = weights.to_vec();
+        probability::normalize_probabilities_inplace(&mut prob)?;
+
+        let mut fallback = 0;
+        for (index, &p) in prob.iter().enumerate() {
+            if p > prob[fallback] {
+                fallback = index;
+            }
+        }
+
+        let scale = T::from_f64(n as f64);
+        for p in prob.iter_mut() {
+            *p = *p * scale;
+        }
+
+        let mut alias: Vec<usize> = (0..n).collect();
+        let mut small: Vec<usize> = Vec::with_capacity(n);
+        let mut large: Vec<usize> = Vec::with_capacity(n);
+
+        for (index, &p) in prob.iter().enumerate() {
+            if p < one {
+                small.push(index);
+            } else {
+                large.push(index);
+            }
+        }
+
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            large.pop();
+
+            alias[s] = l;
+            prob[l] = (prob[l] + prob[s]) - one;
+
+            if prob[l] < one {
+                small.push(l);
+            } else {
+                large.push(l);
+            }
+        }
+
+        // whatever is left over differs from one only by rounding
+        for &index in large.iter().chain(small.iter()) {
+            if prob[index] > zero {
+                prob[index] = one;
+                alias[index] = index;
+            } else {
+                alias[index] = fallback;
+            }
+        }
+
+        Ok(WeightedAlias {
+            prob: prob,
+            alias: alias,
+        })
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.prob.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.prob.is_empty()
+    }
+
+    /// Picks an index using 64 random bits: the high 32 select a column by
+    /// multiply-shift and the low bits become the coin in `[0, 1)`, using at
+    /// most `mantissa_digits` of them so the conversion is exact.
+    #[inline]
+    pub fn sample(&self, bits: u64) -> usize {
+        let n = self.prob.len() as u64;
+        let column = (((bits >> 32) * n) >> 32) as usize;
+
+        let digits = if T::mantissa_digits() < 32 { T::mantissa_digits() } else { 32 };
+        let coin_bits = (bits & 0xffffffff) >> (32 - digits);
+        let coin = T::from_f64(coin_bits as f64 / (1_u64 << digits) as f64);
+
+        if coin < self.prob[column] {
+            column
+        } else {
+            self.alias[column]
+        }
+    }
+}
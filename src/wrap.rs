@@ -0,0 +1,133 @@
+use float::Float;
+
+
+/// `x` wrapped into the half-open range `[lo, hi)`, as on a periodic domain
+/// where `hi` is the same point as `lo`.
+///
+/// Values already in range come back unchanged, `hi` maps to exactly `lo`,
+/// and the wrapped value is the exact remainder of `x - lo`, so a value one
+/// period outside lands on the same bits as its image inside whenever that
+/// difference is exact. A range or distance wider than the largest finite
+/// value is wrapped in halves. Infinities and NaNs give NaN.
+///
+/// ```
+/// use float::wrap_range;
+///
+/// assert_eq!(wrap_range(370.0_f64, 0.0, 360.0), 10.0);
+/// assert_eq!(wrap_range(-10.0_f64, 0.0, 360.0), 350.0);
+/// assert_eq!(wrap_range(360.0_f64, 0.0, 360.0), 0.0);
+/// assert_eq!(wrap_range(-180.0_f32, -180.0, 180.0), -180.0);
+/// assert_eq!(wrap_range(180.0_f32, -180.0, 180.0), -180.0);
+/// assert_eq!(wrap_range(0.1_f64 + 3.0, 0.0, 1.0), 0.10000000000000009);
+///
+/// // just below lo, where lo + remainder would round up to hi
+/// assert!(wrap_range(-1e-20_f64, 0.0, 1.0) < 1.0);
+/// assert!(wrap_range(f64::INFINITY, 0.0, 1.0).is_nan());
+///
+/// // hi - lo and x - lo overflow
+/// assert_eq!(wrap_range(f64::MAX, -f64::MAX, f64::MAX), -f64::MAX);
+/// let x = wrap_range(f64::MAX, -1e308, 0.0);
+/// assert!((x / ((f64::MAX - 1e308) - 1e308) - 1.0).abs() < 1e-14);
+/// ```
+#[inline]
+pub fn wrap_range<T: Float>(x: T, lo: T, hi: T) -> T {
+    assert!(lo < hi, "wrap_range needs lo < hi");
+    if x >= lo && x < hi {
+        return x;
+    }
+    let width = hi - lo;
+    let difference = x - lo;
+    if overflows(x, lo, hi, width, difference) {
+        let half = T::from_f64(0.5);
+        let wrapped = wrap_range(x * half, lo * half, hi * half);
+        return wrapped + wrapped;
+    }
+    let mut offset = difference % width;
+    if offset < T::from_f64(0.0) {
+        offset = offset + width;
+    }
+    let wrapped = lo + offset;
+    // a remainder within rounding of the width lands on hi, which is lo
+    if wrapped >= hi { lo } else { wrapped }
+}
+
+/// `x` reflected back and forth into the closed range `[lo, hi]`, a
+/// triangle wave that rises from `lo` to `hi` and falls back over two
+/// widths.
+///
+/// Values in range come back unchanged and both bounds are fixed points, so
+/// `hi + d` folds to `hi - d` and `lo - d` to `lo + d`, exactly when those
+/// are representable. A range or distance wider than the largest finite
+/// value is folded in halves. Infinities and NaNs give NaN.
+///
+/// ```
+/// use float::fold_range;
+///
+/// assert_eq!(fold_range(1.25_f64, 0.0, 1.0), 0.75);
+/// assert_eq!(fold_range(-0.25_f64, 0.0, 1.0), 0.25);
+/// assert_eq!(fold_range(2.25_f64, 0.0, 1.0), 0.25);
+/// assert_eq!(fold_range(1.0_f32, 0.0, 1.0), 1.0);
+/// assert_eq!(fold_range(2.0_f32, 0.0, 1.0), 0.0);
+/// assert_eq!(fold_range(-3.0_f32, 0.0, 1.0), 1.0);
+/// assert_eq!(fold_range(7.0_f64, -1.0, 2.0), 1.0);
+///
+/// // twice the width overflows
+/// let x = fold_range(f64::MAX, 0.0, f64::MAX * 0.75);
+/// assert!((x / f64::MAX - 0.5).abs() < 1e-15);
+/// assert!(fold_range(f32::NAN, 0.0, 1.0).is_nan());
+///
+/// // hi - lo and x - lo overflow
+/// let x = fold_range(f64::MAX, -f64::MAX, 1e308);
+/// assert!((x / (1e308 - (f64::MAX - 1e308)) - 1.0).abs() < 1e-14);
+/// assert_eq!(fold_range(-f64::MAX, 0.0, f64::MAX), f64::MAX);
+/// ```
+#[inline]
+pub fn fold_range<T: Float>(x: T, lo: T, hi: T) -> T {
+    assert!(lo < hi, "fold_range needs lo < hi");
+    if x >= lo && x <= hi {
+        return x;
+    }
+    let width = hi - lo;
+    let difference = x - lo;
+    if overflows(x, lo, hi, width, difference) {
+        let half = T::from_f64(0.5);
+        let folded = fold_range(x * half, lo * half, hi * half);
+        return folded + folded;
+    }
+    // twice the width may overflow, the remainder by infinity is a no-op
+    // and the reflection below still applies
+    let mut offset = (difference % (width + width)).abs();
+    if offset > width {
+        // exact, offset is at most twice the width
+        offset = width - (offset - width);
+    }
+    (lo + offset).clamp(&lo, &hi)
+}
+
+/// Whether `hi - lo` or `x - lo` overflowed for finite arguments, which
+/// halving all three brings back into range; doubling the result is exact.
+#[inline]
+fn overflows<T: Float>(x: T, lo: T, hi: T, width: T, difference: T) -> bool {
+    !(width.is_finite() && difference.is_finite()) && x.is_finite() && lo.is_finite() && hi.is_finite()
+}
+
+/// `t` bouncing between `0` and `length`, the usual animation helper:
+/// `fold_range(t, 0, length)`, with a zero length holding at zero.
+///
+/// ```
+/// use float::ping_pong;
+///
+/// let frames: Vec<f32> = (0..7).map(|i| ping_pong(i as f32 * 0.5, 1.0)).collect();
+/// assert_eq!(frames, [0.0, 0.5, 1.0, 0.5, 0.0, 0.5, 1.0]);
+/// assert_eq!(ping_pong(-0.25_f64, 1.0), 0.25);
+/// assert_eq!(ping_pong(3.5_f64, 0.0), 0.0);
+/// ```
+#[inline]
+pub fn ping_pong<T: Float>(t: T, length: T) -> T {
+    assert!(length >= T::from_f64(0.0), "ping_pong needs a length of zero or more");
+    if length == T::from_f64(0.0) {
+        length
+    } else {
+        fold_range(t, T::from_f64(0.0), length)
+    }
+}